@@ -1,9 +1,11 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use serde::{Deserialize, Serialize};
 
-use crate::shared_types::{ApiKey, ApiResponse};
-
-use super::{get_base_url, get_builder};
+use crate::{
+    client::SfsClient,
+    error::SfsError,
+    shared_types::{ApiKey, ApiResponse},
+};
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -11,31 +13,28 @@ pub struct ResData {
     pub access_token: String,
     pub api_key: ApiKey,
 }
-pub async fn login(key: &str) -> anyhow::Result<ResData> {
-    let mut url = get_base_url()?;
+pub async fn login(client: &SfsClient, key: &str) -> anyhow::Result<ResData> {
+    let mut url = client.get_base_url()?;
     url.set_path("auth/gh-cli-login");
     url.query_pairs_mut().append_pair("key", key);
 
-    let res = get_builder(reqwest::Method::GET, url)?.send().await?;
+    let res = client
+        .get_builder(reqwest::Method::GET, url)?
+        .send()
+        .await?;
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!(
-            "{0} ({status})\nResponse: {res_text}",
-            status
-                .canonical_reason()
-                .unwrap_or("Unexpected error occured")
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while logging in!");
     }
 
     let res_data: ApiResponse<ResData> = res.json().await?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => {
-            return Err(anyhow!(
-                "'data' is not present!\nResponse: {}",
-                serde_json::to_string_pretty(&res_data)?
-            ))
-        }
+        None => Err(anyhow!(
+            "'data' is not present!\nResponse: {}",
+            serde_json::to_string_pretty(&res_data)?
+        )),
     }
 }
@@ -1,33 +1,32 @@
 use std::collections::HashMap;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use serde::{Deserialize, Serialize};
 
-use crate::shared_types::{ApiResponse, DirTree, FsFile};
+use crate::{
+    client::SfsClient,
+    error::SfsError,
+    shared_types::{ApiResponse, DirTree, FsFile},
+};
 
-use super::{get_base_url, get_builder};
+use super::retry::{read_body, send_idempotent};
 
 pub struct DirTreeResponse {
     pub dirtree: DirTree,
     pub file_counts: HashMap<String, u32>,
 }
 
-pub async fn get_dirtree() -> anyhow::Result<DirTreeResponse> {
-    let mut url = super::get_base_url()?;
+pub async fn get_dirtree(client: &SfsClient) -> anyhow::Result<DirTreeResponse> {
+    let mut url = client.get_base_url()?;
     url.set_path("fs/tree");
 
-    let res = super::get_builder(reqwest::Method::GET, url)?
-        .send()
-        .await?;
+    let res = send_idempotent(client, client.get_builder(reqwest::Method::GET, url)?).await?;
 
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "{}, Error occured while fetching directory tree!\nResponse: {}",
-            status,
-            res_text
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while fetching directory tree!");
     }
 
     #[derive(Deserialize)]
@@ -44,7 +43,7 @@ pub async fn get_dirtree() -> anyhow::Result<DirTreeResponse> {
         file_counts: Vec<ResDataFileCountObj>,
     }
 
-    let res_data: ApiResponse<ResData> = res.json().await?;
+    let res_data: ApiResponse<ResData> = serde_json::from_slice(&read_body(client, res).await?)?;
     let data = match res_data.data {
         Some(data) => data,
         None => return Err(anyhow::anyhow!("No data found in response!")),
@@ -63,15 +62,16 @@ pub async fn get_dirtree() -> anyhow::Result<DirTreeResponse> {
     })
 }
 
-pub async fn mkdir(dirpath: &str) -> anyhow::Result<DirTree> {
-    let mut url = get_base_url()?;
+pub async fn mkdir(client: &SfsClient, dirpath: &str) -> anyhow::Result<DirTree> {
+    let mut url = client.get_base_url()?;
     url.set_path("fs/mkdir");
 
     #[derive(Serialize)]
     struct ReqBody<'a> {
         path: &'a str,
     }
-    let res = get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .json(&ReqBody { path: dirpath })
         .send()
         .await?;
@@ -80,18 +80,76 @@ pub async fn mkdir(dirpath: &str) -> anyhow::Result<DirTree> {
 
     if !status.is_success() {
         let res_text: String = res.text().await?;
-        return Err(anyhow!(
-            "Error occured while calling 'mkdir' ({}): {}",
-            status,
-            res_text
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while creating directory!");
     }
 
     let res_data: ApiResponse<DirTree> = res.json().await?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => return Err(anyhow!("no data returned!")),
+        None => Err(anyhow!("no data returned!")),
+    }
+}
+
+/// removes the directory along with its files & nested directories, returns the updated tree
+pub async fn rmdir(client: &SfsClient, dirpath: &str) -> anyhow::Result<DirTree> {
+    let mut url = client.get_base_url()?;
+    url.set_path("fs/rmdir");
+
+    #[derive(Serialize)]
+    struct ReqBody<'a> {
+        path: &'a str,
+    }
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
+        .json(&ReqBody { path: dirpath })
+        .send()
+        .await?;
+
+    let status = res.status();
+    if !status.is_success() {
+        let res_text = res.text().await.unwrap_or_default();
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while removing directory!");
+    }
+
+    let res_data: ApiResponse<DirTree> = res.json().await?;
+    res_data.data.ok_or(anyhow!("no data returned!"))
+}
+
+/// moves (or renames) the directory, returns the updated tree
+pub async fn mvdir(
+    client: &SfsClient,
+    dirpath: &str,
+    new_dirpath: &str,
+) -> anyhow::Result<DirTree> {
+    let mut url = client.get_base_url()?;
+    url.set_path("fs/mvdir");
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody<'a> {
+        path: &'a str,
+        new_path: &'a str,
     }
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
+        .json(&ReqBody {
+            path: dirpath,
+            new_path: new_dirpath,
+        })
+        .send()
+        .await?;
+
+    let status = res.status();
+    if !status.is_success() {
+        let res_text = res.text().await.unwrap_or_default();
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while moving directory!");
+    }
+
+    let res_data: ApiResponse<DirTree> = res.json().await?;
+    res_data.data.ok_or(anyhow!("no data returned!"))
 }
 
 #[derive(Serialize)]
@@ -100,11 +158,12 @@ pub struct MvOpts<'a> {
     pub file_path: &'a str,
     pub new_file_path: &'a str,
 }
-pub async fn mv(opts: &MvOpts<'_>) -> anyhow::Result<FsFile> {
-    let mut url = get_base_url()?;
+pub async fn mv(client: &SfsClient, opts: &MvOpts<'_>) -> anyhow::Result<FsFile> {
+    let mut url = client.get_base_url()?;
     url.set_path("/fs/mv");
 
-    let res = super::get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .json(opts)
         .send()
         .await?;
@@ -112,12 +171,13 @@ pub async fn mv(opts: &MvOpts<'_>) -> anyhow::Result<FsFile> {
     let res_status = res.status();
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!("Error occured while moving file!\n{}", res_text));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while moving file!");
     }
 
     let res_data: ApiResponse<FsFile> = res.json().await?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => return Err(anyhow!("Response returned no data!")),
+        None => Err(anyhow!("Response returned no data!")),
     }
 }
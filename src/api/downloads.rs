@@ -0,0 +1,370 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use futures_util::StreamExt;
+use indicatif::{MultiProgress, ProgressBar};
+use orion::aead::streaming::StreamOpener;
+use reqwest::StatusCode;
+use tokio::{
+    fs,
+    io::{self, AsyncReadExt, AsyncWriteExt},
+    time,
+};
+
+use crate::{
+    client::SfsClient,
+    constants,
+    error::SfsError,
+    shared_types::{CompressionCodec, FsFile},
+    utils::{
+        self,
+        checksum::{Checksum, ChecksumReader, ChecksumWriter},
+        crypto::CryptoStream,
+        rate_limit::RateLimiter,
+    },
+};
+
+use super::fs_files::get_file_response;
+
+pub struct DownloadFileOpts {
+    pub download_filepath: PathBuf,
+    /// encrypted files are decrypted with this password once all the bytes are received
+    pub password: Option<String>,
+    /// number of consecutive failed attempts to resume an interrupted download before giving up
+    pub max_retries: u32,
+    /// set the local file's modification time to the remote file's `updated_at`
+    pub preserve_mtime: bool,
+    /// shared with the other transfers of the same command
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub progress_bar: ProgressBar,
+}
+
+impl DownloadFileOpts {
+    pub fn new(
+        download_filepath: PathBuf,
+        password: Option<String>,
+        progress_bar: ProgressBar,
+    ) -> Self {
+        Self {
+            download_filepath,
+            password,
+            max_retries: constants::DOWNLOAD_MAX_RETRIES,
+            preserve_mtime: false,
+            rate_limiter: None,
+            progress_bar,
+        }
+    }
+
+    /// raw bytes are kept in this file until the download completes, the remote file's
+    /// `updated_at` is part of the name so a changed remote file is never resumed into
+    pub fn get_part_filepath(&self, file: &FsFile) -> PathBuf {
+        let mut filename = self
+            .download_filepath
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string();
+        filename.push(format!(
+            ".{}.{}",
+            file.updated_at.timestamp(),
+            constants::PART_FILE_EXT
+        ));
+
+        self.download_filepath.with_file_name(filename)
+    }
+}
+
+/// downloads a remote file to `opts.download_filepath`, picks up from a previously left part
+/// file if one exists and resumes dropped connections with range requests.
+pub async fn download_file(
+    client: &SfsClient,
+    file: &FsFile,
+    token: Option<&str>,
+    opts: DownloadFileOpts,
+) -> anyhow::Result<()> {
+    let part_filepath = opts.get_part_filepath(file);
+    let file_size = file.file_size as u64;
+
+    let mut part_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part_filepath)
+        .await?;
+    let mut offset = part_file.metadata().await?.len();
+    if offset > file_size {
+        part_file.set_len(0).await?;
+        offset = 0;
+    }
+    opts.progress_bar.set_position(offset);
+
+    let mut n_retries = 0;
+    while offset < file_size {
+        let prev_offset = offset;
+        let res = append_file_response(
+            client,
+            &file.storage_id,
+            token,
+            &mut part_file,
+            &mut offset,
+            &opts,
+        )
+        .await;
+
+        if offset > prev_offset {
+            n_retries = 0;
+        }
+        if offset >= file_size {
+            break;
+        }
+
+        // the response ending early is an interrupted connection too, just without an error
+        let err = match res {
+            Ok(_) => SfsError::Network(String::from(
+                "connection closed before all bytes were received",
+            ))
+            .into(),
+            Err(err) => err,
+        };
+        // e.g. the file was deleted or the token expired, resuming won't help
+        if !super::retry::is_transient_error(&err) {
+            return Err(err);
+        }
+        n_retries += 1;
+        if n_retries > opts.max_retries {
+            return Err(err.context(format!(
+                "download interrupted at {}/{} bytes, run the command again to resume.",
+                offset, file_size
+            )));
+        }
+
+        time::sleep(Duration::from_secs(2_u64.pow(n_retries.min(5)))).await;
+    }
+    drop(part_file);
+
+    let checksum = Checksum::default();
+    let enc_metadata = file.encryption.as_ref().filter(|e| e.attempt_decryption);
+    if enc_metadata.is_none() && file.compression.is_none() {
+        let mut reader =
+            ChecksumReader::new(fs::File::open(&part_filepath).await?, checksum.clone());
+        io::copy(&mut reader, &mut io::sink()).await?;
+
+        // a corrupted part file must not be resumed from on the next attempt
+        if let Err(err) = checksum.verify(file.checksum.as_deref()) {
+            _ = fs::remove_file(&part_filepath).await;
+            return Err(err);
+        }
+        fs::rename(&part_filepath, &opts.download_filepath).await?;
+    } else {
+        let decryptor = match enc_metadata {
+            Some(enc_metadata) => {
+                let password =
+                    opts.password
+                        .as_deref()
+                        .ok_or(SfsError::Decryption(String::from(
+                            "remote file is encrypted, a password is required!",
+                        )))?;
+                Some(utils::crypto::new_decryptor(password, enc_metadata)?)
+            }
+            None => None,
+        };
+        let block_size = enc_metadata
+            .map(|e| e.get_sealed_block_size())
+            .unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE) as usize;
+
+        if let Err(err) = decode_file(
+            &part_filepath,
+            &opts.download_filepath,
+            decryptor,
+            block_size,
+            file.compression,
+            &checksum,
+        )
+        .await
+        {
+            _ = fs::remove_file(&opts.download_filepath).await;
+            return Err(err);
+        }
+        fs::remove_file(&part_filepath).await?;
+
+        if let Err(err) = checksum.verify(file.checksum.as_deref()) {
+            _ = fs::remove_file(&opts.download_filepath).await;
+            return Err(err);
+        }
+    }
+
+    if opts.preserve_mtime {
+        std::fs::File::options()
+            .write(true)
+            .open(&opts.download_filepath)?
+            .set_modified(file.updated_at.into())?;
+    }
+
+    opts.progress_bar.finish();
+
+    Ok(())
+}
+
+pub struct DownloadFilesOpts {
+    pub password: Option<String>,
+    pub preserve_mtime: bool,
+    pub max_concurrent: usize,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl DownloadFilesOpts {
+    pub fn new(password: Option<String>) -> Self {
+        Self {
+            password,
+            preserve_mtime: false,
+            max_concurrent: constants::MAX_CONCURRENT_TRANSFERS,
+            rate_limiter: None,
+        }
+    }
+}
+
+/// downloads files concurrently with a progress bar each, labelled by their path relative to
+/// `ref_dirpath`. failed downloads don't stop the others, their errors are returned together.
+pub async fn download_files(
+    client: &SfsClient,
+    downloads: Vec<(FsFile, PathBuf)>,
+    ref_dirpath: &Path,
+    opts: DownloadFilesOpts,
+) -> anyhow::Result<()> {
+    let labels = downloads
+        .iter()
+        .map(|(_, filepath)| {
+            filepath
+                .strip_prefix(ref_dirpath)
+                .unwrap_or(filepath)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+    let padding = labels.iter().fold(0, |acc, label| acc.max(label.len()));
+
+    let multi_progress_bar = MultiProgress::new();
+    let results = futures_util::stream::iter(downloads.into_iter().zip(labels).map(
+        |((file, filepath), label)| {
+            let progress_bar =
+                multi_progress_bar.add(ProgressBar::new(file.file_size as u64).with_style(
+                    utils::misc::get_sized_throughput_progress_style(Some(&format!(
+                        "{0:<1$}",
+                        label, padding
+                    ))),
+                ));
+            let mut file_opts =
+                DownloadFileOpts::new(filepath, opts.password.clone(), progress_bar);
+            file_opts.preserve_mtime = opts.preserve_mtime;
+            file_opts.rate_limiter = opts.rate_limiter.clone();
+
+            async move {
+                download_file(client, &file, None, file_opts)
+                    .await
+                    .map_err(|err| anyhow!("'{}': {err}", label))
+            }
+        },
+    ))
+    .buffer_unordered(opts.max_concurrent.max(1))
+    .collect::<Vec<_>>()
+    .await;
+
+    let mut err_msg = String::new();
+    for res in results {
+        if let Err(err) = res {
+            err_msg += &format!("{err}\n");
+        }
+    }
+    if !err_msg.is_empty() {
+        return Err(anyhow!(err_msg));
+    }
+
+    Ok(())
+}
+
+async fn append_file_response(
+    client: &SfsClient,
+    storage_id: &str,
+    token: Option<&str>,
+    part_file: &mut fs::File,
+    offset: &mut u64,
+    opts: &DownloadFileOpts,
+) -> anyhow::Result<()> {
+    let range_start = match *offset {
+        0 => None,
+        offset => Some(offset),
+    };
+    let (_, res) = get_file_response(client, storage_id, token, range_start).await?;
+
+    if range_start.is_some() && res.status() != StatusCode::PARTIAL_CONTENT {
+        // range was ignored, full content is being sent again
+        part_file.set_len(0).await?;
+        *offset = 0;
+        opts.progress_bar.set_position(0);
+    }
+
+    // the response isn't read any faster than the limiter allows, which throttles the sender
+    let mut stream = super::retry::get_body_stream(client, res);
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if let Some(rate_limiter) = &opts.rate_limiter {
+            rate_limiter.acquire(chunk.len()).await;
+        }
+        part_file.write_all(&chunk).await?;
+
+        *offset += chunk.len() as u64;
+        opts.progress_bar.inc(chunk.len() as u64);
+    }
+    part_file.flush().await?;
+
+    Ok(())
+}
+
+/// decrypts (if `decryptor` is given) and decompresses the raw bytes in `src_filepath` into
+/// `dest_filepath`, `block_size` is the size of one sealed block
+async fn decode_file(
+    src_filepath: &Path,
+    dest_filepath: &Path,
+    mut decryptor: Option<CryptoStream<StreamOpener>>,
+    block_size: usize,
+    compression: Option<CompressionCodec>,
+    checksum: &Checksum,
+) -> anyhow::Result<()> {
+    let mut reader = fs::File::open(src_filepath).await?;
+    let mut writer = utils::streams::decompress_writer(
+        ChecksumWriter::new(fs::File::create(dest_filepath).await?, checksum.clone()),
+        compression,
+    );
+
+    let mut buf: Vec<u8> = vec![0; block_size];
+    loop {
+        let mut b_read = 0;
+        while b_read < buf.len() {
+            let n = reader.read(&mut buf[b_read..]).await?;
+            if n == 0 {
+                break;
+            }
+            b_read += n;
+        }
+        if b_read == 0 {
+            break;
+        }
+
+        match &mut decryptor {
+            Some(decryptor) => {
+                let (block, _) = decryptor.e.open_chunk(&buf[..b_read]).map_err(|_| {
+                    SfsError::Decryption(String::from(
+                        "decryption failed! please check your password.",
+                    ))
+                })?;
+                writer.write_all(&block).await?;
+            }
+            None => writer.write_all(&buf[..b_read]).await?,
+        }
+    }
+    writer.shutdown().await?;
+
+    Ok(())
+}
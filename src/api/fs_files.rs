@@ -1,13 +1,20 @@
+use anyhow::Context;
 use clap::{Args, Parser, ValueEnum};
+use futures_util::{StreamExt, TryStreamExt};
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 use crate::{
+    client::SfsClient,
+    constants,
+    error::SfsError,
     shared_types::{ApiResponse, FsFile},
     utils,
 };
 
+use super::retry;
+
 #[derive(Args)]
 pub struct CliColFilters {
     #[arg(long)]
@@ -30,19 +37,19 @@ impl CliColFilters {
         for filter_str in &self.deleted_at {
             filters.extend(utils::filters::parse_filter_str(
                 FilterCol::DeletedAt,
-                &filter_str,
+                filter_str,
             )?);
         }
         for filter_str in &self.created_at {
             filters.extend(utils::filters::parse_filter_str(
                 FilterCol::CreatedAt,
-                &filter_str,
+                filter_str,
             )?);
         }
         for filter_str in &self.file_size {
             filters.extend(utils::filters::parse_filter_str(
                 FilterCol::FileSize,
-                &filter_str,
+                filter_str,
             )?);
         }
 
@@ -50,7 +57,7 @@ impl CliColFilters {
     }
 }
 
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub enum FilterCol {
     CreatedAt,
@@ -62,7 +69,7 @@ pub enum FilterCol {
     Name,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum FilterOp {
     Gt,
@@ -74,7 +81,9 @@ pub enum FilterOp {
     Like,
 }
 
-#[derive(Serialize, Parser, Debug, ValueEnum, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Serialize, Deserialize, Parser, Debug, ValueEnum, Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
 #[serde(rename_all = "camelCase")]
 pub enum OrderCol {
     DeletedAt,
@@ -82,31 +91,33 @@ pub enum OrderCol {
     FileSize,
 }
 
-#[derive(Serialize, Parser, Debug, ValueEnum, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Serialize, Deserialize, Parser, Debug, ValueEnum, Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
 #[serde(rename_all = "camelCase")]
 pub enum Order {
     Asc,
     Desc,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Filter(pub FilterCol, pub FilterOp, pub serde_json::Value);
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum FilterGroupType {
     And,
     Or,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterGroup {
     pub type_: FilterGroupType,
     pub filters: Vec<Filter>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFilesOpts {
     pub dir_path: String,
@@ -148,41 +159,140 @@ pub struct GetFilesReqBody {
     pub page_size: usize,
 }
 
-pub async fn get_files(opts: Option<GetFilesOpts>) -> anyhow::Result<GetFilesReqBody> {
-    let mut url = super::get_base_url()?;
+pub async fn get_files(
+    client: &SfsClient,
+    opts: Option<GetFilesOpts>,
+) -> anyhow::Result<GetFilesReqBody> {
+    let mut url = client.get_base_url()?;
 
-    url.set_path(&format!("fs/get-files"));
+    url.set_path("fs/get-files");
 
-    let mut req = super::get_builder(reqwest::Method::POST, url)?;
+    let mut req = client.get_builder(reqwest::Method::POST, url)?;
     if let Some(opts) = opts {
         req = req.json(&opts);
     }
-    let res = req.send().await?;
+    // only lists files, safe to repeat despite being a POST
+    let res = retry::send_idempotent(client, req).await?;
 
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Request returned non-ok status code! ({})\nResponse: {}",
-            status,
-            res_text
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while listing files!");
     }
 
-    let res_data: ApiResponse<GetFilesReqBody> = res.json().await?;
+    let res_data: ApiResponse<GetFilesReqBody> =
+        serde_json::from_slice(&retry::read_body(client, res).await?)?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => {
-            return Err(anyhow::anyhow!(
-                "Invalid response!\nMessage: {}\nError: {}",
-                res_data.message,
-                res_data.error.unwrap_or("".to_string())
-            ))
+        None => Err(anyhow::anyhow!(
+            "Invalid response!\nMessage: {}\nError: {}",
+            res_data.message,
+            res_data.error.unwrap_or("".to_string())
+        )),
+    }
+}
+
+/// pages through `get_files` until every file in the directory matching `filters` is fetched
+pub async fn get_all_files(
+    client: &SfsClient,
+    dir_path: &str,
+    filters: Option<Vec<FilterGroup>>,
+) -> anyhow::Result<Vec<FsFile>> {
+    let mut files: Vec<FsFile> = vec![];
+
+    let mut page = 1;
+    loop {
+        let mut opts = GetFilesOpts::new(dir_path.to_string());
+        if let Some(filters) = &filters {
+            opts.filters = Some(filters.clone());
+        }
+        opts.page = Some(page);
+
+        let res = get_files(client, Some(opts)).await?;
+        let n_files = res.files.len();
+        files.extend(res.files);
+
+        if n_files == 0 || n_files < res.page_size || files.len() >= res.count {
+            break;
         }
+        page += 1;
     }
+
+    Ok(files)
+}
+
+/// lists multiple directories concurrently, results are returned in no particular order
+pub async fn get_all_files_in_dirs(
+    client: &SfsClient,
+    dir_paths: Vec<String>,
+) -> anyhow::Result<Vec<(String, Vec<FsFile>)>> {
+    get_filtered_files_in_dirs(client, dir_paths, None).await
+}
+
+/// `get_all_files_in_dirs` with the same filters applied in every directory
+pub async fn get_filtered_files_in_dirs(
+    client: &SfsClient,
+    dir_paths: Vec<String>,
+    filters: Option<Vec<FilterGroup>>,
+) -> anyhow::Result<Vec<(String, Vec<FsFile>)>> {
+    let filters = &filters;
+    futures_util::stream::iter(dir_paths.into_iter().map(|dir_path| async move {
+        let files = get_all_files(client, &dir_path, filters.clone()).await?;
+        anyhow::Ok((dir_path, files))
+    }))
+    .buffer_unordered(constants::MAX_CONCURRENT_LISTINGS)
+    .try_collect()
+    .await
 }
 
-pub async fn get_file(abs_filepath: &str) -> anyhow::Result<Option<FsFile>> {
+/// files whose absolute paths match a remote pattern (see `paths::get_remote_pattern_regex`),
+/// sorted by path. only the directories under the pattern's fixed part are listed.
+pub async fn get_files_from_pattern(
+    client: &SfsClient,
+    pattern: &str,
+) -> anyhow::Result<Vec<(String, FsFile)>> {
+    let regex = utils::paths::get_remote_pattern_regex(pattern)
+        .with_context(|| format!("invalid pattern '{pattern}'!"))?;
+    let prefix = utils::paths::get_remote_pattern_prefix(pattern);
+
+    let dirtree = super::dirtree::get_dirtree(client).await?.dirtree;
+    let subtree = match dirtree.get_sub_tree(prefix) {
+        Some(subtree) => subtree,
+        None => return Ok(vec![]),
+    };
+
+    // without "**" files can only match at the depth of the pattern
+    let depth = pattern.matches('/').count();
+    let dir_paths = subtree
+        .get_dir_paths(prefix.trim_end_matches('/'))
+        .into_iter()
+        .map(|dir_path| match dir_path.is_empty() {
+            true => String::from("/"),
+            false => dir_path,
+        })
+        .filter(|dir_path| {
+            pattern.contains("**")
+                || dir_path.trim_end_matches('/').matches('/').count() + 1 == depth
+        })
+        .collect();
+
+    let mut matches: Vec<(String, FsFile)> = get_all_files_in_dirs(client, dir_paths)
+        .await?
+        .into_iter()
+        .flat_map(|(dir_path, files)| {
+            files
+                .into_iter()
+                .map(move |file| (utils::dirtree::join_paths(&[&dir_path, &file.name]), file))
+        })
+        .filter(|(path, _)| regex.is_match(path))
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(matches)
+}
+
+pub async fn get_file(client: &SfsClient, abs_filepath: &str) -> anyhow::Result<Option<FsFile>> {
     let (dirpath, filename) = utils::dirtree::split_path(abs_filepath);
 
     let filters = vec![Filter(FilterCol::Name, FilterOp::Eq, filename.into())];
@@ -192,33 +302,36 @@ pub async fn get_file(abs_filepath: &str) -> anyhow::Result<Option<FsFile>> {
         filters,
     });
 
-    let mut res_files = get_files(Some(opts)).await?;
+    let mut res_files = get_files(client, Some(opts)).await?;
 
     Ok(res_files.files.pop())
 }
 
+/// `range_start` requests the blob from the given byte offset onwards, callers must check for a
+/// `206 Partial Content` status as servers are free to ignore range requests.
 pub async fn get_file_response(
+    client: &SfsClient,
     storage_id: &str,
     token: Option<&str>,
+    range_start: Option<u64>,
 ) -> anyhow::Result<(FsFile, Response)> {
-    let mut url = super::get_base_url()?;
+    let mut url = client.get_base_url()?;
 
     url.set_path(storage_id);
     if let Some(token) = token {
         url.set_query(Some(&format!("token={}", token)));
     }
 
-    let res = super::get_builder(reqwest::Method::GET, url)?
-        .send()
-        .await?;
+    let mut req = client.get_builder(reqwest::Method::GET, url)?;
+    if let Some(range_start) = range_start {
+        req = req.header(reqwest::header::RANGE, format!("bytes={}-", range_start));
+    }
+    let res = retry::send_idempotent(client, req).await?;
     let res_status = res.status();
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Error occured in request ({})\nResponse: {}",
-            res_status,
-            res_text
-        ));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while fetching file!");
     }
 
     let public_file_metadata: FsFile = match res.headers().get("metadata") {
@@ -229,37 +342,35 @@ pub async fn get_file_response(
     Ok((public_file_metadata, res))
 }
 
-pub async fn get_file_metadata(storage_id: &str, token: Option<&str>) -> anyhow::Result<FsFile> {
-    let mut url = super::get_base_url()?;
+pub async fn get_file_metadata(
+    client: &SfsClient,
+    storage_id: &str,
+    token: Option<&str>,
+) -> anyhow::Result<FsFile> {
+    let mut url = client.get_base_url()?;
 
     url.set_path(&format!("metadata/{}", storage_id));
     if let Some(token) = token {
         url.set_query(Some(&format!("token={}", token)));
     }
 
-    let res = super::get_builder(reqwest::Method::GET, url)?
-        .send()
-        .await?;
+    let res =
+        retry::send_idempotent(client, client.get_builder(reqwest::Method::GET, url)?).await?;
     let res_status = res.status();
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Error occured in request ({})\nResponse: {}",
-            res_status,
-            res_text
-        ));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while fetching file metadata!");
     }
 
     let res_data = res.json::<ApiResponse<FsFile>>().await?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => {
-            return Err(anyhow::anyhow!(
-                "Invalid response!\nMessage: {}\nError: {}",
-                res_data.message,
-                res_data.error.unwrap_or("".to_string())
-            ))
-        }
+        None => Err(anyhow::anyhow!(
+            "Invalid response!\nMessage: {}\nError: {}",
+            res_data.message,
+            res_data.error.unwrap_or("".to_string())
+        )),
     }
 }
 
@@ -271,13 +382,18 @@ pub struct SetMetadata<'a> {
     pub is_public: Option<bool>,
     pub cache_max_age_seconds: Option<u64>,
     pub name: Option<&'a str>,
+    pub checksum: Option<&'a str>,
 }
 
-pub async fn set_file_metadata(metadata: SetMetadata<'_>) -> anyhow::Result<()> {
-    let mut url = super::get_base_url()?;
+pub async fn set_file_metadata(
+    client: &SfsClient,
+    metadata: SetMetadata<'_>,
+) -> anyhow::Result<()> {
+    let mut url = client.get_base_url()?;
     url.set_path("/blob/set-metadata");
 
-    let res = super::get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .json(&metadata)
         .send()
         .await?;
@@ -285,11 +401,8 @@ pub async fn set_file_metadata(metadata: SetMetadata<'_>) -> anyhow::Result<()>
     let res_status = res.status();
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Response returned non-ok status code ({})\n{}",
-            res_status,
-            res_text
-        ));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while setting file metadata!");
     }
 
     Ok(())
@@ -302,11 +415,15 @@ pub struct DeleteFilesReqBody<'a> {
     pub file_names: &'a Vec<String>,
 }
 
-pub async fn delete_files(opts: &DeleteFilesReqBody<'_>) -> anyhow::Result<Vec<FsFile>> {
-    let mut url = super::get_base_url()?;
-    url.set_path(&format!("/blob/delete"));
+pub async fn delete_files(
+    client: &SfsClient,
+    opts: &DeleteFilesReqBody<'_>,
+) -> anyhow::Result<Vec<FsFile>> {
+    let mut url = client.get_base_url()?;
+    url.set_path("/blob/delete");
 
-    let res = super::get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .json(opts)
         .send()
         .await?;
@@ -314,11 +431,8 @@ pub async fn delete_files(opts: &DeleteFilesReqBody<'_>) -> anyhow::Result<Vec<F
     let res_status = res.status();
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Api returned non-ok response ({})\nResponse: {}",
-            res_status,
-            res_text
-        ));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while deleting files!");
     }
 
     let res_data: ApiResponse<Vec<FsFile>> = res.json().await?;
@@ -0,0 +1,175 @@
+use std::{future::Future, time::Duration};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use rand::Rng;
+use reqwest::{header, RequestBuilder, Response, StatusCode};
+use tokio::time;
+
+use crate::{
+    client::{ByteStream, SfsClient},
+    constants,
+    error::SfsError,
+};
+
+/// statuses worth another attempt, the request didn't get through or the server is overloaded
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// failures worth another attempt: the connection failed or stalled, or the server is overloaded.
+/// anything else (e.g. a 403/404, a local io error) would only fail the same way again.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            // a malformed request won't get any better
+            return !err.is_builder();
+        }
+        match cause.downcast_ref::<SfsError>() {
+            Some(SfsError::Network(_)) => true,
+            Some(SfsError::Api { status, .. }) => is_transient_status(*status),
+            _ => false,
+        }
+    })
+}
+
+/// `Retry-After` is either a number of seconds or an http date
+fn get_retry_after(res: &Response) -> Option<Duration> {
+    if !matches!(
+        res.status(),
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+
+    let value = res
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => (DateTime::parse_from_rfc2822(value)
+            .ok()?
+            .with_timezone(&Utc)
+            - Utc::now())
+        .to_std()
+        .unwrap_or_default(),
+    };
+
+    Some(delay.min(Duration::from_secs(constants::RETRY_AFTER_MAX_SECS)))
+}
+
+/// exponential backoff with full jitter, so concurrent transfers don't retry in lockstep
+fn get_backoff_delay(n_retries: u32) -> Duration {
+    let max_delay_ms =
+        (constants::RETRY_BASE_DELAY_MS << n_retries.min(16)).min(constants::RETRY_MAX_DELAY_MS);
+
+    Duration::from_millis(rand::thread_rng().gen_range(0..=max_delay_ms))
+}
+
+/// calls `send` again on network errors and transient statuses, up to the client's
+/// `max_retries`. the last response is returned as is, callers still need to check its status.
+pub async fn send_with_retries<F, Fut>(client: &SfsClient, mut send: F) -> anyhow::Result<Response>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<Response>>,
+{
+    let max_retries = client.get_max_retries();
+
+    let mut n_retries = 0;
+    loop {
+        let res = send().await;
+        if n_retries >= max_retries {
+            return res;
+        }
+
+        let delay = match &res {
+            Ok(res) if !is_transient_status(res.status()) => None,
+            Ok(res) => Some(get_retry_after(res).unwrap_or_else(|| get_backoff_delay(n_retries))),
+            Err(err) if is_transient_error(err) => Some(get_backoff_delay(n_retries)),
+            Err(_) => None,
+        };
+        let delay = match delay {
+            Some(delay) => delay,
+            None => return res,
+        };
+
+        n_retries += 1;
+        time::sleep(delay).await;
+    }
+}
+
+fn get_stalled_error(read_timeout: Duration) -> SfsError {
+    SfsError::Network(format!(
+        "no response from the server in {}s, the connection seems to be stalled!",
+        read_timeout.as_secs_f32()
+    ))
+}
+
+/// fails if the response headers don't arrive within the client's read timeout. the timer runs
+/// while the body is sent too, so it's only for requests with small (or no) bodies.
+async fn send_with_read_timeout(
+    client: &SfsClient,
+    req: RequestBuilder,
+) -> anyhow::Result<Response> {
+    let read_timeout = client.get_read_timeout();
+    match time::timeout(read_timeout, req.send()).await {
+        Ok(res) => Ok(res?),
+        Err(_) => Err(get_stalled_error(read_timeout).into()),
+    }
+}
+
+/// retries the request if it can be cloned, requests with streamed bodies are sent only once.
+/// only meant for requests that are safe to repeat, with small bodies (listings, fetches).
+pub async fn send_idempotent(client: &SfsClient, req: RequestBuilder) -> anyhow::Result<Response> {
+    if req.try_clone().is_none() {
+        return send_with_read_timeout(client, req).await;
+    }
+
+    send_with_retries(client, || {
+        let req = req.try_clone().expect("request was cloneable before");
+        send_with_read_timeout(client, req)
+    })
+    .await
+}
+
+/// next chunk of the response body, waits at most `read_timeout` for it. time spent by the
+/// caller between chunks (e.g. throttled by a rate limiter) doesn't count.
+async fn read_chunk(read_timeout: Duration, res: &mut Response) -> anyhow::Result<Option<Vec<u8>>> {
+    match time::timeout(read_timeout, res.chunk()).await {
+        Ok(chunk) => Ok(chunk?.map(|chunk| chunk.to_vec())),
+        Err(_) => Err(get_stalled_error(read_timeout).into()),
+    }
+}
+
+/// body of the response as a stream, fails if a chunk doesn't arrive within the client's read
+/// timeout instead of hanging on a stalled connection
+pub fn get_body_stream(client: &SfsClient, res: Response) -> ByteStream {
+    let read_timeout = client.get_read_timeout();
+    futures_util::stream::try_unfold(res, move |mut res| async move {
+        Ok(read_chunk(read_timeout, &mut res)
+            .await?
+            .map(|chunk| (chunk, res)))
+    })
+    .boxed()
+}
+
+/// whole body of the response, with the same read timeout as `get_body_stream`
+pub async fn read_body(client: &SfsClient, mut res: Response) -> anyhow::Result<Vec<u8>> {
+    let mut body = vec![];
+    while let Some(chunk) = read_chunk(client.get_read_timeout(), &mut res).await? {
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
@@ -1,14 +1,13 @@
-use anyhow::anyhow;
+use anyhow::Context;
 use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    api::get_sudo_builder,
+    client::SfsClient,
+    error::SfsError,
     shared_types::{ApiResponse, DirTree},
 };
 
-use super::get_base_url;
-
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GenAccessTokenRes {
@@ -17,10 +16,11 @@ pub struct GenAccessTokenRes {
 }
 
 pub async fn generate_access_token(
+    client: &SfsClient,
     acpl: &[String],
     expires_at: &DateTime<Utc>,
 ) -> anyhow::Result<GenAccessTokenRes> {
-    let mut url = super::get_base_url()?;
+    let mut url = client.get_base_url()?;
     url.set_path("access/generate-token");
 
     #[derive(Serialize)]
@@ -30,7 +30,8 @@ pub async fn generate_access_token(
         expires_at: &'a String,
     }
 
-    let res = get_sudo_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_sudo_builder(reqwest::Method::POST, url)?
         .json(&Req {
             acpl,
             expires_at: &expires_at
@@ -40,12 +41,11 @@ pub async fn generate_access_token(
         .send()
         .await?;
 
-    if !res.status().is_success() {
+    let status = res.status();
+    if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow::anyhow!(
-            "Failed to generate access token!\nResponse: {}",
-            res_text
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while generating access token!");
     }
 
     let res_data: ApiResponse<GenAccessTokenRes> = res.json().await?;
@@ -58,8 +58,8 @@ pub async fn generate_access_token(
     }
 }
 
-pub async fn blacklist_token(tokens: &Vec<String>) -> anyhow::Result<()> {
-    let mut url = get_base_url()?;
+pub async fn blacklist_token(client: &SfsClient, tokens: &Vec<String>) -> anyhow::Result<()> {
+    let mut url = client.get_base_url()?;
     url.set_path("/access/blacklist-token");
 
     #[derive(Serialize)]
@@ -67,14 +67,16 @@ pub async fn blacklist_token(tokens: &Vec<String>) -> anyhow::Result<()> {
         tokens: &'a Vec<String>,
     }
 
-    let res = get_sudo_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_sudo_builder(reqwest::Method::POST, url)?
         .json(&ReqBody { tokens })
         .send()
         .await?;
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!("API error occured: {status}\n{res_text}"));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while blacklisting tokens!");
     }
 
     Ok(())
@@ -1,24 +1,38 @@
-use std::{path::PathBuf, pin::Pin, sync::Arc};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
-use anyhow::anyhow;
-use futures_util::Stream;
+use anyhow::{anyhow, Context};
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use indicatif::ProgressBar;
-use orion::aead::streaming;
 use reqwest::multipart;
 use serde::{Deserialize, Serialize};
-use tokio::{fs, sync::mpsc, task};
+use tokio::{fs, io::AsyncRead, task};
 
 use crate::{
+    client::SfsClient,
     constants,
+    error::SfsError,
+    journal::{FileFingerprint, UploadJournal},
     shared_types::{self, ApiResponse, FsFile, UploadBlobMetadata},
-    state::STATE,
-    utils,
+    utils::{
+        self,
+        checksum::{self, Checksum},
+        paths,
+        rate_limit::{limit_stream, RateLimiter},
+    },
 };
 
-use super::{get_base_url, get_builder};
+use super::{
+    fs_files::{set_file_metadata, SetMetadata},
+    retry::send_with_retries,
+};
 
-fn get_blob_upload_url() -> anyhow::Result<url::Url> {
-    let mut url = get_base_url()?;
+fn get_blob_upload_url(client: &SfsClient) -> anyhow::Result<url::Url> {
+    let mut url = client.get_base_url()?;
     url.set_path("/blob/upload");
 
     Ok(url)
@@ -29,11 +43,15 @@ pub struct UploadFileOpts {
     /// file is read and encrypted with this password (encryption: AES-GCM)
     pub password: Option<String>,
     pub is_zip_file: bool,
+    /// continue an interrupted multipart upload of the same file from its local journal
+    pub resume: bool,
     pub file_stream_read_buf_size: u32,
+    /// shared with the other transfers of the same command
+    pub rate_limiter: Option<Arc<RateLimiter>>,
     pub progress_bar: ProgressBar,
 }
 
-impl<'a> UploadFileOpts {
+impl UploadFileOpts {
     pub fn new(
         upload_filepath: PathBuf,
         password: Option<String>,
@@ -43,37 +61,94 @@ impl<'a> UploadFileOpts {
             upload_filepath,
             password,
             is_zip_file: false,
+            resume: false,
             file_stream_read_buf_size: constants::FILE_STREAM_READ_BUF_SIZE,
+            rate_limiter: None,
             progress_bar,
         }
     }
 
     fn new_encryptor(
         &self,
-    ) -> anyhow::Result<Option<utils::crypto::CryptoStream<streaming::StreamSealer>>> {
+    ) -> anyhow::Result<Option<utils::crypto::CryptoStream<utils::crypto::StreamSealer>>> {
         match &self.password {
             Some(p) => Ok(Some(utils::crypto::new_encryptor(p)?)),
             None => Ok(None),
         }
     }
+
+    /// continues the sealing stream of the journaled upload instead of starting a new one
+    fn restore_encryptor(
+        &self,
+        journal: &UploadJournal,
+    ) -> anyhow::Result<Option<utils::crypto::CryptoStream<utils::crypto::StreamSealer>>> {
+        match (&self.password, &journal.encryption) {
+            (Some(p), Some(enc_metadata)) => {
+                let enc = utils::crypto::restore_encryptor(p, enc_metadata)?;
+                if journal.key_digest.as_deref()
+                    != Some(&utils::crypto::get_key_digest(p, &enc.salt)?)
+                {
+                    return Err(anyhow!(
+                        "password does not match the one used for the interrupted upload!"
+                    ));
+                }
+
+                Ok(Some(enc))
+            }
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(anyhow!(
+                "interrupted upload was not encrypted, cannot resume it with a password!"
+            )),
+            (None, Some(_)) => Err(anyhow!(
+                "interrupted upload was encrypted, a password is required to resume it!"
+            )),
+        }
+    }
 }
-/// uploads file in chunks if file is bigger than MIN_MULTIPART_UPLOAD_SIZE
+
+/// uploads file in chunks if file is bigger than MIN_MULTIPART_UPLOAD_SIZE, chunked uploads are
+/// journaled locally and can be resumed with `opts.resume`. the file is hashed before it's
+/// uploaded, its checksum is sent along with the upload metadata.
 pub async fn upload_file(
+    client: &SfsClient,
     mut upload_metadata: UploadBlobMetadata,
     opts: UploadFileOpts,
 ) -> anyhow::Result<FsFile> {
-    let state = STATE.read().unwrap().clone();
+    if client.get_token().is_none() {
+        return Err(SfsError::Auth(String::from(
+            "no token selected! please login or add an access token to use.",
+        ))
+        .into());
+    }
 
-    if state.get_active_token()?.is_none() {
-        return Err(anyhow!(
-            "no token selected! please login or add an access token to use."
-        ));
+    if upload_metadata.checksum.is_none() {
+        let upload_filepath = opts.upload_filepath.clone();
+        upload_metadata.checksum = Some(
+            task::spawn_blocking(move || checksum::get_file_checksum(upload_filepath))
+                .await?
+                .context("error occured while hashing file!")?,
+        );
     }
 
     let upload_file = fs::File::open(opts.upload_filepath.clone()).await?;
-    let enc_res = opts
-        .new_encryptor()
-        .map_err(|_| anyhow!("error occured while creating encryptor!"))?;
+    let file_len = upload_file.metadata().await?.len();
+    // progress counts file bytes read, compressed & sealed sizes differ from the file's size
+    let upload_file = utils::streams::compress_reader(
+        opts.progress_bar.wrap_async_read(upload_file),
+        upload_metadata.compression,
+    );
+    let is_multipart = file_len >= constants::MIN_MULTIPART_UPLOAD_SIZE;
+
+    let journal = match is_multipart && !opts.is_zip_file {
+        true => load_resumable_journal(&upload_metadata, &opts)?,
+        false => None,
+    };
+    let enc_res = match &journal {
+        Some(journal) => opts.restore_encryptor(journal)?,
+        None => opts
+            .new_encryptor()
+            .map_err(|_| anyhow!("error occured while creating encryptor!"))?,
+    };
 
     upload_metadata.encryption = match enc_res.as_ref() {
         Some(s) => match opts.is_zip_file {
@@ -83,41 +158,52 @@ pub async fn upload_file(
         None => None,
     };
 
-    // TODO: implement multi-chunk upload for non-encrypted files
-    //
-    //if file_metadata.len() >= constants::MIN_MULTIPART_UPLOAD_SIZE as u64 {
-    //    let chunk_size = (file_metadata.len() as f32 / constants::N_MULTIPART_UPLOAD_CHUNKS as f32)
-    //        .floor() as u64;
-    //
-    //    let upload = upload_file_in_chunks(chunk_size, enc_res, &upload_metadata, &opts).await?;
-    //    file = upload.file;
-    //}
-
-    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let key_digest = match (&opts.password, &enc_res) {
+        (Some(p), Some(enc)) => Some(utils::crypto::get_key_digest(p, &enc.salt)?),
+        _ => None,
+    };
+    let sealer = enc_res.map(|enc| enc.e);
+
+    let file = if is_multipart {
+        let journal = match journal {
+            Some(journal) => journal,
+            None => {
+                let mut journal = UploadJournal::new(
+                    create_multipart_upload(client, &upload_metadata).await?,
+                    paths::canonicalize(&opts.upload_filepath)?,
+                    get_remote_filepath(&upload_metadata),
+                    FileFingerprint::from_path(&opts.upload_filepath)?,
+                    constants::MULTIPART_UPLOAD_PART_SIZE,
+                );
+                journal.encryption = upload_metadata.encryption.clone();
+                journal.key_digest = key_digest;
+                journal.compression = upload_metadata.compression;
+                journal
+            }
+        };
 
-    let upload_metadata = Arc::new(upload_metadata);
-    let upload_handle = task::spawn(async move {
+        upload_file_in_parts(client, upload_file, sealer, journal, &opts).await?
+    } else {
         upload_blob_stream(
-            utils::streams::read_into_stream(
-                upload_file,
-                opts.file_stream_read_buf_size,
-                enc_res.map(|enc| enc.e),
-                Some(sender),
+            client,
+            limit_stream(
+                utils::streams::read_into_stream(
+                    upload_file,
+                    opts.file_stream_read_buf_size,
+                    sealer,
+                    None,
+                ),
+                opts.rate_limiter.clone(),
             ),
             &upload_metadata,
         )
-        .await
-    });
-
-    while let Some(v) = receiver.recv().await {
-        opts.progress_bar.inc(v as u64);
-    }
-    let file = upload_handle.await??;
+        .await?
+    };
     opts.progress_bar.finish();
 
     if opts.is_zip_file {
         if let Err(err) = fs::remove_file(opts.upload_filepath.clone()).await {
-            println!(
+            eprintln!(
                 "WARNING: failed to delete file '{}'\n{err}",
                 opts.upload_filepath.to_string_lossy()
             );
@@ -127,13 +213,93 @@ pub async fn upload_file(
     Ok(file)
 }
 
+/// records the checksum of a streamed upload (e.g. from stdin) whose contents weren't known
+/// before it started, call once the stream was read till the end
+pub async fn set_file_checksum(
+    client: &SfsClient,
+    file: &mut FsFile,
+    dir_path: &str,
+    checksum: &Checksum,
+) -> anyhow::Result<()> {
+    let digest = checksum.get_digest();
+    let path = utils::dirtree::join_paths(&[dir_path, &file.name]);
+
+    set_file_metadata(
+        client,
+        SetMetadata {
+            path: &path,
+            checksum: Some(&digest),
+            is_public: None,
+            cache_max_age_seconds: None,
+            name: None,
+        },
+    )
+    .await
+    .map_err(|err| anyhow!("error occured while setting file checksum!\n{err}"))?;
+    file.checksum = Some(digest);
+
+    Ok(())
+}
+
+fn get_remote_filepath(upload_metadata: &UploadBlobMetadata) -> String {
+    utils::dirtree::join_paths(&[&upload_metadata.dir_path, &upload_metadata.name])
+}
+
+/// returns the journal of an interrupted upload of this file if it can be continued, unfinished
+/// journals that can't be resumed are left alone for `journal clean` to pick up
+fn load_resumable_journal(
+    upload_metadata: &UploadBlobMetadata,
+    opts: &UploadFileOpts,
+) -> anyhow::Result<Option<UploadJournal>> {
+    let local_filepath = paths::canonicalize(&opts.upload_filepath)?;
+    let remote_filepath = get_remote_filepath(upload_metadata);
+
+    let journal = match UploadJournal::load(&local_filepath, &remote_filepath)? {
+        Some(journal) => journal,
+        None => return Ok(None),
+    };
+    let journal_desc = format!(
+        "{}/{} parts of '{}' were uploaded {}",
+        journal.parts.len(),
+        journal.get_n_parts(),
+        local_filepath.to_string_lossy(),
+        journal
+            .updated_at
+            .with_timezone(&chrono::Local)
+            .format(constants::LOCAL_DATETIME_FORMAT),
+    );
+
+    if !opts.resume {
+        eprintln!(
+            "WARNING: {journal_desc}, use --resume to continue that upload. starting over..."
+        );
+        return Ok(None);
+    }
+    if journal.compression != upload_metadata.compression {
+        eprintln!(
+            "WARNING: {journal_desc}, but with a different compression codec. starting over..."
+        );
+        return Ok(None);
+    }
+    if journal.is_stale() || journal.part_size != constants::MULTIPART_UPLOAD_PART_SIZE {
+        eprintln!(
+            "WARNING: {journal_desc}, but the file changed since or the journal is too old to resume. starting over..."
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(journal))
+}
+
 pub async fn upload_blob_stream(
+    client: &SfsClient,
     stream: Pin<Box<dyn Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static>>,
     upload_metadata: &UploadBlobMetadata,
 ) -> anyhow::Result<FsFile> {
-    let url = get_blob_upload_url()?;
+    let url = get_blob_upload_url(client)?;
 
-    let res = get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .header(
             constants::HEADER_UPLOAD_METADATA,
             serde_json::to_string(upload_metadata)?,
@@ -145,9 +311,8 @@ pub async fn upload_blob_stream(
 
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!(
-            "({status}) error occured while uploading blob!\n{res_text}"
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while uploading blob!");
     }
 
     let res_data: ApiResponse<FsFile> = res.json().await?;
@@ -156,60 +321,212 @@ pub async fn upload_blob_stream(
         .ok_or(anyhow!("received null data from API response!"))
 }
 
-// TODO: needs a rewrite.
-//
-//async fn upload_file_in_chunks(
-//    chunk_size: u64,
-//    encryptor: Option<utils::crypto::Encryptor>,
-//    upload_metadata: &UploadBlobMetadata,
-//    opts: &UploadFileOpts<'_>,
-//) -> anyhow::Result<MultipartUploadResult> {
-//    let upload_id = create_multipart_upload(upload_metadata).await?;
-//    let (enc_stream, header) = match encryptor {
-//        Some((stream, header)) => (Some(stream), Some(header)),
-//        None => (None, None),
-//    };
-//
-//    let handles_iter = (0..constants::N_MULTIPART_UPLOAD_CHUNKS as u64).map(|n| {
-//        let upload_filepath = opts.upload_filepath.clone();
-//        let upload_id = upload_id.clone();
-//
-//        async {
-//            let mut upload_file = fs::File::open(upload_filepath).await?;
-//            upload_file.seek(SeekFrom::Start(n * chunk_size)).await?;
-//
-//            let upload_part_opts = UploadPartOpts {
-//                upload_id: &upload_id,
-//                part_num: n as usize,
-//                stream: read_file_stream(
-//                    upload_file,
-//                    chunk_size,
-//                    FILE_STREAM_READ_CHUNK_SIZE,
-//                    enc_stream,
-//                ),
-//            };
-//            upload_part(upload_part_opts).await
-//        }
-//    });
-//
-//    let mut parts = vec![];
-//    for res_handle in futures_util::future::join_all(handles_iter).await {
-//        let part = res_handle?;
-//        parts.push(part);
-//    }
-//
-//    complete_multipart_upload(&parts).await
-//}
-
-async fn create_multipart_upload(metadata: &UploadBlobMetadata) -> anyhow::Result<String> {
-    let mut url = get_base_url()?;
+/// parts are assembled from whole read blocks so that every part boundary lines up with the
+/// `block_size` of the encryption metadata. blocks are sealed in order, parts are uploaded
+/// concurrently. parts already recorded in the journal are still read & sealed (to keep the
+/// sealer in step) but not uploaded again.
+async fn upload_file_in_parts<R>(
+    client: &SfsClient,
+    upload_file: R,
+    sealer: Option<utils::crypto::StreamSealer>,
+    journal: UploadJournal,
+    opts: &UploadFileOpts,
+) -> anyhow::Result<FsFile>
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    let blocks_per_part =
+        (constants::MULTIPART_UPLOAD_PART_SIZE / opts.file_stream_read_buf_size).max(1);
+
+    let uploaded_part_numbers: HashSet<u32> =
+        journal.parts.iter().map(|part| part.part_number).collect();
+    let upload_id = journal.upload_id.clone();
+    let journal = Mutex::new(journal);
+
+    let mut blocks_stream =
+        utils::streams::read_into_stream(upload_file, opts.file_stream_read_buf_size, sealer, None);
+    let parts_stream = async_stream::try_stream! {
+        let mut part_number = 1;
+        let mut part: Vec<u8> = vec![];
+        let mut n_blocks = 0;
+        while let Some(block) = blocks_stream.next().await {
+            part.extend(block?);
+            n_blocks += 1;
+
+            if n_blocks == blocks_per_part {
+                yield (part_number, std::mem::take(&mut part));
+
+                part_number += 1;
+                n_blocks = 0;
+            }
+        }
+
+        if n_blocks > 0 || part_number == 1 {
+            yield (part_number, part);
+        }
+    };
+
+    parts_stream
+        .try_for_each_concurrent(
+            constants::MAX_CONCURRENT_PART_UPLOADS,
+            |(part_number, data): (u32, Vec<u8>)| {
+                let upload_id = &upload_id;
+                let uploaded_part_numbers = &uploaded_part_numbers;
+                let journal = &journal;
+                async move {
+                    if !uploaded_part_numbers.contains(&part_number) {
+                        let part = upload_part(
+                            client,
+                            &UploadPartOpts {
+                                upload_id,
+                                part_number,
+                                data,
+                                rate_limiter: opts.rate_limiter.clone(),
+                            },
+                        )
+                        .await?;
+
+                        let mut journal = journal.lock().unwrap();
+                        journal.parts.push(part);
+                        if !opts.is_zip_file {
+                            journal.save()?;
+                        }
+                    }
+
+                    anyhow::Ok(())
+                }
+            },
+        )
+        .await?;
+
+    let mut journal = journal.into_inner().unwrap();
+    journal.parts.sort_by_key(|part| part.part_number);
+    let file = complete_multipart_upload(client, &upload_id, &journal.parts)
+        .await?
+        .file;
+    journal.delete()?;
+
+    Ok(file)
+}
+
+/// copies the stored bytes of `file` to `dest_filepath` as they are, its encryption & compression
+/// metadata is copied along so the copy is read with the same password. `src_client` can be of
+/// a different file system than `client`.
+pub async fn copy_file(
+    src_client: &SfsClient,
+    file: &FsFile,
+    client: &SfsClient,
+    dest_filepath: &str,
+    force_write: bool,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    progress_bar: ProgressBar,
+) -> anyhow::Result<FsFile> {
+    let (dir_path, name) = utils::dirtree::split_path(dest_filepath);
+    let upload_metadata = UploadBlobMetadata {
+        name: name.to_string(),
+        content_type: file.content_type.clone(),
+        is_public: file.is_public,
+        encryption: file.encryption.clone(),
+        cache_max_age_seconds: Some(file.cache_max_age_seconds),
+        dir_path: dir_path.to_string(),
+        force_write,
+        deleted_at: None,
+        checksum: file.checksum.clone(),
+        compression: file.compression,
+    };
+
+    let (_, stream) = src_client.download_stream(&file.storage_id, None).await?;
+    let stream = limit_stream(
+        stream.inspect_ok(move |chunk| progress_bar.inc(chunk.len() as u64)),
+        rate_limiter,
+    );
+
+    if (file.file_size as u64) < constants::MIN_MULTIPART_UPLOAD_SIZE {
+        return upload_blob_stream(client, stream, &upload_metadata).await;
+    }
+
+    // parts end on sealed block boundaries, like the parts of `upload_file_in_parts`
+    let block_size = file
+        .encryption
+        .as_ref()
+        .filter(|e| e.attempt_decryption)
+        .map(|e| e.get_sealed_block_size())
+        .unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE) as usize;
+    let part_size =
+        (constants::MULTIPART_UPLOAD_PART_SIZE as usize / block_size).max(1) * block_size;
+
+    let mut stream = stream;
+    let parts_stream = async_stream::try_stream! {
+        let mut part_number = 1;
+        let mut part: Vec<u8> = Vec::with_capacity(part_size);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let mut chunk = chunk.as_slice();
+            while !chunk.is_empty() {
+                let n = (part_size - part.len()).min(chunk.len());
+                part.extend_from_slice(&chunk[..n]);
+                chunk = &chunk[n..];
+
+                if part.len() == part_size {
+                    yield (part_number, std::mem::take(&mut part));
+                    part_number += 1;
+                }
+            }
+        }
+
+        if !part.is_empty() || part_number == 1 {
+            yield (part_number, part);
+        }
+    };
+
+    let upload_id = create_multipart_upload(client, &upload_metadata).await?;
+    let parts = Mutex::new(vec![]);
+    parts_stream
+        .try_for_each_concurrent(
+            constants::MAX_CONCURRENT_PART_UPLOADS,
+            |(part_number, data): (u32, Vec<u8>)| {
+                let upload_id = &upload_id;
+                let parts = &parts;
+                async move {
+                    let part = upload_part(
+                        client,
+                        &UploadPartOpts {
+                            upload_id,
+                            part_number,
+                            data,
+                            rate_limiter: None,
+                        },
+                    )
+                    .await?;
+                    parts.lock().unwrap().push(part);
+
+                    anyhow::Ok(())
+                }
+            },
+        )
+        .await?;
+
+    let mut parts = parts.into_inner().unwrap();
+    parts.sort_by_key(|part| part.part_number);
+
+    Ok(complete_multipart_upload(client, &upload_id, &parts)
+        .await?
+        .file)
+}
+
+async fn create_multipart_upload(
+    client: &SfsClient,
+    metadata: &UploadBlobMetadata,
+) -> anyhow::Result<String> {
+    let mut url = client.get_base_url()?;
     url.set_path("/blob/create-multipart-upload");
 
     let metadata_b = serde_json::to_vec(&metadata)?;
 
     let metadata_part = reqwest::multipart::Part::bytes(metadata_b);
     let form = multipart::Form::new().part("metadata", metadata_part);
-    let res = get_builder(reqwest::Method::POST, url.clone())?
+    let res = client
+        .get_builder(reqwest::Method::POST, url.clone())?
         .multipart(form)
         .send()
         .await?;
@@ -217,10 +534,8 @@ async fn create_multipart_upload(metadata: &UploadBlobMetadata) -> anyhow::Resul
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-
-        return Err(anyhow!(
-            "({status}) error occured while creating multipart upload!\n{res_text}"
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while creating multipart upload!");
     }
 
     #[derive(Deserialize)]
@@ -243,28 +558,40 @@ pub struct UploadPartResult {
 }
 pub struct UploadPartOpts<'a> {
     pub upload_id: &'a str,
-    pub part_num: usize,
-    pub stream: Pin<Box<dyn Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static>>,
+    /// part numbers start at 1
+    pub part_number: u32,
+    pub data: Vec<u8>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
-pub async fn upload_part<'a>(opts: UploadPartOpts<'a>) -> anyhow::Result<UploadPartResult> {
-    let mut url = get_base_url()?;
+/// failed attempts are retried, the part data stays in memory until it's uploaded
+pub async fn upload_part(
+    client: &SfsClient,
+    opts: &UploadPartOpts<'_>,
+) -> anyhow::Result<UploadPartResult> {
+    let mut url = client.get_base_url()?;
     url.set_path("/blob/upload-part");
     url.query_pairs_mut()
         .append_pair("id", opts.upload_id)
-        .append_pair("n", &opts.part_num.to_string());
+        .append_pair("n", &opts.part_number.to_string());
 
-    let res = get_builder(reqwest::Method::POST, url)?
-        .body(reqwest::Body::wrap_stream(opts.stream))
-        .send()
-        .await?;
+    let res = send_with_retries(client, || async {
+        Ok(client
+            .get_builder(reqwest::Method::POST, url.clone())?
+            .body(get_part_body(opts))
+            .send()
+            .await?)
+    })
+    .await?;
 
     let status = res.status();
     if !status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!(
-            "({status}) error occured while uploading part number {}!\n{res_text}",
-            opts.part_num
-        ));
+        return Err(SfsError::from_response(status, &res_text)).with_context(|| {
+            format!(
+                "error occured while uploading part number {}!",
+                opts.part_number
+            )
+        });
     }
 
     let res_data: ApiResponse<UploadPartResult> = res.json().await?;
@@ -273,6 +600,24 @@ pub async fn upload_part<'a>(opts: UploadPartOpts<'a>) -> anyhow::Result<UploadP
         .ok_or(anyhow!("received null data from API response!"))
 }
 
+/// a new body is needed for every attempt, rate limited bodies are streamed
+fn get_part_body(opts: &UploadPartOpts<'_>) -> reqwest::Body {
+    match &opts.rate_limiter {
+        Some(rate_limiter) => {
+            let chunks = opts
+                .data
+                .chunks(constants::RATE_LIMIT_CHUNK_SIZE)
+                .map(|chunk| anyhow::Ok(chunk.to_vec()))
+                .collect::<Vec<_>>();
+            reqwest::Body::wrap_stream(limit_stream(
+                futures_util::stream::iter(chunks),
+                Some(rate_limiter.clone()),
+            ))
+        }
+        None => reqwest::Body::from(opts.data.clone()),
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MultipartUploadResult {
@@ -281,12 +626,16 @@ pub struct MultipartUploadResult {
 }
 
 pub async fn complete_multipart_upload(
-    parts: &Vec<UploadPartResult>,
+    client: &SfsClient,
+    upload_id: &str,
+    parts: &[UploadPartResult],
 ) -> anyhow::Result<MultipartUploadResult> {
-    let mut url = get_base_url()?;
+    let mut url = client.get_base_url()?;
     url.set_path("/blob/complete-multipart-upload");
+    url.query_pairs_mut().append_pair("id", upload_id);
 
-    let res = get_builder(reqwest::Method::POST, url)?
+    let res = client
+        .get_builder(reqwest::Method::POST, url)?
         .json(parts)
         .send()
         .await?;
@@ -295,11 +644,8 @@ pub async fn complete_multipart_upload(
 
     if !status.is_success() {
         let res_text = res.text().await?;
-
-        return Err(anyhow!(
-            "({status}) error occured in multipart upload completion!\n{}",
-            res_text
-        ));
+        return Err(SfsError::from_response(status, &res_text))
+            .context("error occured while completing multipart upload!");
     }
 
     let res_data: ApiResponse<MultipartUploadResult> = res.json().await?;
@@ -1,24 +1,29 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 
-use crate::shared_types::{ApiKeyUsage, ApiResponse};
+use crate::{
+    client::SfsClient,
+    error::SfsError,
+    shared_types::{ApiKeyUsage, ApiResponse},
+};
 
-use super::get_sudo_builder;
+use super::retry::send_idempotent;
 
-pub async fn get_api_usage() -> anyhow::Result<ApiKeyUsage> {
-    let mut url = super::get_base_url()?;
+pub async fn get_api_usage(client: &SfsClient) -> anyhow::Result<ApiKeyUsage> {
+    let mut url = client.get_base_url()?;
     url.set_path("/usage");
 
-    let res = get_sudo_builder(reqwest::Method::GET, url)?.send().await?;
+    let res = send_idempotent(client, client.get_sudo_builder(reqwest::Method::GET, url)?).await?;
     let res_status = res.status();
 
     if !res_status.is_success() {
         let res_text = res.text().await?;
-        return Err(anyhow!("({res_status}): {res_text}"));
+        return Err(SfsError::from_response(res_status, &res_text))
+            .context("error occured while fetching api usage!");
     }
 
     let res_data: ApiResponse<ApiKeyUsage> = res.json().await?;
     match res_data.data {
         Some(data) => Ok(data),
-        None => return Err(anyhow!("Data was returned null!")),
+        None => Err(anyhow!("Data was returned null!")),
     }
 }
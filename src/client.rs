@@ -0,0 +1,249 @@
+use std::{pin::Pin, sync::LazyLock, time::Duration};
+
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use url::Url;
+
+use crate::{
+    api::{
+        self,
+        dirtree::DirTreeResponse,
+        fs_files::{GetFilesOpts, GetFilesReqBody},
+        tokens::GenAccessTokenRes,
+    },
+    config::{CliConfig, CONFIG},
+    constants,
+    error::SfsError,
+    shared_types::{ApiKeyUsage, FsFile, UploadBlobMetadata},
+    state::{ActiveToken, STATE},
+    utils::local_auth::{EnvAuthData, LocalAuthData},
+};
+
+pub type ByteStream = Pin<Box<dyn Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static>>;
+
+/// handle to a SFS file system, all the functions in `api` send their requests through one.
+/// cloning is cheap, clones share the same connection pool.
+#[derive(Clone)]
+pub struct SfsClient {
+    base_url: Url,
+    /// access token sent with every request, can be the owner's or a shared one
+    token: Option<String>,
+    /// base64 encoded `key:secret` of the owner's api key, needed for owner only actions
+    api_creds: Option<String>,
+    max_retries: u32,
+    /// longest wait for a response or for the next chunk of its body, see `api::retry`
+    read_timeout: Duration,
+    http: reqwest::Client,
+}
+
+/// connections of the CLI's clients are pooled in here, clients are built again whenever the
+/// local state (e.g. the active token) could have changed
+static LOCAL_HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    build_http_client(&CONFIG.read().unwrap()).expect("error occured while building http client!")
+});
+
+fn build_http_client(config: &CliConfig) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(config.get_connect_timeout())
+        .pool_idle_timeout(Duration::from_secs(constants::HTTP_POOL_IDLE_TIMEOUT_SECS))
+        .tcp_keepalive(Duration::from_secs(constants::HTTP_TCP_KEEPALIVE_SECS));
+    if let Some(timeout) = config.get_timeout() {
+        builder = builder.timeout(timeout);
+    }
+
+    builder.build()
+}
+
+impl SfsClient {
+    /// `token` is an access token of the file system, requests without one can only read public
+    /// files
+    pub fn new(base_url: &str, token: Option<String>) -> anyhow::Result<Self> {
+        Ok(Self {
+            base_url: Url::parse(base_url)?,
+            token,
+            api_creds: None,
+            max_retries: constants::API_MAX_RETRIES,
+            read_timeout: Duration::from_secs(constants::HTTP_READ_TIMEOUT_SECS),
+            http: build_http_client(&CliConfig::default())?,
+        })
+    }
+
+    /// client for the CLI's config, active token and login. `SFS_TOKEN`, `SFS_API_KEY` &
+    /// `SFS_API_SECRET` are used instead of the login if set.
+    pub fn from_local_state() -> anyhow::Result<Self> {
+        let config = CONFIG.read().unwrap();
+        let state = STATE.read().unwrap();
+
+        let token = state.get_active_token()?.map(|(token, _)| token);
+        let api_key = match EnvAuthData::get() {
+            Some(env_auth) => env_auth.api_key,
+            None => match state.active_token {
+                ActiveToken::RootAccessToken => LocalAuthData::get()?
+                    .map(|auth_data| (auth_data.api_key.key, auth_data.api_key.secret)),
+                ActiveToken::Tag(_) => None,
+            },
+        };
+
+        let mut client = Self {
+            base_url: Url::parse(config.get_base_url())?,
+            token,
+            api_creds: None,
+            max_retries: config.get_max_retries(),
+            read_timeout: config.get_read_timeout(),
+            http: LOCAL_HTTP_CLIENT.clone(),
+        };
+        if let Some((key, secret)) = api_key {
+            client = client.with_api_key(&key, &secret);
+        }
+
+        Ok(client)
+    }
+
+    /// client for a token saved locally with a tag (see `tokens generate --tag`), e.g. of a
+    /// file system shared by someone else. owner only actions aren't available with it.
+    pub fn from_token_tag(tag: &str) -> anyhow::Result<Self> {
+        let config = CONFIG.read().unwrap();
+        let state = STATE.read().unwrap();
+
+        let token = state
+            .tokens
+            .get(tag)
+            .ok_or(SfsError::NotFound(format!("token tag '{tag}' not found!")))?;
+
+        Ok(Self {
+            base_url: Url::parse(config.get_base_url())?,
+            token: Some(token.to_string()),
+            api_creds: None,
+            max_retries: config.get_max_retries(),
+            read_timeout: config.get_read_timeout(),
+            http: LOCAL_HTTP_CLIENT.clone(),
+        })
+    }
+
+    /// owner only actions (tokens, usage) are authorized with the api key
+    pub fn with_api_key(mut self, key: &str, secret: &str) -> Self {
+        self.api_creds = Some(BASE64_STANDARD.encode(format!("{key}:{secret}")));
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// e.g. to set custom timeouts or a proxy
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn get_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn get_read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    /// base url with the access token set in the query
+    pub fn get_base_url(&self) -> anyhow::Result<Url> {
+        let mut url = self.base_url.clone();
+        if let Some(token) = &self.token {
+            url.set_query(Some(format!("token={}", token).as_str()));
+        }
+
+        Ok(url)
+    }
+
+    pub fn get_builder(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+    ) -> anyhow::Result<reqwest::RequestBuilder> {
+        Ok(self.http.request(method, url))
+    }
+
+    pub fn get_sudo_builder(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+    ) -> anyhow::Result<reqwest::RequestBuilder> {
+        let api_creds = self.api_creds.as_ref().ok_or(SfsError::PermissionDenied(String::from(
+            "only FileSystem owners are allowed to perform this action! either login and switch to your access token or ask the owner to perform this action for you."
+        )))?;
+
+        Ok(self
+            .http
+            .request(method, url)
+            .header("Authorization", format!("Bearer {}", api_creds)))
+    }
+
+    pub async fn get_dirtree(&self) -> anyhow::Result<DirTreeResponse> {
+        api::dirtree::get_dirtree(self).await
+    }
+
+    /// one page of files in a directory, see `list_files` for all of them
+    pub async fn get_files(&self, opts: GetFilesOpts) -> anyhow::Result<GetFilesReqBody> {
+        api::fs_files::get_files(self, Some(opts)).await
+    }
+
+    pub async fn list_files(&self, dir_path: &str) -> anyhow::Result<Vec<FsFile>> {
+        api::fs_files::get_all_files(self, dir_path, None).await
+    }
+
+    pub async fn get_file(&self, abs_filepath: &str) -> anyhow::Result<Option<FsFile>> {
+        api::fs_files::get_file(self, abs_filepath).await
+    }
+
+    pub async fn get_file_metadata(&self, storage_id: &str) -> anyhow::Result<FsFile> {
+        api::fs_files::get_file_metadata(self, storage_id, self.get_token()).await
+    }
+
+    /// uploads the stream as is, encryption & compression are up to the caller (see
+    /// `utils::streams::read_into_stream`)
+    pub async fn upload_stream(
+        &self,
+        stream: ByteStream,
+        upload_metadata: &UploadBlobMetadata,
+    ) -> anyhow::Result<FsFile> {
+        api::uploads::upload_blob_stream(self, stream, upload_metadata).await
+    }
+
+    /// raw stored bytes of the file, still encrypted/compressed if it was uploaded that way
+    pub async fn download_stream(
+        &self,
+        storage_id: &str,
+        range_start: Option<u64>,
+    ) -> anyhow::Result<(FsFile, ByteStream)> {
+        let (file, res) =
+            api::fs_files::get_file_response(self, storage_id, self.get_token(), range_start)
+                .await?;
+        Ok((file, api::retry::get_body_stream(self, res)))
+    }
+
+    pub async fn generate_access_token(
+        &self,
+        acpl: &[String],
+        expires_at: &DateTime<Utc>,
+    ) -> anyhow::Result<GenAccessTokenRes> {
+        api::tokens::generate_access_token(self, acpl, expires_at).await
+    }
+
+    pub async fn blacklist_tokens(&self, tokens: &Vec<String>) -> anyhow::Result<()> {
+        api::tokens::blacklist_token(self, tokens).await
+    }
+
+    pub async fn get_api_usage(&self) -> anyhow::Result<ApiKeyUsage> {
+        api::usage::get_api_usage(self).await
+    }
+}
@@ -1,8 +1,16 @@
+use anyhow::Context;
 use clap::{Parser, Subcommand};
 use colored::*;
 use inquire::Text;
 
-use crate::{api, config::CONFIG, shared_types::CliSubCmd, utils::local_auth};
+use sfs_cli::{
+    api,
+    config::CONFIG,
+    constants,
+    utils::{local_auth, prompts},
+};
+
+use crate::cmd::{get_client, CliSubCmd};
 
 #[derive(Parser)]
 pub struct AuthCommand {
@@ -18,11 +26,21 @@ enum Commands {
 }
 
 impl AuthCommand {
-    async fn handle_login(&self) {
-        let config = CONFIG.read().unwrap();
+    async fn handle_login(&self) -> anyhow::Result<()> {
+        prompts::ensure_interactive(
+            "a login code",
+            &format!(
+                "set {} (and {} & {}) instead of logging in",
+                constants::ENV_TOKEN,
+                constants::ENV_API_KEY,
+                constants::ENV_API_SECRET
+            ),
+        )?;
+
+        let gh_login_uri = CONFIG.read().unwrap().get_gh_login_uri();
         println!(
             "Please copy and paste this oauth url in your browser:\n{}\n",
-            config.get_gh_login_uri().blue(),
+            gh_login_uri.blue(),
         );
 
         print!("{}", String::from("Note: ").bright_black());
@@ -39,11 +57,13 @@ impl AuthCommand {
         );
         println!();
 
-        let code = Text::new("Enter login code:").prompt().unwrap();
+        let code = Text::new("Enter login code:")
+            .prompt()
+            .context("error occured while displaying login code prompt!")?;
 
-        let credentials = api::auth::login(&code)
+        let credentials = api::auth::login(&get_client()?, &code)
             .await
-            .expect("Error occured while logging in!");
+            .context("Error occured while logging in!")?;
 
         let local_auth_data = local_auth::LocalAuthData {
             access_token: credentials.access_token,
@@ -51,23 +71,28 @@ impl AuthCommand {
         };
         local_auth_data
             .save()
-            .expect("Error occured while saving auth data in keyring!");
+            .context("Error occured while saving auth data in keyring!")?;
+
+        println!("Successfully logged in!");
 
-        println!("Successfully logged in!")
+        Ok(())
     }
 
-    fn handle_logout(&self) {
-        local_auth::LocalAuthData::delete().expect("Error occured while deleting keyring entry");
+    fn handle_logout(&self) -> anyhow::Result<()> {
+        local_auth::LocalAuthData::delete()
+            .context("Error occured while deleting keyring entry")?;
 
         println!("Successfully logged out!");
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for AuthCommand {
-    async fn run(&self) {
+    async fn run(&self) -> anyhow::Result<()> {
         match &self.commands {
             Commands::Login => self.handle_login().await,
             Commands::Logout => self.handle_logout(),
-        };
+        }
     }
 }
@@ -7,40 +7,53 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use chrono::{DateTime, Duration, Utc};
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueHint};
 use colored::*;
-use futures_util::StreamExt;
+use futures_util::{stream::LocalBoxStream, StreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use inquire::Confirm;
-use orion::{aead::streaming, kdf};
-use serde_json::json;
+use serde::Serialize;
 use tokio::{
     fs,
     io::{self, AsyncWriteExt},
-    sync::mpsc,
+    sync::{mpsc, Semaphore},
     task,
 };
 use url::Url;
 use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
 
-use crate::{
-    api::{self, fs_files::*, uploads::UploadFileOpts},
+use sfs_cli::{
+    api::{
+        self,
+        downloads::{DownloadFileOpts, DownloadFilesOpts},
+        fs_files::*,
+        uploads::UploadFileOpts,
+    },
     constants,
+    error::SfsError,
     shared_types::{
-        self, AccessTokenPermission, CliSubCmd, FsFile, PermissionChar, UploadBlobMetadata,
+        self, AccessTokenPermission, CmdRateLimitParams, CmdTransferParams, FsFile, PermissionChar,
+        UploadBlobMetadata,
     },
     state::STATE,
     utils::{
         self,
-        crypto::derive_key_from_password,
+        checksum::{Checksum, ChecksumReader, ChecksumWriter},
         dirtree,
         files::{self, get_share_url},
-        paths, str2x, tokens,
+        paths, prompts, str2x, tokens,
     },
 };
 
+use crate::cmd::{
+    dirtree::get_pattern_matches,
+    get_client,
+    output::{self, ShareUrlOutput},
+    CliSubCmd,
+};
+
 #[derive(Args)]
 #[group(multiple = true)]
 pub struct CmdUploadParams {
@@ -57,12 +70,8 @@ pub struct CmdUploadParams {
     content_type: Option<String>,
 
     #[arg(long)]
-    /// generate a safely-shareable url containing read-only permissions for the uploaded file. (default expiry: 30 mins)
-    share: bool,
-
-    #[arg(long, value_parser = str2x::str2duration)]
-    /// provide custom expiry time for the share url. (e.g "AAdBBhCCmDDs", "7d30s", "2h30m12s", "30m" [default])
-    share_exp: Option<Duration>,
+    /// compress contents before uploading (and encrypting), files are decompressed by `cat` and downloads
+    compress: Option<shared_types::CompressionCodec>,
 
     #[arg(long, value_parser = str2x::str2duration)]
     /// set the 'max-age' value for CDN cache distribution, defaults to 0 (format: 12d23h34m45s)
@@ -72,7 +81,7 @@ pub struct CmdUploadParams {
     exp_input: shared_types::CmdExpiryParams,
 }
 impl CmdUploadParams {
-    pub fn into_upload_metadata(
+    pub fn get_upload_metadata(
         &self,
         name: String,
         dir_path: String,
@@ -88,36 +97,34 @@ impl CmdUploadParams {
             cache_max_age_seconds: self.get_cache_max_age_seconds(),
             content_type: self.content_type.clone(),
             encryption,
+            checksum: None,
+            compression: self.compress,
         }
     }
 
-    pub fn get_password(&self) -> Option<String> {
+    pub fn get_password(&self) -> anyhow::Result<Option<String>> {
         match var("PASSWORD") {
-            Ok(pwd) => Some(pwd),
+            Ok(pwd) => Ok(Some(pwd)),
             Err(_) => match self.password {
-                true => Some(
-                    dialoguer::Password::new()
-                        .with_prompt("create password (remember this password!)")
-                        .with_confirmation("confirm", "passwords don't match!")
-                        .interact()
-                        .unwrap(),
-                ),
-                false => None,
+                true => {
+                    prompts::ensure_interactive(
+                        "a password",
+                        "set the PASSWORD shell variable instead",
+                    )?;
+
+                    Ok(Some(
+                        dialoguer::Password::new()
+                            .with_prompt("create password (remember this password!)")
+                            .with_confirmation("confirm", "passwords don't match!")
+                            .interact()
+                            .context("error occured while displaying password prompt!")?,
+                    ))
+                }
+                false => Ok(None),
             },
         }
     }
 
-    pub fn is_share(&self) -> bool {
-        self.share || self.share_exp.is_some()
-    }
-
-    // get default now+30mins expiry date or the custom one in `share_exp`
-    pub fn get_share_expiry(&self) -> DateTime<Utc> {
-        self.share_exp
-            .map(|s| Utc::now() + s)
-            .unwrap_or(Utc::now() + Duration::minutes(30))
-    }
-
     pub fn get_deleted_at(&self) -> Option<DateTime<Utc>> {
         match self.exp_input.is_unset() {
             true => None,
@@ -130,8 +137,33 @@ impl CmdUploadParams {
     }
 }
 
+#[derive(Args)]
+#[group(multiple = true)]
+pub struct CmdShareParams {
+    #[arg(long)]
+    /// generate a safely-shareable url containing read-only permissions for the uploaded file. (default expiry: 30 mins)
+    share: bool,
+
+    #[arg(long, value_parser = str2x::str2duration)]
+    /// provide custom expiry time for the share url. (e.g "AAdBBhCCmDDs", "7d30s", "2h30m12s", "30m" [default])
+    share_exp: Option<Duration>,
+}
+impl CmdShareParams {
+    pub fn is_share(&self) -> bool {
+        self.share || self.share_exp.is_some()
+    }
+
+    // get default now+30mins expiry date or the custom one in `share_exp`
+    pub fn get_share_expiry(&self) -> DateTime<Utc> {
+        self.share_exp
+            .map(|s| Utc::now() + s)
+            .unwrap_or(Utc::now() + Duration::minutes(30))
+    }
+}
+
 #[derive(Parser)]
 pub struct UploadBlobCommand {
+    #[arg(value_hint = ValueHint::AnyPath)]
     /// list of path patterns. (default: files are uploaded in a single zip file in case of multiple files covered by the pattern)
     source_path_patterns: Vec<String>,
 
@@ -150,6 +182,9 @@ pub struct UploadBlobCommand {
     #[command(flatten)]
     upload_params: CmdUploadParams,
 
+    #[command(flatten)]
+    share_params: CmdShareParams,
+
     #[arg(long)]
     /// all the files and the directory structures covered under the path pattern will be recursively created in remote dirpath
     recursive: bool,
@@ -157,6 +192,13 @@ pub struct UploadBlobCommand {
     #[arg(long)]
     /// do not display any confirm prompts
     no_confirm: bool,
+
+    #[arg(long)]
+    /// continue interrupted uploads of large files from where they stopped (see `journal ls`)
+    resume: bool,
+
+    #[command(flatten)]
+    transfer_params: CmdTransferParams,
 }
 
 #[derive(Parser)]
@@ -165,61 +207,100 @@ pub struct SelectCommand {
 
     #[command(flatten)]
     upload_params: CmdUploadParams,
+
+    #[command(flatten)]
+    share_params: CmdShareParams,
 }
 
 #[derive(Parser)]
 pub struct CatCommand {
+    /// takes in relative path, absolute path, or a url of the remote file. files matching a
+    /// pattern (e.g. "logs/*.log") are printed one after another
+    location_hint: String,
+}
+
+#[derive(Parser)]
+pub struct GetCommand {
     /// takes in relative path, absolute path, or a url of the remote file
     location_hint: String,
+
+    /// local path to save the file at, can be an existing directory. (default: remote file name in CWD)
+    output: Option<PathBuf>,
+
+    #[arg(long, short)]
+    /// overwrite the local file in case it already exists
+    force: bool,
+
+    #[arg(long, default_value_t = constants::DOWNLOAD_MAX_RETRIES)]
+    /// number of consecutive failed attempts to resume an interrupted download before giving up
+    retries: u32,
+
+    #[command(flatten)]
+    rate_limit_params: CmdRateLimitParams,
 }
 
-impl CliSubCmd for UploadBlobCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+#[derive(Parser)]
+pub struct PullCommand {
+    /// relative or absolute path to the remote directory to download
+    remote_dirpath: String,
 
-        let paths: Vec<(u64, PathBuf)> = self
-            .source_path_patterns
-            .iter()
-            .flat_map(|patt| {
-                paths::get_paths_from_pattern(patt)
-                    .expect("invalid pattern received, could not be resolved to a path!")
-            })
-            .collect();
-        if paths.len() == 0 {
-            println!(
-                "{}",
-                String::from(
-                    "No matching files or directories exist! please check your provided patterns."
-                )
-                .red()
-            );
-            return;
+    /// local directory to recreate the remote directory structure in. (default: CWD)
+    local_dirpath: Option<PathBuf>,
+
+    #[arg(long, short)]
+    /// overwrite local files that already exist, these are skipped otherwise
+    force: bool,
+
+    #[command(flatten)]
+    transfer_params: CmdTransferParams,
+}
+
+impl CliSubCmd for UploadBlobCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let mut paths: Vec<(u64, PathBuf)> = vec![];
+        for patt in &self.source_path_patterns {
+            paths.extend(paths::get_paths_from_pattern(patt).map_err(|err| {
+                SfsError::Usage(format!(
+                    "invalid pattern '{patt}', could not be resolved to a path! {err}"
+                ))
+            })?);
+        }
+        if paths.is_empty() {
+            return Err(SfsError::NotFound(String::from(
+                "No matching files or directories exist! please check your provided patterns.",
+            ))
+            .into());
         }
         let only_paths = paths.iter().map(|(_, p)| p);
 
-        println!(
-            "{}",
-            format!("files covered by pattern: {}", paths.len()).bold()
-        );
-
         let upload_dirpath = match &self.dirpath {
             Some(dirpath) => dirtree::get_absolute_path(dirpath, wd),
             None => wd.to_string(),
         }
         .to_string();
 
-        let (ref_wd, pretty_paths) =
-            paths::get_pretty_paths(paths.iter().map(|(size, p)| (*size, p)));
+        if output::is_text() {
+            println!(
+                "{}",
+                format!("files covered by pattern: {}", paths.len()).bold()
+            );
+
+            let (ref_wd, pretty_paths) =
+                paths::get_pretty_paths(paths.iter().map(|(size, p)| (*size, p)));
 
-        println!();
-        if ref_wd.len() > 0 {
-            println!("{}", ref_wd.bold().dimmed());
+            println!();
+            if !ref_wd.is_empty() {
+                println!("{}", ref_wd.bold().dimmed());
+            }
+            println!("{}", pretty_paths.dimmed().blue());
+            println!();
         }
-        println!("{}", pretty_paths.dimmed().blue());
-        println!();
 
         if !self.no_confirm {
+            prompts::ensure_interactive("a confirmation", "use --no-confirm to skip it")?;
+
             let confirm = Confirm::new(&format!(
                 "{} files will be uploaded at {}, confirm:",
                 paths.len(),
@@ -227,15 +308,15 @@ impl CliSubCmd for UploadBlobCommand {
             ))
             .with_default(false)
             .prompt()
-            .expect("error occured while displaying confirm prompt!");
+            .context("error occured while displaying confirm prompt!")?;
 
             if !confirm {
                 println!("Aborted upload.");
-                return;
+                return Ok(());
             }
         }
 
-        let password = self.upload_params.get_password();
+        let password = self.upload_params.get_password()?;
 
         if self.recursive {
             let ref_wd = Self::get_ref_wd_from_paths(only_paths.clone());
@@ -244,37 +325,44 @@ impl CliSubCmd for UploadBlobCommand {
             spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
             Self::create_dirtree_from_filepaths(wd, ref_wd, only_paths.clone())
                 .await
-                .unwrap();
+                .context("error occured while creating directory tree!")?;
             spinner.finish_and_clear();
 
-            self.upload_files(wd, ref_wd, only_paths.clone())
+            let fs_files = self
+                .upload_files(wd, ref_wd, only_paths.clone(), password)
                 .await
-                .expect("files upload unsuccessful!");
+                .context("files upload unsuccessful!")?;
+
+            if !output::is_text() {
+                return output::print_list(fs_files);
+            }
 
             println!("\n{}", "files uploaded successfully.".bold());
-            return;
+            return Ok(());
         }
 
         let is_zip_file = paths.len() > 1;
 
         let upload_filepath = if is_zip_file {
-            &self.get_zipfile_from_paths(only_paths, password.as_deref())
+            &self.get_zipfile_from_paths(only_paths, password.as_deref())?
         } else {
             &paths[0].1
         };
         let filename = self.name.as_deref().unwrap_or(
             upload_filepath
                 .file_name()
-                .expect(&format!(
-                    "invalid path provided '{}'",
-                    upload_filepath.to_string_lossy()
-                ))
+                .with_context(|| {
+                    format!(
+                        "invalid path provided '{}'",
+                        upload_filepath.to_string_lossy()
+                    )
+                })?
                 .to_str()
-                .expect("invalid upload filepath! non-utf8 string provided."),
+                .context("invalid upload filepath! non-utf8 string provided.")?,
         );
         let file_len = fs::metadata(upload_filepath)
             .await
-            .expect("error occured while reading file! metadata could not be read.")
+            .context("error occured while reading file! metadata could not be read.")?
             .len();
 
         let mut opts = UploadFileOpts::new(
@@ -284,8 +372,11 @@ impl CliSubCmd for UploadBlobCommand {
                 .with_style(utils::misc::get_sized_throughput_progress_style(None)),
         );
         opts.is_zip_file = is_zip_file;
+        opts.resume = self.resume;
+        opts.rate_limiter = self.transfer_params.get_rate_limiter();
         let fs_file = api::uploads::upload_file(
-            self.upload_params.into_upload_metadata(
+            &get_client()?,
+            self.upload_params.get_upload_metadata(
                 filename.to_string(),
                 upload_dirpath.clone(),
                 self.force,
@@ -294,43 +385,64 @@ impl CliSubCmd for UploadBlobCommand {
             opts,
         )
         .await
-        .expect("error occured while uploading file!");
+        .context("error occured while uploading file!")?;
 
-        if self.upload_params.is_share() {
+        let upload_path = dirtree::join_paths(&[&upload_dirpath, &fs_file.name]);
+
+        let mut share: Option<ShareUrlOutput> = None;
+        if self.share_params.is_share() {
             let share_url: String;
+            let mut access_token: Option<String> = None;
 
             if self.upload_params.public {
                 share_url = files::get_share_url(None, &fs_file.storage_id)
-                    .expect("error occured while generating share url!")
+                    .context("error occured while generating share url!")?
                     .to_string();
             } else {
                 let acpl: Vec<String> = vec![tokens::get_acp(
                     AccessTokenPermission::from_str(&PermissionChar::Read.to_string())
-                        .expect("Error occured while parsing read access token permission"),
+                        .context("Error occured while parsing read access token permission")?,
                     &format!("{}/{}", upload_dirpath, fs_file.name),
                 )];
-                let expires_at = self.upload_params.get_share_expiry();
+                let expires_at = self.share_params.get_share_expiry();
 
-                let res_data = api::tokens::generate_access_token(&acpl, &expires_at)
-                    .await
-                    .expect("error occured while generating access token!");
+                let res_data =
+                    api::tokens::generate_access_token(&get_client()?, &acpl, &expires_at)
+                        .await
+                        .context("error occured while generating access token!")?;
 
                 share_url = files::get_share_url(Some(&res_data.access_token), &fs_file.storage_id)
-                    .expect("error occured while generating share url!")
+                    .context("error occured while generating share url!")?
                     .to_string();
+                access_token = Some(res_data.access_token);
             }
 
-            println!("\n{}\n", share_url.blue());
+            share = Some(ShareUrlOutput::new(share_url, access_token.as_deref())?);
         }
 
-        println!(
-            "{}",
-            format!(
-                "Upload full path: {}",
-                dirtree::join_paths(&[&upload_dirpath, &fs_file.name]),
-            )
-            .dimmed()
-        );
+        if !output::is_text() {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct UploadOutput {
+                path: String,
+                file: FsFile,
+                share: Option<ShareUrlOutput>,
+            }
+
+            return output::print_value(&UploadOutput {
+                path: upload_path,
+                file: fs_file,
+                share,
+            });
+        }
+
+        if let Some(share) = share {
+            println!("\n{}\n", share.url.blue());
+        }
+
+        println!("{}", format!("Upload full path: {}", upload_path).dimmed());
+
+        Ok(())
     }
 }
 
@@ -350,24 +462,24 @@ impl UploadBlobCommand {
         let mkdir_paths = filepaths
             .map(|filepath| {
                 let dirpath = filepath.parent().unwrap_or(Path::new(""));
-                let path = dirpath.to_str().unwrap().trim_start_matches(ref_wd);
-                utils::dirtree::join_paths(&[wd, path])
+                let dirpath = dirpath.to_string_lossy();
+                utils::dirtree::join_paths(&[wd, dirpath.trim_start_matches(ref_wd)])
             })
             .collect::<HashSet<String>>();
+        let client = get_client()?;
         for res in futures_util::future::join_all(
             mkdir_paths
                 .iter()
-                .map(|abs_path| api::dirtree::mkdir(&abs_path)),
+                .map(|abs_path| api::dirtree::mkdir(&client, abs_path)),
         )
         .await
         {
-            match res {
-                Err(err) => err_msg += &format!("{err}\n"),
-                Ok(_) => {}
+            if let Err(err) = res {
+                err_msg += &format!("{err}\n")
             }
         }
 
-        if err_msg.len() > 0 {
+        if !err_msg.is_empty() {
             return Err(anyhow!(err_msg));
         }
 
@@ -380,7 +492,8 @@ impl UploadBlobCommand {
         wd: &'a str,
         ref_wd: &'a str,
         filepaths: I,
-    ) -> anyhow::Result<()>
+        password: Option<String>,
+    ) -> anyhow::Result<Vec<FsFile>>
     where
         I: ExactSizeIterator<Item = &'a PathBuf> + Clone,
     {
@@ -405,16 +518,25 @@ impl UploadBlobCommand {
         let progress_padded_labels =
             progress_padded_labels.map(|label| format!("{0:<1$}", label, padding));
 
+        let semaphore = Arc::new(Semaphore::new(self.transfer_params.get_jobs()));
+        let rate_limiter = self.transfer_params.get_rate_limiter();
+
         let mut upload_handles = vec![];
         for (filepath, progress_padded_label) in filepaths.clone().zip(progress_padded_labels) {
             let filepath = filepath.clone();
             let ref_wd = ref_wd.clone();
             let wd = wd.clone();
-            let pwd = self.upload_params.get_password();
+            let pwd = password.clone();
             let force_write = self.force;
+            let resume = self.resume;
+            let compression = self.upload_params.compress;
             let multi_progress_bar = multi_progress_bar.clone();
+            let semaphore = semaphore.clone();
+            let rate_limiter = rate_limiter.clone();
 
             let handle = task::spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+
                 let mut path_segs: Vec<&str> = match filepath.to_str() {
                     Some(p) => p.trim_start_matches(ref_wd.as_ref()),
                     None => {
@@ -446,7 +568,12 @@ impl UploadBlobCommand {
                         &progress_padded_label,
                     )));
                 multi_progress_bar.add(progress_bar.clone());
-                Ok(api::uploads::upload_file(
+
+                let mut opts = UploadFileOpts::new(filepath.clone(), pwd, progress_bar);
+                opts.resume = resume;
+                opts.rate_limiter = rate_limiter;
+                api::uploads::upload_file(
+                    &get_client()?,
                     UploadBlobMetadata {
                         name: filename.to_string(),
                         content_type: None,
@@ -456,32 +583,38 @@ impl UploadBlobCommand {
                         encryption: None,
                         cache_max_age_seconds: Some(0),
                         deleted_at: None,
+                        checksum: None,
+                        compression,
                     },
-                    UploadFileOpts::new(filepath.clone(), pwd, progress_bar),
+                    opts,
                 )
-                .await?)
+                .await
             });
 
             upload_handles.push(handle);
         }
 
         let results = futures_util::future::join_all(upload_handles).await;
-        let results = results.iter().map(|r| r.as_ref().unwrap().as_ref());
+        let results = results.into_iter().map(|r| {
+            r.context("upload task failed to complete!")
+                .and_then(|res| res)
+        });
 
+        let mut fs_files = vec![];
         let mut err_msg = String::from("");
         for res in results {
             match res {
                 Err(err) => {
                     err_msg += &format!("{err}\n");
                 }
-                Ok(_) => {}
+                Ok(fs_file) => fs_files.push(fs_file),
             }
         }
-        if err_msg.len() > 0 {
+        if !err_msg.is_empty() {
             return Err(anyhow!("WARNING: some files failed to upload!\n{err_msg}"));
         }
 
-        Ok(())
+        Ok(fs_files)
     }
 
     fn get_ref_wd_from_paths<'a, I>(paths: I) -> &'a str
@@ -492,7 +625,7 @@ impl UploadBlobCommand {
 
         let mut ref_wd = paths.next().map(|p| p.to_str().unwrap_or("")).unwrap_or("");
         for path in paths {
-            let path_str = path.to_str().unwrap();
+            let path_str = path.to_str().unwrap_or("");
 
             let mut ref_wd_split = ref_wd.split(MAIN_SEPARATOR);
             let mut path_str_split = path_str.split(MAIN_SEPARATOR);
@@ -509,9 +642,7 @@ impl UploadBlobCommand {
 
                 common_i += ref_seg.unwrap().len() + 1;
             }
-            if common_i > 0 {
-                common_i -= 1
-            };
+            common_i = common_i.saturating_sub(1);
 
             ref_wd = &ref_wd[0..common_i];
         }
@@ -519,21 +650,25 @@ impl UploadBlobCommand {
         ref_wd
     }
 
-    fn get_zipfile_from_paths<'a, I>(&self, paths: I, password: Option<&str>) -> PathBuf
+    fn get_zipfile_from_paths<'a, I>(
+        &self,
+        paths: I,
+        password: Option<&str>,
+    ) -> anyhow::Result<PathBuf>
     where
         I: ExactSizeIterator<Item = &'a PathBuf> + Clone,
     {
         let paths = paths.into_iter();
 
-        let mut output_filepath = current_dir().expect("cannot find os CWD!");
+        let mut output_filepath = current_dir().context("cannot find os CWD!")?;
 
         output_filepath.push(format!(
             "tmp_upload__{}.zip",
-            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string()
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
         ));
 
         let zipfile = std::fs::File::create(&output_filepath)
-            .expect("error creating an upload zip file in CWD");
+            .context("error creating an upload zip file in CWD")?;
         let mut zipwriter = ZipWriter::new(zipfile);
 
         let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Zstd);
@@ -541,250 +676,466 @@ impl UploadBlobCommand {
             options = options.with_aes_encryption(zip::AesMode::Aes256, password);
         }
 
-        println!("{}", String::from("Unraveling file patterns...").bold());
+        if output::is_text() {
+            println!("{}", String::from("Unraveling file patterns...").bold());
+        }
         let ref_wd = Self::get_ref_wd_from_paths(paths.clone());
 
-        println!("{}", String::from("Compressing files...").bold());
+        if output::is_text() {
+            println!("{}", String::from("Compressing files...").bold());
+        }
         for path in paths.clone() {
-            let path_str = path.to_str().unwrap();
-            let path_str = path_str
+            let path_str = path
+                .to_str()
+                .with_context(|| {
+                    format!(
+                        "path '{}' is not a valid utf8 string!",
+                        path.to_string_lossy()
+                    )
+                })?
                 .strip_prefix(ref_wd)
-                .expect("ref working directory is not working! author skill issues...");
+                .with_context(|| {
+                    format!(
+                        "path '{}' is outside of the common directory '{ref_wd}'!",
+                        path.to_string_lossy()
+                    )
+                })?;
 
             if path.is_dir() {
                 zipwriter
                     .add_directory(path_str, options)
-                    .expect("error occured while creating directory in zipfile!");
+                    .context("error occured while creating directory in zipfile!")?;
             } else {
                 zipwriter
                     .start_file(path_str, options)
-                    .expect("error occured while starting file write in zipfile");
+                    .context("error occured while starting file write in zipfile")?;
 
-                let mut f = std::fs::File::open(&path).expect(&format!(
-                    "error opening file '{}' for copy!",
-                    path.to_string_lossy()
-                ));
-                std::io::copy(&mut f, &mut zipwriter).expect("error copying file into zipfile!");
+                let mut f = std::fs::File::open(path).with_context(|| {
+                    format!("error opening file '{}' for copy!", path.to_string_lossy())
+                })?;
+                std::io::copy(&mut f, &mut zipwriter)
+                    .context("error copying file into zipfile!")?;
             }
         }
+        zipwriter
+            .finish()
+            .context("error occured while finishing the zipfile!")?;
 
-        output_filepath
+        Ok(output_filepath)
     }
 }
 
-impl CliSubCmd for CatCommand {
-    async fn run(&self) {
+/// remote file resolved from a location hint (a path or a share url)
+struct RemoteFile {
+    storage_id: String,
+    access_token: Option<String>,
+    file: FsFile,
+}
+
+/// takes in relative path, absolute path, or a url of the remote file
+async fn get_remote_file(location_hint: &str) -> anyhow::Result<RemoteFile> {
+    if let Ok(url) = Url::parse(location_hint) {
+        let storage_id = url.path().trim_matches('/').to_string();
+        let access_token = url
+            .query_pairs()
+            .find(|(q, _)| q == "token")
+            .map(|(_, token)| token.to_string());
+
+        let file = get_file_metadata(&get_client()?, &storage_id, access_token.as_deref()).await?;
+
+        return Ok(RemoteFile {
+            storage_id,
+            access_token,
+            file,
+        });
+    }
+
+    let (wd, active_token) = {
         let state = STATE.read().unwrap();
-        let wd = state.get_wd();
-
-        let (url, file): (Url, Option<FsFile>) = match Url::parse(&self.location_hint) {
-            Ok(url) => (url, None),
-            Err(_err) => {
-                let active_token = state
-                    .get_active_token()
-                    .expect("provided access token seems invalid!")
-                    .expect("access token not found! please ensure you're logged in or have added an access token.");
-
-                let abs_path = dirtree::get_absolute_path(&self.location_hint, wd);
-                let (dirpath, filename) = dirtree::split_path(&abs_path);
-
-                let filters = FilterGroup {
-                    type_: FilterGroupType::And,
-                    filters: vec![Filter(FilterCol::Name, FilterOp::Eq, json!(filename))],
-                };
-                let mut opts = GetFilesOpts::new(dirpath.to_string());
-                opts.filters = Some(vec![filters]);
-
-                let mut res_files = api::fs_files::get_files(Some(opts))
-                    .await
-                    .expect("error occured while fetching file from given path!");
-
-                let file = res_files
-                    .files
-                    .pop()
-                    .expect("no file found in the given path!");
-
-                match get_share_url(Some(&active_token.0), &file.storage_id) {
-                    Ok(url) => (url, Some(file)),
-                    Err(_) => {
-                        eprintln!("unexpected error occured while generating url!");
-
-                        eprintln!("access token has been generated:");
-                        eprintln!("{}", active_token.0.bold().cyan());
-                        eprintln!();
-                        eprintln!(
-                            "{}",
-                            format!(
-                                "expires_at: {}",
-                                active_token
-                                    .1
-                                    .expires_at
-                                    .format(constants::LOCAL_DATETIME_FORMAT)
-                                    .to_string()
-                                    .magenta()
-                            )
-                            .dimmed()
-                        );
-                        eprintln!(
-                            "{}",
-                            format!("acpl: {}", active_token.1.acpl.join(", ").blue()).dimmed()
-                        );
-                        return;
-                    }
-                }
+        (state.get_wd().to_string(), state.get_active_token()?)
+    };
+    let (access_token, _) = active_token.ok_or(SfsError::Auth(String::from(
+        "access token not found! please ensure you're logged in or have added an access token.",
+    )))?;
+
+    let abs_path = dirtree::get_absolute_path(location_hint, &wd);
+    let file = get_file(&get_client()?, &abs_path)
+        .await?
+        .ok_or(SfsError::NotFound(format!(
+            "no file found at path '{}'!",
+            abs_path
+        )))?;
+
+    Ok(RemoteFile {
+        storage_id: file.storage_id.clone(),
+        access_token: Some(access_token),
+        file,
+    })
+}
+
+impl CliSubCmd for CatCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let remote_files = match Url::parse(&self.location_hint).is_err()
+            && paths::is_pattern(&self.location_hint)
+        {
+            true => {
+                let wd = STATE.read().unwrap().get_wd().to_string();
+                let pattern = dirtree::get_absolute_path(&self.location_hint, &wd);
+
+                get_pattern_matches(&get_client()?, &pattern)
+                    .await?
+                    .into_iter()
+                    .map(|(_, file)| RemoteFile {
+                        storage_id: file.storage_id.clone(),
+                        access_token: None,
+                        file,
+                    })
+                    .collect()
             }
+            false => vec![get_remote_file(&self.location_hint)
+                .await
+                .context("error occured while fetching remote file!")?],
         };
 
-        let storage_id = url.path().trim_matches('/');
-        let access_token = match url.query_pairs().find(|(q, _)| q.to_string() == "token") {
-            Some((_, token)) => Some(token.to_string()),
-            None => None,
-        };
+        // asked once for all the matched files
+        let password = remote_files
+            .iter()
+            .any(|remote_file| {
+                remote_file
+                    .file
+                    .encryption
+                    .as_ref()
+                    .is_some_and(|e| e.attempt_decryption)
+            })
+            .then(utils::crypto::get_decryption_password)
+            .transpose()
+            .context("error occured while reading password!")?;
 
-        let metadata = match file {
-            Some(file) => file,
-            None => get_file_metadata(storage_id, access_token.as_deref())
-                .await
-                .expect("error occured while fetching metadata for file!"),
-        };
+        for remote_file in remote_files {
+            cat_file(remote_file, password.as_deref()).await?;
+        }
 
-        let mut decryptor: Option<utils::crypto::CryptoStream<streaming::StreamOpener>> = None;
-        match &metadata.encryption {
-            Some(enc_metadata) => {
-                if enc_metadata.attempt_decryption {
-                    let password = var("PASSWORD").unwrap_or_else(|_| {
-                        dialoguer::Password::new()
-                            .with_prompt("File is encrypted, please enter a password:")
-                            .interact()
-                            .unwrap()
-                    });
-
-                    let salt = kdf::Salt::from_slice(
-                        enc_metadata
-                            .salt
-                            .as_ref()
-                            .expect("encryption metadata field missing: 'salt'"),
-                    )
-                    .expect("invalid encryption metadata! password salt is not valid.");
-                    let key = derive_key_from_password(password.as_bytes(), &salt)
-                        .expect("error occured while deriving key!");
-                    let nonce = streaming::Nonce::from_slice(
-                        &enc_metadata
-                            .nonce
-                            .as_ref()
-                            .expect("encryption metadata field missing: 'header'"),
-                    )
-                    .expect(
-                        "error occured while parsing encryption metadata! received invalid header.",
-                    );
-                    let e = streaming::StreamOpener::new(&key, &nonce)
-                        .expect("error occured while initializing pull encrypted stream!");
+        Ok(())
+    }
+}
 
-                    decryptor = Some(utils::crypto::CryptoStream { e, nonce, salt })
+/// writes the decrypted & decompressed contents of the file to stdout
+async fn cat_file(remote_file: RemoteFile, password: Option<&str>) -> anyhow::Result<()> {
+    let RemoteFile {
+        storage_id,
+        access_token,
+        file: metadata,
+    } = remote_file;
+
+    let mut decryptor = match (
+        metadata
+            .encryption
+            .as_ref()
+            .filter(|e| e.attempt_decryption),
+        password,
+    ) {
+        (Some(enc_metadata), Some(password)) => Some(
+            utils::crypto::new_decryptor(password, enc_metadata)
+                .context("error occured while initializing decryptor!")?,
+        ),
+        (Some(_), None) => {
+            return Err(SfsError::Decryption(String::from(
+                "remote file is encrypted, a password is required!",
+            ))
+            .into())
+        }
+        (None, _) => None,
+    };
+
+    let client = get_client()?;
+    let (_, res) = get_file_response(&client, &storage_id, access_token.as_deref(), None)
+        .await
+        .context("error occured while fetching file!")?;
+    let mut stream = api::retry::get_body_stream(&client, res);
+
+    let file_stream_read_buf_size = metadata
+        .encryption
+        .as_ref()
+        .map(|e| e.get_sealed_block_size())
+        .unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE)
+        as usize;
+
+    let progress_bar = Rc::new(ProgressBar::new(metadata.file_size as u64));
+
+    let prog = Rc::clone(&progress_bar);
+    let blocks_stream = async_stream::try_stream! {
+        let mut data_buf: Vec<u8> = vec![];
+        while let Some(chunk_res) = stream.next().await {
+            let chunk = chunk_res?;
+            data_buf.extend_from_slice(&chunk);
+            prog.inc(chunk.len() as u64);
+            drop(chunk);
+
+            if data_buf.len() < file_stream_read_buf_size {
+                continue;
+            }
+
+            let offset = (data_buf.len() / file_stream_read_buf_size) * file_stream_read_buf_size;
+            let residual = data_buf.drain(offset..).collect::<Vec<u8>>();
+            yield data_buf;
+
+            data_buf = residual;
+        }
+
+        yield data_buf;
+    };
+    let mut blocks_stream: LocalBoxStream<anyhow::Result<Vec<u8>>> = Box::pin(blocks_stream);
+
+    let checksum = Checksum::default();
+    let mut stdout = utils::streams::decompress_writer(
+        ChecksumWriter::new(io::stdout(), checksum.clone()),
+        metadata.compression,
+    );
+    while let Some(blocks_res) = blocks_stream.next().await {
+        let blocks = blocks_res.context("error occured while reading stream!")?;
+        let blocks_len = blocks.len();
+        let mut i = 0;
+        let mut c = 0;
+        while i < blocks.len() {
+            let slice = &blocks[i..blocks_len.min(i + file_stream_read_buf_size)];
+            let slice = match &mut decryptor {
+                Some(d) => {
+                    &d.e.open_chunk(slice)
+                        .map_err(|_| {
+                            SfsError::Decryption(String::from(
+                                "decryption failed! please check your password.",
+                            ))
+                        })?
+                        .0
                 }
+                None => slice,
+            };
 
-                enc_metadata.attempt_decryption
+            stdout
+                .write_all(slice)
+                .await
+                .context("write to stdout failed!")?;
+            if c % 10 == 0 {
+                if let Err(err) = stdout.flush().await {
+                    eprintln!("WARNING: cannot flush stdout. error: {err}");
+                }
             }
-            None => false,
-        };
 
-        let (_, res) = get_file_response(storage_id, access_token.as_deref())
+            i += file_stream_read_buf_size;
+            c += 1;
+        }
+    }
+
+    progress_bar.finish_and_clear();
+    stdout
+        .shutdown()
+        .await
+        .context("error occured while writing to stdout!")?;
+
+    checksum
+        .verify(metadata.checksum.as_deref())
+        .context("received file does not match its checksum!")?;
+
+    Ok(())
+}
+
+impl CliSubCmd for GetCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let RemoteFile {
+            access_token, file, ..
+        } = get_remote_file(&self.location_hint)
             .await
-            .expect("error occured while fetching file!");
-        let mut stream = res.bytes_stream();
+            .context("error occured while fetching remote file!")?;
 
-        let file_stream_read_buf_size = metadata
+        let mut download_filepath = match &self.output {
+            Some(output) => {
+                paths::get_absolute_path(output).context("invalid output path provided!")?
+            }
+            None => current_dir().context("cannot find os CWD!")?,
+        };
+        if download_filepath.is_dir() {
+            download_filepath.push(&file.name);
+        }
+
+        if download_filepath.exists() && !self.force {
+            return Err(SfsError::AlreadyExists(format!(
+                "'{}' already exists! use --force to overwrite it.",
+                download_filepath.to_string_lossy()
+            ))
+            .into());
+        }
+
+        let password = file
             .encryption
             .as_ref()
-            .map(|e| {
-                e.block_size.unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE)
-                    + streaming::ABYTES as u32
-            })
-            .unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE)
-            as usize;
+            .filter(|e| e.attempt_decryption)
+            .map(|_| utils::crypto::get_decryption_password())
+            .transpose()
+            .context("error occured while reading password!")?;
 
-        let progress_bar = Rc::new(ProgressBar::new(metadata.file_size as u64));
+        let mut opts = DownloadFileOpts::new(
+            download_filepath.clone(),
+            password,
+            ProgressBar::new(file.file_size as u64)
+                .with_style(utils::misc::get_sized_throughput_progress_style(None)),
+        );
+        opts.max_retries = self.retries;
+        opts.rate_limiter = self.rate_limit_params.get_rate_limiter();
+        api::downloads::download_file(&get_client()?, &file, access_token.as_deref(), opts)
+            .await
+            .context("error occured while downloading file!")?;
 
-        let prog = Rc::clone(&progress_bar);
-        let blocks_stream = async_stream::stream! {
-            let mut data_buf: Vec<u8> = vec![];
-            while let Some(chunk_res) = stream.next().await {
-                let chunk = chunk_res.expect("error occured while reading stream!");
-                data_buf.extend_from_slice(&chunk);
-                prog.inc(chunk.len() as u64);
-                drop(chunk);
+        println!(
+            "{}",
+            format!("Saved to: {}", download_filepath.to_string_lossy()).dimmed()
+        );
 
-                if data_buf.len() < file_stream_read_buf_size as usize {
-                    continue;
-                }
+        Ok(())
+    }
+}
 
-                let offset = (data_buf.len() / file_stream_read_buf_size) * file_stream_read_buf_size;
-                let residual = data_buf.drain(offset..).collect::<Vec<u8>>();
-                yield data_buf;
+impl CliSubCmd for PullCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = STATE.read().unwrap().get_wd().to_string();
 
-                data_buf = residual;
+        let remote_dirpath = dirtree::get_absolute_path(&self.remote_dirpath, &wd);
+        let local_dirpath = match &self.local_dirpath {
+            Some(dirpath) => {
+                paths::get_absolute_path(dirpath).context("invalid local dirpath provided!")?
             }
+            None => current_dir().context("cannot find os CWD!")?,
+        };
 
-            yield data_buf;
+        let res = api::dirtree::get_dirtree(&get_client()?)
+            .await
+            .context("error occured while fetching dirtree!")?;
+        let subtree = match res.dirtree.get_sub_tree(&remote_dirpath) {
+            Some(subtree) => subtree,
+            None => {
+                return Err(SfsError::NotFound(format!(
+                    "remote directory '{}' does not exist!",
+                    remote_dirpath
+                ))
+                .into());
+            }
         };
-        let mut blocks_stream = Box::pin(blocks_stream);
-
-        let mut stdout = io::stdout();
-        while let Some(blocks) = blocks_stream.next().await {
-            let blocks_len = blocks.len();
-            let mut i = 0;
-            let mut c = 0;
-            while i < blocks.len() {
-                let slice = &blocks[i..blocks_len.min(i + file_stream_read_buf_size)];
-                let slice = match &mut decryptor {
-                    Some(d) => {
-                        &d.e.open_chunk(slice)
-                            .expect("error occured while decrypting!")
-                            .0
-                    }
-                    None => slice,
-                };
 
-                _ = stdout
-                    .write_all(slice)
-                    .await
-                    .expect("write to stdout failed!");
-                if c % 10 == 0 {
-                    if let Err(err) = stdout.flush().await {
-                        eprintln!("WARNING: cannot flush stdout. error: {err}");
-                    }
+        let spinner = ProgressBar::new_spinner().with_message("listing remote files");
+        spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+        let listings = api::fs_files::get_all_files_in_dirs(
+            &get_client()?,
+            subtree.get_dir_paths(&remote_dirpath),
+        )
+        .await
+        .context("error occured while listing remote files!")?;
+        spinner.finish_and_clear();
+
+        let mut n_skipped = 0;
+        let mut downloads: Vec<(FsFile, PathBuf)> = vec![];
+        for (dirpath, files) in listings {
+            let local_dir = local_dirpath.join(
+                dirpath
+                    .strip_prefix(&remote_dirpath)
+                    .unwrap_or("")
+                    .trim_start_matches('/'),
+            );
+            fs::create_dir_all(&local_dir)
+                .await
+                .context("error occured while creating local directory!")?;
+
+            for file in files {
+                let filepath = local_dir.join(&file.name);
+                if filepath.exists() && !self.force {
+                    n_skipped += 1;
+                    continue;
                 }
 
-                i += file_stream_read_buf_size;
-                c += 1;
+                downloads.push((file, filepath));
             }
         }
 
-        progress_bar.finish_and_clear();
-        _ = stdout.shutdown().await;
+        if downloads.is_empty() {
+            println!(
+                "{}",
+                format!(
+                    "nothing to download. ({} existing files skipped)",
+                    n_skipped
+                )
+                .bold()
+            );
+            return Ok(());
+        }
+
+        let password = downloads
+            .iter()
+            .any(|(file, _)| {
+                file.encryption
+                    .as_ref()
+                    .is_some_and(|e| e.attempt_decryption)
+            })
+            .then(utils::crypto::get_decryption_password)
+            .transpose()
+            .context("error occured while reading password!")?;
+
+        let mut opts = DownloadFilesOpts::new(password);
+        opts.max_concurrent = self.transfer_params.get_jobs();
+        opts.rate_limiter = self.transfer_params.get_rate_limiter();
+
+        let n_downloads = downloads.len();
+        api::downloads::download_files(&get_client()?, downloads, &local_dirpath, opts)
+            .await
+            .context("some files failed to download!")?;
+
+        println!(
+            "\n{}",
+            format!(
+                "{} files downloaded into {}",
+                n_downloads,
+                local_dirpath.to_string_lossy()
+            )
+            .bold()
+        );
+        if n_skipped > 0 {
+            println!(
+                "{}",
+                format!(
+                    "{} existing files skipped, use --force to overwrite.",
+                    n_skipped
+                )
+                .dimmed()
+            );
+        }
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for SelectCommand {
-    async fn run(&self) {
+    async fn run(&self) -> anyhow::Result<()> {
         let file_stream_read_buf_size = constants::FILE_STREAM_READ_BUF_SIZE;
 
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
         let abs_filepath = dirtree::get_absolute_path(&self.filepath, wd);
         let (dirpath, filename) = dirtree::split_path(&abs_filepath);
 
-        let enc = self.upload_params.get_password().map(|p| {
-            utils::crypto::new_encryptor(&p).expect("error occured while initializing decryptor")
-        });
+        let enc = self
+            .upload_params
+            .get_password()?
+            .map(|p| utils::crypto::new_encryptor(&p))
+            .transpose()
+            .context("error occured while initializing decryptor")?;
         let enc_metadata = enc
             .as_ref()
             .map(|e| e.into_encryption_metadata(Some(file_stream_read_buf_size)));
 
         let (sender, mut receiver) = mpsc::unbounded_channel();
 
-        let stdin = io::stdin();
+        let checksum = Checksum::default();
+        let stdin = utils::streams::compress_reader(
+            ChecksumReader::new(io::stdin(), checksum.clone()),
+            self.upload_params.compress,
+        );
         let stdin_stream = utils::streams::read_into_stream(
             stdin,
             file_stream_read_buf_size,
@@ -796,18 +1147,19 @@ impl CliSubCmd for SelectCommand {
         let dirpath = dirpath.to_string();
         let upload_metadata =
             self.upload_params
-                .into_upload_metadata(filename, dirpath, true, enc_metadata);
+                .get_upload_metadata(filename, dirpath.clone(), true, enc_metadata);
+        let client = get_client()?;
+        let upload_client = client.clone();
         let upload_handle = task::spawn(async move {
-            api::uploads::upload_blob_stream(stdin_stream, &upload_metadata)
-                .await
-                .expect("error occured while uploading file!")
+            api::uploads::upload_blob_stream(&upload_client, stdin_stream, &upload_metadata).await
         });
 
-        let is_share = self.upload_params.is_share();
-        let share_exp = self.upload_params.get_share_expiry();
+        let is_share = self.share_params.is_share();
+        let share_exp = self.share_params.get_share_expiry();
         let token_handle = task::spawn(async move {
             if is_share {
                 match api::tokens::generate_access_token(
+                    &client,
                     &[format!("r:{}", abs_filepath)],
                     &share_exp,
                 )
@@ -835,14 +1187,20 @@ impl CliSubCmd for SelectCommand {
         let (file, token) = futures_util::future::join(upload_handle, token_handle).await;
         progress_bar.finish();
 
-        let file = file.expect("error occured while uploading file!");
-        let token = token.expect("error occured while generating token!");
+        let mut file = file
+            .context("upload task failed to complete!")?
+            .context("error occured while uploading file!")?;
+        let token = token.context("error occured while generating token!")?;
+
+        api::uploads::set_file_checksum(&get_client()?, &mut file, &dirpath, &checksum)
+            .await
+            .context("error occured while uploading file!")?;
 
         if let Some(token_res) = token {
             let access_token: shared_types::AccessToken = token_res
                 .access_token
                 .parse()
-                .expect("invalid access token returned! cannot be parsed.");
+                .context("invalid access token returned! cannot be parsed.")?;
             let url = get_share_url(
                 match self.upload_params.public {
                     true => None,
@@ -850,7 +1208,7 @@ impl CliSubCmd for SelectCommand {
                 },
                 &file.storage_id,
             )
-            .expect("failed to generate url!");
+            .context("failed to generate url!")?;
 
             eprintln!("\n{}\n", url.to_string().bold().cyan());
             eprintln!(
@@ -870,5 +1228,7 @@ impl CliSubCmd for SelectCommand {
                 .dimmed()
             );
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,433 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use clap::{Arg, Command, CommandFactory, Parser, ValueEnum, ValueHint};
+use serde::{Deserialize, Serialize};
+
+use sfs_cli::{
+    config::{self, CONFIG},
+    constants,
+    shared_types::DirTree,
+    state::STATE,
+    utils::{dirtree, local_auth::EnvAuthData},
+    SfsClient,
+};
+
+use crate::{cmd::CliSubCmd, Cli};
+
+/// printed by `__complete` instead of candidates when the argument is a local path, the scripts
+/// fall back to the shell's own file completion
+const FILES_DIRECTIVE: &str = ":files";
+
+/// ids of the args taking remote directories
+const REMOTE_DIR_ARGS: [&str; 3] = ["dirpath", "new_dirpath", "remote_dirpath"];
+/// ids of the args taking remote files (or directories, e.g. `mv`)
+const REMOTE_PATH_ARGS: [&str; 8] = [
+    "filepath",
+    "new_filepath",
+    "src",
+    "dest",
+    "filenames",
+    "filename",
+    "path",
+    "location_hint",
+];
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Parser)]
+#[command(after_help = "Setup:
+  bash  echo 'source <(sfs-cli completions bash)' >> ~/.bashrc
+  zsh   echo 'source <(sfs-cli completions zsh)' >> ~/.zshrc
+  fish  sfs-cli completions fish > ~/.config/fish/completions/sfs-cli.fish")]
+pub struct CompletionsCommand {
+    shell: CompletionShell,
+
+    #[arg(long)]
+    /// name the binary is called with, if it's renamed or aliased (default: sfs-cli)
+    bin_name: Option<String>,
+}
+
+/// prints the candidates for the last of `words`, one per line. used by the completion scripts.
+#[derive(Parser)]
+pub struct CompleteCommand {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    /// the command line after the binary name, the last word is the one being completed
+    words: Vec<String>,
+}
+
+/// remote names fetched for completions, kept for a short while as scripts call `__complete`
+/// on every <TAB>
+#[derive(Serialize, Deserialize, Default)]
+struct CompletionCache {
+    /// base url & active token the names were fetched with
+    key: String,
+    dirtree: Option<(DateTime<Utc>, DirTree)>,
+    /// (dirpath, (fetched_at, filenames))
+    files: HashMap<String, (DateTime<Utc>, Vec<String>)>,
+}
+
+impl CompletionCache {
+    fn get_key() -> String {
+        format!(
+            "{} {}",
+            CONFIG.read().unwrap().get_base_url(),
+            STATE.read().unwrap().get_active_token_label()
+        )
+    }
+
+    fn get_filepath() -> Option<PathBuf> {
+        let profile = CONFIG.read().unwrap().get_profile().to_string();
+        config::get_profile_dirpath(&profile)
+            .ok()
+            .map(|dirpath| dirpath.join("completion_cache.json"))
+    }
+
+    fn is_fresh(fetched_at: &DateTime<Utc>) -> bool {
+        Utc::now() - fetched_at < Duration::seconds(constants::COMPLETION_CACHE_TTL_SECS)
+    }
+
+    fn load() -> Self {
+        let key = Self::get_key();
+        let cache = Self::get_filepath()
+            .and_then(|filepath| fs::read_to_string(filepath).ok())
+            .and_then(|contents| serde_json::from_str::<Self>(&contents).ok())
+            .filter(|cache| cache.key == key);
+
+        cache.unwrap_or(Self {
+            key,
+            ..Default::default()
+        })
+    }
+
+    fn save(&self) {
+        // same as the local state, nothing is written if credentials come from the environment
+        if EnvAuthData::get().is_some() {
+            return;
+        }
+        if let (Some(filepath), Ok(contents)) = (Self::get_filepath(), serde_json::to_vec(self)) {
+            _ = fs::write(filepath, contents);
+        }
+    }
+
+    async fn get_dirtree(&mut self, client: &SfsClient) -> Option<&DirTree> {
+        if !matches!(&self.dirtree, Some((fetched_at, _)) if Self::is_fresh(fetched_at)) {
+            let dirtree = client.get_dirtree().await.ok()?.dirtree;
+            self.dirtree = Some((Utc::now(), dirtree));
+            self.save();
+        }
+
+        self.dirtree.as_ref().map(|(_, dirtree)| dirtree)
+    }
+
+    async fn get_filenames(&mut self, client: &SfsClient, dirpath: &str) -> Option<&Vec<String>> {
+        if !matches!(self.files.get(dirpath), Some((fetched_at, _)) if Self::is_fresh(fetched_at)) {
+            let filenames = client
+                .list_files(dirpath)
+                .await
+                .ok()?
+                .into_iter()
+                .map(|file| file.name)
+                .collect();
+            self.files
+                .retain(|_, (fetched_at, _)| Self::is_fresh(fetched_at));
+            self.files
+                .insert(dirpath.to_string(), (Utc::now(), filenames));
+            self.save();
+        }
+
+        self.files.get(dirpath).map(|(_, filenames)| filenames)
+    }
+}
+
+impl CompletionsCommand {
+    fn get_script(&self) -> String {
+        let bin_name = self
+            .bin_name
+            .clone()
+            .unwrap_or(Cli::command().get_name().to_string());
+        let fn_name = format!(
+            "__{}_complete",
+            bin_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        );
+
+        let script = match self.shell {
+            CompletionShell::Bash => {
+                r#"__FN() {
+    local IFS=$'\n'
+    local candidates=($(BIN __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+
+    if [[ "${candidates[0]}" == "DIRECTIVE" ]]; then
+        compopt -o default
+        COMPREPLY=()
+        return
+    fi
+
+    COMPREPLY=("${candidates[@]}")
+    if [[ ${#COMPREPLY[@]} -eq 1 && "${COMPREPLY[0]}" == */ ]]; then
+        compopt -o nospace
+    fi
+}
+complete -F __FN BIN
+"#
+            }
+            CompletionShell::Zsh => {
+                r#"__FN() {
+    local -a candidates
+    candidates=(${(f)"$(BIN __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+
+    if [[ "${candidates[1]}" == "DIRECTIVE" ]]; then
+        _files
+        return
+    fi
+
+    compadd -Q -S '' -- ${(M)candidates:#*/}
+    compadd -Q -- ${candidates:#*/}
+}
+compdef __FN BIN
+"#
+            }
+            CompletionShell::Fish => {
+                r#"function __FN
+    set -l words (commandline -opc)
+    set -l current (commandline -ct)
+    set -l candidates (BIN __complete -- $words[2..-1] "$current" 2>/dev/null)
+
+    if test "$candidates[1]" = "DIRECTIVE"
+        __fish_complete_path "$current"
+        return
+    end
+
+    printf '%s\n' $candidates
+end
+complete -c BIN -f -a '(__FN)'
+"#
+            }
+        };
+
+        script
+            .replace("__FN", &fn_name)
+            .replace("BIN", &bin_name)
+            .replace("DIRECTIVE", FILES_DIRECTIVE)
+    }
+}
+
+impl CliSubCmd for CompletionsCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        print!("{}", self.get_script());
+
+        Ok(())
+    }
+}
+
+/// what the word being completed is, found by walking the words through the clap commands
+enum CompletionTarget<'a> {
+    Subcommands(&'a Command),
+    Flags(&'a Command),
+    /// value of an option or positional arg of the command at `cmd_path`
+    Value {
+        arg: &'a Arg,
+        cmd_path: Vec<&'a str>,
+    },
+    Nothing,
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values()
+}
+
+fn find_flag<'a>(cmd: &'a Command, word: &str) -> Option<&'a Arg> {
+    match word.strip_prefix("--") {
+        Some(long) => cmd.get_arguments().find(|arg| arg.get_long() == Some(long)),
+        None => {
+            let short = word.strip_prefix('-')?.chars().next()?;
+            cmd.get_arguments()
+                .find(|arg| arg.get_short() == Some(short))
+        }
+    }
+}
+
+fn get_target<'a>(root: &'a Command, words: &[String]) -> CompletionTarget<'a> {
+    let (current, words) = match words.split_last() {
+        Some((current, words)) => (current.as_str(), words),
+        None => ("", words),
+    };
+
+    let mut cmd = root;
+    let mut cmd_path = vec![];
+    let mut positional_i = 0;
+    let mut pending_option = None;
+    let mut is_escaped = false;
+
+    for word in words {
+        if pending_option.take().is_some() {
+            continue;
+        }
+        if !is_escaped && word == "--" {
+            is_escaped = true;
+            continue;
+        }
+        // `--flag=value` & `-fvalue` carry their own values
+        if !is_escaped && word.starts_with('-') && word.len() > 1 {
+            let is_inline = word.contains('=') || (!word.starts_with("--") && word.len() > 2);
+            pending_option = find_flag(cmd, word).filter(|arg| takes_value(arg) && !is_inline);
+            continue;
+        }
+        if positional_i == 0 {
+            if let Some(subcmd) = cmd.find_subcommand(word) {
+                cmd = subcmd;
+                cmd_path.push(subcmd.get_name());
+                continue;
+            }
+        }
+
+        positional_i += 1;
+    }
+
+    if let Some(arg) = pending_option {
+        return CompletionTarget::Value { arg, cmd_path };
+    }
+    if !is_escaped && current.starts_with('-') {
+        return CompletionTarget::Flags(cmd);
+    }
+    if cmd.has_subcommands() && positional_i == 0 {
+        return CompletionTarget::Subcommands(cmd);
+    }
+
+    let positionals: Vec<&Arg> = cmd.get_positionals().collect();
+    let arg = positionals.get(positional_i).or(positionals
+        .last()
+        .filter(|arg| arg.get_num_args().is_some_and(|n| n.max_values() > 1)));
+    match arg {
+        Some(arg) => CompletionTarget::Value { arg, cmd_path },
+        None => CompletionTarget::Nothing,
+    }
+}
+
+impl CompleteCommand {
+    /// profile selected with `--profile` on the line being completed, it has to be set before the
+    /// config is loaded
+    pub fn get_profile_arg(&self) -> Option<String> {
+        self.words
+            .iter()
+            .enumerate()
+            .find_map(|(i, word)| match word.strip_prefix("--profile") {
+                Some("") => self.words.get(i + 1).cloned(),
+                Some(value) => value.strip_prefix('=').map(|value| value.to_string()),
+                None => None,
+            })
+    }
+
+    /// names in the remote directory the word points to, directories end with "/"
+    async fn get_remote_candidates(word: &str, include_files: bool) -> Option<Vec<String>> {
+        let client = SfsClient::from_local_state().ok()?;
+        let mut cache = CompletionCache::load();
+
+        let (parent, _) = dirtree::split_path(word);
+        let (parent, parent_abs_path) = match word.contains('/') {
+            true => (
+                parent,
+                dirtree::get_absolute_path(parent, STATE.read().unwrap().get_wd()),
+            ),
+            false => ("", STATE.read().unwrap().get_wd().to_string()),
+        };
+
+        let mut candidates: Vec<String> = cache
+            .get_dirtree(&client)
+            .await?
+            .get_sub_tree(&parent_abs_path)?
+            .children
+            .iter()
+            .map(|child| format!("{parent}{}/", child.name))
+            .collect();
+        if include_files {
+            if let Some(filenames) = cache.get_filenames(&client, &parent_abs_path).await {
+                candidates.extend(filenames.iter().map(|name| format!("{parent}{name}")));
+            }
+        }
+
+        Some(candidates)
+    }
+
+    async fn get_value_candidates(arg: &Arg, cmd_path: &[&str], current: &str) -> Vec<String> {
+        let id = arg.get_id().as_str();
+
+        let possible_values = arg.get_possible_values();
+        if !possible_values.is_empty() {
+            return possible_values
+                .iter()
+                .filter(|value| !value.is_hide_set())
+                .map(|value| value.get_name().to_string())
+                .collect();
+        }
+
+        if id == "profile"
+            || (matches!(cmd_path, ["config", "profiles", "use" | "rm"]) && id == "name")
+        {
+            return CONFIG
+                .read()
+                .unwrap()
+                .get_profiles()
+                .into_iter()
+                .map(|(name, _, _)| name.to_string())
+                .collect();
+        }
+        if (matches!(cmd_path, ["tokens", "use" | "ls"]) && matches!(id, "input" | "tag"))
+            || (matches!(cmd_path, ["cp"]) && matches!(id, "from" | "to"))
+        {
+            return STATE.read().unwrap().tokens.keys().cloned().collect();
+        }
+        if REMOTE_DIR_ARGS.contains(&id) || REMOTE_PATH_ARGS.contains(&id) {
+            return Self::get_remote_candidates(current, REMOTE_PATH_ARGS.contains(&id))
+                .await
+                .unwrap_or_default();
+        }
+
+        match arg.get_value_hint() {
+            ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath => {
+                vec![FILES_DIRECTIVE.to_string()]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl CliSubCmd for CompleteCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let mut root = Cli::command();
+        root.build();
+
+        let current = self.words.last().map(|word| word.as_str()).unwrap_or("");
+        let mut candidates = match get_target(&root, &self.words) {
+            CompletionTarget::Subcommands(cmd) => cmd
+                .get_subcommands()
+                .filter(|subcmd| !subcmd.is_hide_set())
+                .map(|subcmd| subcmd.get_name().to_string())
+                .collect(),
+            CompletionTarget::Flags(cmd) => cmd
+                .get_arguments()
+                .filter(|arg| !arg.is_hide_set())
+                .filter_map(|arg| arg.get_long().map(|long| format!("--{long}")))
+                .collect(),
+            CompletionTarget::Value { arg, cmd_path } => {
+                Self::get_value_candidates(arg, &cmd_path, current).await
+            }
+            CompletionTarget::Nothing => vec![],
+        };
+
+        if candidates.first().map(|c| c.as_str()) != Some(FILES_DIRECTIVE) {
+            candidates.retain(|candidate| candidate.starts_with(current));
+            candidates.sort();
+        }
+        for candidate in candidates {
+            println!("{candidate}");
+        }
+
+        Ok(())
+    }
+}
@@ -1,27 +1,216 @@
-use clap::Parser;
+use std::io;
 
-use crate::{
-    config::{LogLevel, CONFIG},
-    shared_types::CliSubCmd,
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+
+use sfs_cli::{
+    config::{self, ConfigKey, ConfigValueSource, LogLevel, ProfileConfig, CONFIG},
+    utils::local_auth::LocalAuthData,
 };
 
+use crate::cmd::{output, CliSubCmd};
+
 #[derive(Parser)]
-#[group(required = true, multiple = false)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 pub struct ConfigCommand {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     #[arg(long)]
     /// default log level is "chirpy", it can be annoying i totally get why you'd wa-
     set_log_level: Option<LogLevel>,
 }
 
+#[derive(Subcommand)]
+enum Commands {
+    /// print the value in use of a setting
+    Get { key: ConfigKey },
+    /// save a setting to the config file
+    Set { key: ConfigKey, value: String },
+    /// remove a setting from the config file, its default is used again
+    Unset { key: ConfigKey },
+    /// list all settings and where their values come from
+    List,
+    /// manage named profiles, each with its own base url, login, tokens and working directory
+    Profiles {
+        #[command(subcommand)]
+        command: ProfilesCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfilesCommands {
+    /// add a profile, or update the base url of an existing one
+    Add {
+        name: String,
+
+        #[arg(long)]
+        /// api base url of the deployment (e.g. "https://staging.example.com")
+        base_url: String,
+
+        #[arg(long)]
+        /// github oauth app used to login, defaults to the one of the default profile
+        github_client_id: Option<String>,
+    },
+    /// list profiles, the selected one is marked
+    Ls,
+    /// select the profile to use when neither `--profile` nor `SFS_PROFILE` is set
+    Use { name: String },
+    /// remove a profile along with its login, tokens and upload journals
+    Rm { name: String },
+}
+
+impl ConfigCommand {
+    fn handle_list(&self) -> anyhow::Result<()> {
+        let config = CONFIG.read().unwrap();
+
+        #[derive(Serialize)]
+        struct ConfigValueOutput {
+            key: &'static str,
+            value: Option<String>,
+            source: ConfigValueSource,
+        }
+        let mut values = vec![];
+        for key in ConfigKey::value_variants() {
+            let (value, source) = config.get_effective_value(*key)?;
+            values.push(ConfigValueOutput {
+                key: key.as_str(),
+                value,
+                source,
+            });
+        }
+
+        if !output::is_text() {
+            return output::print_list(values);
+        }
+
+        let key_padding = values.iter().fold(0, |acc, v| acc.max(v.key.len()));
+        let value_padding = values.iter().fold(0, |acc, v| {
+            acc.max(v.value.as_ref().map(|value| value.len()).unwrap_or(1))
+        });
+        for v in values {
+            println!(
+                "{:<key_padding$} {:<value_padding$} {}",
+                v.key.bold(),
+                v.value.as_deref().unwrap_or("-"),
+                format!("({})", v.source).dimmed()
+            );
+        }
+        println!(
+            "\n{}",
+            format!("profile: {}", config.get_profile()).dimmed()
+        );
+
+        Ok(())
+    }
+
+    fn handle_profiles(&self, command: &ProfilesCommands) -> anyhow::Result<()> {
+        let mut config = CONFIG.write().unwrap();
+
+        match command {
+            ProfilesCommands::Add {
+                name,
+                base_url,
+                github_client_id,
+            } => {
+                url::Url::parse(base_url).context("invalid base url provided!")?;
+                config
+                    .add_profile(
+                        name,
+                        ProfileConfig {
+                            base_url: base_url.clone(),
+                            github_client_id: github_client_id.clone(),
+                        },
+                    )
+                    .context("error occured while adding profile!")?;
+
+                println!("profile '{}' saved.", name.bold());
+            }
+            ProfilesCommands::Ls => {
+                let profiles = config.get_profiles();
+                let padding = profiles
+                    .iter()
+                    .fold(0, |acc, (name, _, _)| acc.max(name.len()));
+
+                for (name, base_url, is_selected) in profiles {
+                    println!(
+                        "{} {:<padding$} {}",
+                        match is_selected {
+                            true => "[v]".green(),
+                            false => "[ ]".dimmed(),
+                        },
+                        name.bold(),
+                        base_url.dimmed()
+                    );
+                }
+            }
+            ProfilesCommands::Use { name } => {
+                config
+                    .set_active_profile(name)
+                    .context("error occured while selecting profile!")?;
+
+                println!("using profile '{}'.", name.bold());
+            }
+            ProfilesCommands::Rm { name } => {
+                config
+                    .remove_profile(name)
+                    .context("error occured while removing profile!")?;
+
+                match std::fs::remove_dir_all(config::get_profile_dirpath(name)?) {
+                    Ok(_) => {}
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => {
+                        return Err(err).context("error occured while deleting profile's state!")
+                    }
+                }
+                LocalAuthData::delete_profile(name)
+                    .context("error occured while deleting keyring entry of the profile!")?;
+
+                println!("profile '{}' removed.", name.bold());
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl CliSubCmd for ConfigCommand {
-    async fn run(&self) {
+    async fn run(&self) -> anyhow::Result<()> {
+        match &self.command {
+            Some(Commands::Get { key }) => {
+                let (value, _) = CONFIG.read().unwrap().get_effective_value(*key)?;
+                println!("{}", value.as_deref().unwrap_or(""));
+                return Ok(());
+            }
+            Some(Commands::Set { key, value }) => {
+                return CONFIG
+                    .write()
+                    .unwrap()
+                    .set_value(*key, value)
+                    .context("error occured while setting config value");
+            }
+            Some(Commands::Unset { key }) => {
+                return CONFIG
+                    .write()
+                    .unwrap()
+                    .unset_value(*key)
+                    .context("error occured while writing to config file");
+            }
+            Some(Commands::List) => return self.handle_list(),
+            Some(Commands::Profiles { command }) => return self.handle_profiles(command),
+            None => {}
+        }
+
         let mut config = CONFIG.write().unwrap();
 
-        match self.set_log_level.clone() {
-            Some(log_level) => config
+        if let Some(log_level) = self.set_log_level.clone() {
+            config
                 .set_log_level(log_level)
-                .expect("error occured while writing to config file"),
-            None => {}
+                .context("error occured while writing to config file")?;
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Parser, ValueHint};
+use colored::Colorize;
+use tokio::task;
+
+use sfs_cli::{constants, dev_server::DevServer, utils::paths};
+
+use crate::cmd::CliSubCmd;
+
+#[derive(Parser)]
+pub struct DevServerCommand {
+    #[arg(long, default_value_t = constants::DEV_SERVER_DEFAULT_PORT)]
+    /// port to listen on, only connections from this machine are accepted
+    port: u16,
+
+    #[arg(long, default_value = ".sfs-dev", value_hint = ValueHint::DirPath)]
+    /// directory the files & the api key are kept in, created if it doesn't exist
+    dir: PathBuf,
+}
+
+impl CliSubCmd for DevServerCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let dirpath = paths::get_absolute_path(&self.dir)?;
+        let server = DevServer::start(&dirpath, self.port)
+            .context("error occured while starting dev server!")?;
+
+        println!(
+            "{}",
+            format!(
+                "dev server listening at {}, files are kept in '{}'.",
+                server.get_creds().base_url,
+                dirpath.to_string_lossy()
+            )
+            .dimmed()
+        );
+        println!(
+            "{}",
+            "run these in another terminal to use the CLI with it as the owner:".dimmed()
+        );
+        for (key, value) in server.get_env() {
+            println!("export {key}={value}");
+        }
+
+        task::spawn_blocking(move || server.wait()).await?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,19 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound,
+};
+
+use anyhow::{anyhow, Context};
 use chrono::Duration;
-use clap::Parser;
+use clap::{Args, Parser};
 use colored::Colorize;
-use serde::Serialize;
+use futures_util::StreamExt;
+use indicatif::{MultiProgress, ProgressBar};
+use inquire::Confirm;
+use regex::Regex;
 use serde_json::json;
 
-use crate::{
+use sfs_cli::{
     api::{
         self,
         dirtree::MvOpts,
@@ -14,9 +23,19 @@ use crate::{
         },
     },
     constants::{self, MIME_TYPES},
-    shared_types::{self, AccessToken, AccessTokenPermission, ApiResponse, CliSubCmd, DirTree},
+    error::SfsError,
+    shared_types::{
+        self, AccessToken, AccessTokenPermission, CmdTransferParams, DirTree, FsFile, TreeFormat,
+    },
     state::STATE,
-    utils::{self, files, x2str},
+    utils::{self, checksum::Checksum, files, paths, prompts, x2str},
+    SfsClient,
+};
+
+use crate::cmd::{
+    get_client,
+    output::{self, DuOutput, FindOutput, ShareUrlOutput, TreeOutput},
+    CliSubCmd,
 };
 
 #[derive(Parser)]
@@ -28,6 +47,23 @@ pub struct TreeCommand {
     /// start this path with a "/" to indicate absolute path, if in a wd "." & ".." are also
     /// supported
     dirpath: Option<String>,
+
+    #[arg(long)]
+    /// list the files in every directory along with their sizes
+    files: bool,
+
+    #[arg(long)]
+    /// roll up the file counts & sizes of nested directories into their parents
+    cumulative: bool,
+
+    #[arg(long)]
+    /// only count (and list) the files matching a pattern (e.g. "**/*.rs"), directories without
+    /// any are left out. relative to the printed directory unless it starts with "/"
+    pattern: Option<String>,
+
+    #[arg(long, value_enum)]
+    /// layout to print the tree in, takes precedence over the global `--output` flag
+    format: Option<TreeFormat>,
 }
 
 #[derive(Parser)]
@@ -44,12 +80,16 @@ pub struct RmdirCommand {
 
 #[derive(Parser)]
 pub struct RmCommand {
-    /// names of the files to delete in the selected directory
+    /// names of the files to delete in the selected directory, or patterns of them (e.g. "**/*.o")
     filenames: Vec<String>,
 
     #[arg(long)]
     /// path of the remote dir containing the files to be deleted (defaults to currently set WD)
     dirpath: Option<String>,
+
+    #[arg(long)]
+    /// do not ask before deleting the files matching a pattern
+    no_confirm: bool,
 }
 
 #[derive(Parser)]
@@ -63,11 +103,44 @@ pub struct MvdirCommand {
 
 #[derive(Parser)]
 pub struct MvCommand {
-    /// path of the file to move
+    /// path of the file to move, or a pattern of the files to move (e.g. "/tmp/*.log")
     filepath: String,
 
-    /// new location of the file
+    /// new location of the file, the directory to move the files into for a pattern
     new_filepath: String,
+
+    #[arg(long)]
+    /// do not ask before moving the files matching a pattern
+    no_confirm: bool,
+}
+
+#[derive(Parser)]
+pub struct CpCommand {
+    /// path of the file or directory to copy
+    src: String,
+
+    /// new location of the copy, files & directories are copied into it if it's an existing
+    /// directory
+    dest: String,
+
+    #[arg(short, long)]
+    /// copy a directory along with its files & nested directories
+    recursive: bool,
+
+    #[arg(short, long)]
+    /// overwrite files that already exist at the destination
+    force: bool,
+
+    #[arg(long)]
+    /// tag of a locally saved token to copy from, its file system's paths are relative to "/"
+    from: Option<String>,
+
+    #[arg(long)]
+    /// tag of a locally saved token to copy into, its file system's paths are relative to "/"
+    to: Option<String>,
+
+    #[command(flatten)]
+    transfer_params: CmdTransferParams,
 }
 
 #[derive(Parser)]
@@ -88,6 +161,21 @@ pub struct LsCommand {
     #[arg(short, long)]
     page: Option<usize>,
 
+    #[arg(long)]
+    /// order by a column
+    order_by: Option<OrderCol>,
+
+    #[arg(long)]
+    /// order in which given column should be ordered by
+    order: Option<Order>,
+
+    #[command(flatten)]
+    file_filters: FileFilterArgs,
+}
+
+/// filters of `ls` & `find`
+#[derive(Args)]
+pub struct FileFilterArgs {
     #[arg(short, long)]
     /// search by file name. use '%' to search as a pattern. (e.g. "myfilename.json", "v%_myexe.%")
     name: Option<String>,
@@ -104,14 +192,6 @@ pub struct LsCommand {
     /// show only encrypted files
     encrypted: Option<bool>,
 
-    #[arg(long)]
-    /// order by a column
-    order_by: Option<OrderCol>,
-
-    #[arg(long)]
-    /// order in which given column should be ordered by
-    order: Option<Order>,
-
     #[arg(long)]
     /// show files in trash bin (these files are permanently deleted after ~7 days)
     trash: bool,
@@ -120,12 +200,41 @@ pub struct LsCommand {
     filters: CliColFilters,
 }
 
+#[derive(Parser)]
+pub struct FindCommand {
+    /// path of the remote directory to search in, along with its nested directories. defaults
+    /// to currently selected WD
+    dirpath: Option<String>,
+
+    #[arg(long)]
+    /// search only this many levels deep, 1 searches only the directory itself
+    max_depth: Option<usize>,
+
+    #[command(flatten)]
+    file_filters: FileFilterArgs,
+}
+
+#[derive(Parser)]
+pub struct DuCommand {
+    /// path of the remote directory to report on, defaults to currently selected WD
+    dirpath: Option<String>,
+
+    #[arg(long)]
+    /// report nested directories only this many levels deep, 0 reports only the total
+    max_depth: Option<usize>,
+
+    #[arg(long)]
+    /// also report the size & count of files in the trash bin, separately from the others
+    trash: bool,
+}
+
 #[derive(Parser)]
 pub struct PwdCommand;
 
 #[derive(Parser)]
 pub struct UrlCommand {
-    /// path to file, can be relative to currently set WD or can be absolute starting with "/"
+    /// path to file, can be relative to currently set WD or can be absolute starting with "/".
+    /// a pattern (e.g. "*.pdf") prints a url for every matching file
     path: String,
 
     #[command(flatten)]
@@ -145,57 +254,313 @@ pub struct TouchCommand {
     exp_input: Option<shared_types::CmdExpiryParams>,
 }
 
-#[derive(Serialize, Debug)]
-struct Req<'a> {
-    path: &'a str,
+impl TreeCommand {
+    /// the files are only listed when needed, otherwise the counts come along with the dirtree
+    fn needs_listings(&self) -> bool {
+        self.files || self.cumulative || self.pattern.is_some()
+    }
+
+    /// `subtree` at `path` with its counts, sizes & files. also returns the total count of the
+    /// (matching) files in it & its nested directories, to prune the branches without any
+    fn get_tree_output(
+        &self,
+        subtree: &DirTree,
+        path: &str,
+        file_counts: &HashMap<String, u32>,
+        listings: Option<&HashMap<String, Vec<FsFile>>>,
+        pattern: Option<&Regex>,
+        level: i16,
+    ) -> (TreeOutput, u64) {
+        let files: Option<Vec<FsFile>> = listings.map(|listings| {
+            listings
+                .get(path)
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter(|file| match pattern {
+                            Some(pattern) => {
+                                pattern.is_match(&utils::dirtree::join_paths(&[path, &file.name]))
+                            }
+                            None => true,
+                        })
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        let children: Vec<(TreeOutput, u64)> = subtree
+            .children
+            .iter()
+            .map(|child| {
+                self.get_tree_output(
+                    child,
+                    &utils::dirtree::join_paths(&[path, &child.name]),
+                    file_counts,
+                    listings,
+                    pattern,
+                    level.saturating_sub(1),
+                )
+            })
+            .filter(|(_, total_count)| pattern.is_none() || *total_count > 0)
+            .collect();
+
+        let mut file_count = match &files {
+            Some(files) => files.len() as u64,
+            None => *file_counts.get(&subtree.id).unwrap_or(&0) as u64,
+        };
+        let mut size = files
+            .as_ref()
+            .map(|files| files.iter().map(|file| file.file_size as u64).sum::<u64>());
+        let total_count = file_count + children.iter().map(|(_, count)| count).sum::<u64>();
+        if self.cumulative {
+            file_count = total_count;
+            size = size.map(|size| {
+                size + children
+                    .iter()
+                    .filter_map(|(child, _)| child.size)
+                    .sum::<u64>()
+            });
+        }
+
+        let node = TreeOutput {
+            id: subtree.id.clone(),
+            name: subtree.name.clone(),
+            path: path.to_string(),
+            file_count,
+            size,
+            files: files.filter(|_| self.files),
+            children: match level > 0 {
+                true => children.into_iter().map(|(child, _)| child).collect(),
+                false => vec![],
+            },
+        };
+
+        (node, total_count)
+    }
+
+    /// layout picked with `--format`, or the one matching the global `--output` flag
+    fn get_format(&self) -> TreeFormat {
+        match self.format {
+            Some(format) => format,
+            None if output::is_text() => TreeFormat::Text,
+            None => TreeFormat::Json,
+        }
+    }
+
+    /// `node` as a dirtree to print, along with the counts, sizes & files of its directories by
+    /// id
+    fn get_print_dir_tree(
+        node: &TreeOutput,
+        file_counts: &mut HashMap<String, u32>,
+        sizes: &mut HashMap<String, u64>,
+        files: &mut HashMap<String, Vec<FsFile>>,
+    ) -> DirTree {
+        file_counts.insert(node.id.clone(), node.file_count as u32);
+        if let Some(size) = node.size {
+            sizes.insert(node.id.clone(), size);
+        }
+        if let Some(node_files) = &node.files {
+            files.insert(node.id.clone(), node_files.clone());
+        }
+
+        DirTree {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            children: node
+                .children
+                .iter()
+                .map(|child| Self::get_print_dir_tree(child, file_counts, sizes, files))
+                .collect(),
+        }
+    }
+
+    fn get_text(&self, node: &TreeOutput, cwd: &str) -> String {
+        let mut file_counts = HashMap::new();
+        let mut sizes = HashMap::new();
+        let mut files = HashMap::new();
+        let dirtree = Self::get_print_dir_tree(node, &mut file_counts, &mut sizes, &mut files);
+
+        let mut opts = utils::dirtree::PrintDirTreeOpts::get_default_opts();
+        opts.file_counts = Some(&file_counts);
+        opts.sizes = node.size.map(|_| &sizes);
+        opts.files = node.files.as_ref().map(|_| &files);
+        opts.print_note = !self.cumulative;
+        opts.cwd_dir_path = cwd;
+        opts.dir_path = &node.path;
+
+        dirtree.print_dir_tree(&opts)
+    }
+
+    fn get_stats_str(node: &TreeOutput) -> String {
+        let file_count = match node.file_count {
+            1 => String::from("1 file"),
+            count => format!("{count} files"),
+        };
+        match node.size {
+            Some(size) => format!("{file_count}, {}", x2str::bytes2str(size)),
+            None => file_count,
+        }
+    }
+
+    fn escape_dot(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    fn get_dot(node: &TreeOutput, res: &mut String) {
+        let path = Self::escape_dot(&node.path);
+        res.push_str(&format!(
+            "  \"{path}\" [label=\"{}/\\n{}\"];\n",
+            Self::escape_dot(&node.name),
+            Self::get_stats_str(node)
+        ));
+        for file in node.files.iter().flatten() {
+            let filepath = Self::escape_dot(&utils::dirtree::join_paths(&[&node.path, &file.name]));
+            res.push_str(&format!(
+                "  \"{filepath}\" [shape=note, label=\"{}\\n{}\"];\n",
+                Self::escape_dot(&file.name),
+                x2str::bytes2str(file.file_size as u64)
+            ));
+            res.push_str(&format!("  \"{path}\" -> \"{filepath}\";\n"));
+        }
+        for child in &node.children {
+            res.push_str(&format!(
+                "  \"{path}\" -> \"{}\";\n",
+                Self::escape_dot(&child.path)
+            ));
+            Self::get_dot(child, res);
+        }
+    }
+
+    /// inline code span of `value`, fenced with more backticks than any run of them inside it
+    fn get_markdown_code(value: &str) -> String {
+        let longest_run = value
+            .split(|c| c != '`')
+            .map(|run| run.len())
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run + 1);
+        match longest_run {
+            0 => format!("{fence}{value}{fence}"),
+            _ => format!("{fence} {value} {fence}"),
+        }
+    }
+
+    fn get_markdown(node: &TreeOutput, indent: usize, res: &mut String) {
+        let padding = " ".repeat(indent);
+        res.push_str(&format!(
+            "{padding}- **{}** ({})\n",
+            Self::get_markdown_code(&format!("{}/", node.name)),
+            Self::get_stats_str(node)
+        ));
+        for file in node.files.iter().flatten() {
+            res.push_str(&format!(
+                "{padding}  - {} ({})\n",
+                Self::get_markdown_code(&file.name),
+                x2str::bytes2str(file.file_size as u64)
+            ));
+        }
+        for child in &node.children {
+            Self::get_markdown(child, indent + 2, res);
+        }
+    }
 }
 
 impl CliSubCmd for TreeCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
-        let res = api::dirtree::get_dirtree()
+        let res = api::dirtree::get_dirtree(&get_client()?)
             .await
-            .expect("Unexpected error occured while fetching dirtree!");
-
-        let mut opts = utils::dirtree::PrintDirTreeOpts::get_default_opts();
-        opts.file_counts = Some(&res.file_counts);
-        opts.level = self.level.unwrap_or(i16::MAX);
-        opts.print_note = true;
-        opts.cwd_dir_path = wd;
+            .context("Unexpected error occured while fetching dirtree!")?;
 
         let dirpath = match &self.dirpath {
             Some(path) => path.as_str(),
             None => wd,
         };
-        let abs_path = utils::dirtree::get_absolute_path(dirpath, wd);
+        let abs_path = match utils::dirtree::get_absolute_path(dirpath, wd).trim_end_matches('/') {
+            "" => String::from("/"),
+            abs_path => abs_path.to_string(),
+        };
         let subtree = match res.dirtree.get_sub_tree(&abs_path) {
             Some(dirtree) => dirtree,
             None => {
-                println!(
+                return Err(SfsError::NotFound(format!(
                     "Invalid path '{}' provided, no path matching the given path exists!",
                     abs_path
-                );
-                return;
+                ))
+                .into());
+            }
+        };
+
+        let pattern = match &self.pattern {
+            Some(pattern) => {
+                let pattern = utils::dirtree::get_absolute_path(pattern, &abs_path);
+                Some(
+                    paths::get_remote_pattern_regex(&pattern)
+                        .map_err(|_| SfsError::Usage(format!("invalid pattern '{pattern}'!")))?,
+                )
             }
+            None => None,
+        };
+        let listings: Option<HashMap<String, Vec<FsFile>>> = match self.needs_listings() {
+            true => {
+                let dir_paths = subtree.get_dir_paths(&abs_path);
+                let spinner = ProgressBar::new_spinner()
+                    .with_message(format!("listing {} directories", dir_paths.len()));
+                spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+                let listings = api::fs_files::get_all_files_in_dirs(&get_client()?, dir_paths)
+                    .await
+                    .context("error occured while listing remote files!")?;
+                spinner.finish_and_clear();
+
+                Some(listings.into_iter().collect())
+            }
+            false => None,
         };
 
-        println!("Directory tree ({}):", abs_path);
-        println!("{}", subtree.print_dir_tree(&opts));
+        let (tree, _) = self.get_tree_output(
+            subtree,
+            &abs_path,
+            &res.file_counts,
+            listings.as_ref(),
+            pattern.as_ref(),
+            self.level.unwrap_or(i16::MAX),
+        );
+
+        let mut res = String::new();
+        match self.get_format() {
+            TreeFormat::Text => {
+                println!("Directory tree ({}):", abs_path);
+                let cwd = match wd.trim_end_matches('/') {
+                    "" => "/",
+                    cwd => cwd,
+                };
+                res.push_str(&self.get_text(&tree, cwd));
+                res.push('\n');
+            }
+            TreeFormat::Json => return output::print_value(&tree),
+            TreeFormat::Dot => {
+                res.push_str("digraph tree {\n  node [shape=folder];\n");
+                Self::get_dot(&tree, &mut res);
+                res.push_str("}\n");
+            }
+            TreeFormat::Markdown => Self::get_markdown(&tree, 0, &mut res),
+        }
+        print!("{res}");
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for MkdirCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
         let abs_path = utils::dirtree::get_absolute_path(&self.dirpath, wd);
 
-        let dirtree = api::dirtree::mkdir(&abs_path)
+        let dirtree = api::dirtree::mkdir(&get_client()?, &abs_path)
             .await
-            .expect("error occured while calling 'mkdir' api!");
+            .context("error occured while calling 'mkdir' api!")?;
 
         let subtree = dirtree.get_sub_tree(wd).unwrap_or(&dirtree);
 
@@ -204,116 +569,96 @@ impl CliSubCmd for MkdirCommand {
 
         println!("Directory tree (/{}):", subtree.name);
         println!("{}", subtree.print_dir_tree(&print_dirtree_opts));
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for RmdirCommand {
-    async fn run(&self) {
-        let mut url = api::get_base_url().expect("config issue, cannot fetch base url");
-        url.set_path("fs/rmdir");
-
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
-
-        let req = Req {
-            path: &utils::dirtree::get_absolute_path(&self.dirpath, wd),
-        };
-        let res = api::get_builder(reqwest::Method::POST, url)
-            .expect("error occured while building request")
-            .json(&req)
-            .send()
-            .await
-            .expect("error occured while sending request");
-
-        if !res.status().is_success() {
-            println!(
-                "{}",
-                String::from("Error occured while removing directory!").red()
-            );
-            println!("{}", res.text().await.unwrap().bright_black());
-            return;
-        }
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
-        let res_data: ApiResponse<DirTree> = res.json().await.unwrap();
-        let dirtree = res_data.data.unwrap();
+        let dirtree = api::dirtree::rmdir(
+            &get_client()?,
+            &utils::dirtree::get_absolute_path(&self.dirpath, wd),
+        )
+        .await
+        .context("Error occured while removing directory!")?;
 
         let mut print_dirtree_opts = utils::dirtree::PrintDirTreeOpts::get_default_opts();
         print_dirtree_opts.cwd_dir_path = wd;
 
         println!("Directory tree (/):");
         println!("{}", dirtree.print_dir_tree(&print_dirtree_opts));
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for MvdirCommand {
-    async fn run(&self) {
-        let mut url = api::get_base_url().expect("config issue, cannot fetch base url");
-        url.set_path("fs/mvdir");
-
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
-
-        #[derive(Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct Req<'a> {
-            path: &'a str,
-            new_path: &'a str,
-        }
-        let req = Req {
-            path: &utils::dirtree::get_absolute_path(&self.dirpath, wd),
-            new_path: &utils::dirtree::get_absolute_path(&self.new_dirpath, wd),
-        };
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
-        let res = api::get_builder(reqwest::Method::POST, url)
-            .expect("error occured while building request")
-            .json(&req)
-            .send()
-            .await
-            .expect("error occured while sending request");
-
-        let res_data: ApiResponse<DirTree> = res.json().await.unwrap();
-        let dirtree = res_data.data.unwrap();
+        let dirtree = api::dirtree::mvdir(
+            &get_client()?,
+            &utils::dirtree::get_absolute_path(&self.dirpath, wd),
+            &utils::dirtree::get_absolute_path(&self.new_dirpath, wd),
+        )
+        .await
+        .context("Error occured while moving directory!")?;
 
         let mut print_dirtree_opts = utils::dirtree::PrintDirTreeOpts::get_default_opts();
         print_dirtree_opts.cwd_dir_path = wd;
 
         println!("Directory tree (/):");
         println!("{}", dirtree.print_dir_tree(&print_dirtree_opts));
+
+        Ok(())
     }
 }
 
-impl CliSubCmd for Cd {
-    async fn run(&self) {
-        let res = api::dirtree::get_dirtree()
-            .await
-            .expect("Unexpected error occured while fetching dirtree!");
-
+impl Cd {
+    /// `dirtree` is fetched again by `run`, the shell passes in its cached one
+    pub fn change_wd(&self, dirtree: &DirTree) -> anyhow::Result<()> {
         let state = STATE.read().unwrap();
         let wd = state.get_wd();
 
         let dirpath = &utils::dirtree::get_absolute_path(&self.dirpath, wd);
-        let sub_dirtree = res.dirtree.get_sub_tree(dirpath);
+        let sub_dirtree = dirtree.get_sub_tree(dirpath);
         if sub_dirtree.is_none() {
-            println!("{}", String::from("Path does not exists!").red());
-            return;
+            return Err(SfsError::NotFound(String::from("Path does not exists!")).into());
         }
 
         drop(state);
 
         let mut state_mut = STATE.write().unwrap();
         state_mut
-            .set_wd(&dirpath)
-            .expect("Error occured while setting a working directory!");
+            .set_wd(dirpath)
+            .context("Error occured while setting a working directory!")?;
+
+        Ok(())
+    }
+}
+
+impl CliSubCmd for Cd {
+    async fn run(&self) -> anyhow::Result<()> {
+        let res = api::dirtree::get_dirtree(&get_client()?)
+            .await
+            .context("Unexpected error occured while fetching dirtree!")?;
+
+        self.change_wd(&res.dirtree)
     }
 }
 
 impl CliSubCmd for PwdCommand {
-    async fn run(&self) {
+    async fn run(&self) -> anyhow::Result<()> {
         let state = STATE.write().expect(
             "failed to acquire lock over config, THIS SHOULD NOT HAPPEN, PLEASE REPORT BUG!!",
         );
 
         println!("{}", state.get_wd());
+
+        Ok(())
     }
 }
 
@@ -328,24 +673,18 @@ impl LsCommand {
     }
 }
 
-impl CliSubCmd for LsCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
-
-        let dirpath = match &self.dirpath {
-            Some(dirpath) => utils::dirtree::get_absolute_path(dirpath, wd),
-            None => wd.to_string(),
-        };
-
+impl FileFilterArgs {
+    fn get_filter_groups(&self) -> anyhow::Result<Vec<FilterGroup>> {
         let mut filters: Vec<FilterGroup> = vec![];
         let mut main_and_group = FilterGroup {
             type_: FilterGroupType::And,
             filters: vec![],
         };
-        main_and_group
-            .filters
-            .extend(self.filters.parse_get_filters().expect("invalid filters!"));
+        main_and_group.filters.extend(
+            self.filters
+                .parse_get_filters()
+                .context("invalid filters!")?,
+        );
         if self.trash {
             main_and_group.filters.push(Filter(
                 FilterCol::DeletedAt,
@@ -429,6 +768,21 @@ impl CliSubCmd for LsCommand {
         }
         filters.push(main_and_group);
 
+        Ok(filters)
+    }
+}
+
+impl CliSubCmd for LsCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let dirpath = match &self.dirpath {
+            Some(dirpath) => utils::dirtree::get_absolute_path(dirpath, wd),
+            None => wd.to_string(),
+        };
+
+        let filters = self.file_filters.get_filter_groups()?;
+
         let get_file_opts = GetFilesOpts {
             dir_path: dirpath.clone(),
             filters: Some(filters),
@@ -437,13 +791,17 @@ impl CliSubCmd for LsCommand {
             order_by: self.order_by,
             order: self.order,
         };
-        let res = api::fs_files::get_files(Some(get_file_opts))
+        let res = api::fs_files::get_files(&get_client()?, Some(get_file_opts))
             .await
-            .expect("error occured while fetching fetching files");
+            .context("error occured while fetching fetching files")?;
+
+        if !output::is_text() {
+            return output::print_list(&res.files);
+        }
 
-        if res.files.len() == 0 {
+        if res.files.is_empty() {
             println!("{}", "no results found.".to_string().bold());
-            return;
+            return Ok(());
         }
 
         let mut pretty_file_sizes: Vec<String> = vec![];
@@ -471,6 +829,10 @@ impl CliSubCmd for LsCommand {
                 true => "🌐",
                 false => "",
             };
+            emo_tags += match file.compression.is_some() {
+                true => "📦",
+                false => "",
+            };
 
             let cache_ttl_str =
                 Self::get_file_cache_duration_str(file.cache_max_age_seconds).dimmed();
@@ -493,6 +855,15 @@ impl CliSubCmd for LsCommand {
             );
             print!("{0:>1$} ", pretty_file_size.bold(), file_size_padding);
             print!("{0:>1$} ", file.get_filetype(), file_type_padding);
+            print!(
+                "{0:<1$} ",
+                file.checksum
+                    .as_deref()
+                    .map(|c| &c[..constants::SHORT_CHECKSUM_LEN.min(c.len())])
+                    .unwrap_or("-")
+                    .dimmed(),
+                constants::SHORT_CHECKSUM_LEN
+            );
             print!("{} ", file.name.bold().cyan());
             print!("{} ", emo_tags);
             println!();
@@ -503,8 +874,8 @@ impl CliSubCmd for LsCommand {
 
         println!();
         println!(
-            "{} {} {}",
-            format!("(page: {})", page),
+            "(page: {}) {} {}",
+            page,
             format!(
                 "showing {}-{} of {} in",
                 offset + 1,
@@ -514,101 +885,485 @@ impl CliSubCmd for LsCommand {
             .dimmed(),
             dirpath.bold()
         );
+
+        Ok(())
+    }
+}
+
+impl CliSubCmd for FindCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let dirpath = match &self.dirpath {
+            Some(dirpath) => utils::dirtree::get_absolute_path(dirpath, wd),
+            None => wd.to_string(),
+        };
+        let filters = self.file_filters.get_filter_groups()?;
+
+        let res = api::dirtree::get_dirtree(&get_client()?)
+            .await
+            .context("error occured while fetching dirtree!")?;
+        let subtree = res
+            .dirtree
+            .get_sub_tree(&dirpath)
+            .ok_or(SfsError::NotFound(format!(
+                "remote directory '{dirpath}' does not exist!"
+            )))?;
+
+        // files directly in `dirpath` are 1 level deep
+        let base_depth = dirpath.trim_end_matches('/').matches('/').count();
+        let dir_paths: Vec<String> = subtree
+            .get_dir_paths(&dirpath)
+            .into_iter()
+            .filter(|path| match self.max_depth {
+                Some(max_depth) => {
+                    path.trim_end_matches('/').matches('/').count() - base_depth < max_depth
+                }
+                None => true,
+            })
+            .collect();
+
+        let spinner = ProgressBar::new_spinner()
+            .with_message(format!("searching {} directories", dir_paths.len()));
+        spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+        let listings =
+            api::fs_files::get_filtered_files_in_dirs(&get_client()?, dir_paths, Some(filters))
+                .await
+                .context("error occured while searching remote files!")?;
+        spinner.finish_and_clear();
+
+        let mut results: Vec<FindOutput> = listings
+            .into_iter()
+            .flat_map(|(dirpath, files)| {
+                files.into_iter().map(move |file| FindOutput {
+                    path: utils::dirtree::join_paths(&[&dirpath, &file.name]),
+                    file,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if !output::is_text() {
+            return output::print_list(&results);
+        }
+        if results.is_empty() {
+            println!("{}", "no results found.".to_string().bold());
+            return Ok(());
+        }
+
+        for result in results {
+            let (dirpath, filename) = utils::dirtree::split_path(&result.path);
+            println!("{}{}", dirpath.dimmed(), filename.bold());
+        }
+
+        Ok(())
+    }
+}
+
+impl DuCommand {
+    /// size & count of the files directly in each directory
+    async fn get_dir_usages(
+        dir_paths: &[String],
+        filters: Option<Vec<FilterGroup>>,
+    ) -> anyhow::Result<BTreeMap<String, (u64, u64)>> {
+        let listings =
+            api::fs_files::get_filtered_files_in_dirs(&get_client()?, dir_paths.to_vec(), filters)
+                .await
+                .context("error occured while listing remote files!")?;
+
+        Ok(listings
+            .into_iter()
+            .map(|(dirpath, files)| {
+                let size = files.iter().map(|file| file.file_size as u64).sum();
+                (dirpath, (size, files.len() as u64))
+            })
+            .collect())
+    }
+
+    /// totals of the directory along with its nested directories, these are sorted right after
+    /// `{dirpath}/` so only they are visited
+    fn get_cumulative_usage(usages: &BTreeMap<String, (u64, u64)>, dirpath: &str) -> (u64, u64) {
+        let nested_prefix = format!("{}/", dirpath.trim_end_matches('/'));
+        let nested_usages = usages
+            .range::<str, _>((Bound::Included(nested_prefix.as_str()), Bound::Unbounded))
+            .take_while(|(path, _)| path.starts_with(&nested_prefix))
+            // the root's prefix is its own path
+            .filter(|(path, _)| *path != dirpath)
+            .map(|(_, usage)| usage);
+
+        usages
+            .get(dirpath)
+            .into_iter()
+            .chain(nested_usages)
+            .fold((0, 0), |(size, count), usage| {
+                (size + usage.0, count + usage.1)
+            })
+    }
+}
+
+impl CliSubCmd for DuCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let dirpath = match &self.dirpath {
+            Some(dirpath) => utils::dirtree::get_absolute_path(dirpath, wd),
+            None => wd.to_string(),
+        };
+        let dirpath = match dirpath.trim_end_matches('/') {
+            "" => String::from("/"),
+            dirpath => dirpath.to_string(),
+        };
+
+        let res = api::dirtree::get_dirtree(&get_client()?)
+            .await
+            .context("error occured while fetching dirtree!")?;
+        let subtree = res
+            .dirtree
+            .get_sub_tree(&dirpath)
+            .ok_or(SfsError::NotFound(format!(
+                "remote directory '{dirpath}' does not exist!"
+            )))?;
+        let dir_paths = subtree.get_dir_paths(&dirpath);
+
+        let spinner = ProgressBar::new_spinner()
+            .with_message(format!("listing {} directories", dir_paths.len()));
+        spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+        let usages = Self::get_dir_usages(&dir_paths, None).await?;
+        let trash_usages = match self.trash {
+            true => Some(
+                Self::get_dir_usages(
+                    &dir_paths,
+                    Some(vec![FilterGroup {
+                        type_: FilterGroupType::And,
+                        filters: vec![Filter(FilterCol::DeletedAt, FilterOp::Ne, json!(null))],
+                    }]),
+                )
+                .await?,
+            ),
+            false => None,
+        };
+        spinner.finish_and_clear();
+
+        let base_depth = dirpath.trim_end_matches('/').matches('/').count();
+        let mut rows: Vec<DuOutput> = dir_paths
+            .iter()
+            .filter(|path| match self.max_depth {
+                Some(max_depth) => {
+                    path.trim_end_matches('/').matches('/').count() - base_depth <= max_depth
+                }
+                None => true,
+            })
+            .map(|path| {
+                let (size, file_count) = Self::get_cumulative_usage(&usages, path);
+                let trash_usage = trash_usages
+                    .as_ref()
+                    .map(|trash_usages| Self::get_cumulative_usage(trash_usages, path));
+
+                DuOutput {
+                    path: path.clone(),
+                    size,
+                    file_count,
+                    trash_size: trash_usage.map(|(size, _)| size),
+                    trash_file_count: trash_usage.map(|(_, count)| count),
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+        if !output::is_text() {
+            return output::print_list(&rows);
+        }
+
+        let pretty_rows: Vec<(String, String, String)> = rows
+            .iter()
+            .map(|row| {
+                let trash = match (row.trash_size, row.trash_file_count) {
+                    (Some(size), Some(count)) => {
+                        format!("trash: {} in {count} files", x2str::bytes2str(size))
+                    }
+                    _ => String::new(),
+                };
+                (
+                    x2str::bytes2str(row.size),
+                    format!("{} files", row.file_count),
+                    trash,
+                )
+            })
+            .collect();
+        let size_padding = pretty_rows.iter().fold(0, |acc, row| acc.max(row.0.len()));
+        let count_padding = pretty_rows.iter().fold(0, |acc, row| acc.max(row.1.len()));
+        let trash_padding = pretty_rows.iter().fold(0, |acc, row| acc.max(row.2.len()));
+
+        for (row, (size, count, trash)) in rows.iter().zip(pretty_rows) {
+            println!(
+                "{}  {}  {}{}",
+                format!("{size:>size_padding$}").bold(),
+                format!("{count:>count_padding$}").dimmed(),
+                match trash.is_empty() {
+                    true => String::new(),
+                    false => format!("{}  ", format!("{trash:<trash_padding$}").red()),
+                },
+                row.path.cyan()
+            );
+        }
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for RmCommand {
-    async fn run(&self) {
-        if self.filenames.len() == 0 {
+    async fn run(&self) -> anyhow::Result<()> {
+        if self.filenames.is_empty() {
             println!("{}", String::from("no file names provided.").red());
         }
 
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
-        let res = api::dirtree::get_dirtree()
+        let res = api::dirtree::get_dirtree(&get_client()?)
             .await
-            .expect("error occured while fetching dirtree!");
+            .context("error occured while fetching dirtree!")?;
 
         let dirpath = match &self.dirpath {
             Some(dirpath) => {
                 let abs_path = utils::dirtree::get_absolute_path(dirpath, wd);
                 res.dirtree
                     .get_sub_tree(&abs_path)
-                    .expect("provided dirpath does not exist!");
+                    .ok_or(SfsError::NotFound(format!(
+                        "remote directory '{abs_path}' does not exist!"
+                    )))?;
 
                 dirpath
             }
             None => {
                 res.dirtree
                     .get_sub_tree(wd)
-                    .expect("current WD incorrectly set, please switch to a valid WD!");
+                    .context("current WD incorrectly set, please switch to a valid WD!")?;
 
                 wd
             }
         };
 
-        let deleted_files = api::fs_files::delete_files(&api::fs_files::DeleteFilesReqBody {
-            dir_path: dirpath,
-            file_names: &self.filenames,
-        })
-        .await
-        .expect("error occured while deleteing files!");
+        if !self.filenames.iter().any(|name| paths::is_pattern(name)) {
+            let deleted_files = api::fs_files::delete_files(
+                &get_client()?,
+                &api::fs_files::DeleteFilesReqBody {
+                    dir_path: dirpath,
+                    file_names: &self.filenames,
+                },
+            )
+            .await
+            .context("error occured while deleteing files!")?;
+
+            println!(
+                "{}",
+                format!("Deleted {} files successfully.", deleted_files.len()).bold()
+            );
+            return Ok(());
+        }
+
+        let abs_dirpath = utils::dirtree::get_absolute_path(dirpath, wd);
+        let mut deletions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for filename in &self.filenames {
+            let path = utils::dirtree::get_absolute_path(filename, &abs_dirpath);
+            let paths = match paths::is_pattern(filename) {
+                true => get_pattern_matches(&get_client()?, &path)
+                    .await?
+                    .into_iter()
+                    .map(|(path, _)| path)
+                    .collect(),
+                false => vec![path],
+            };
+
+            for path in paths {
+                let (dirpath, filename) = utils::dirtree::split_path(&path);
+                deletions
+                    .entry(dirpath.to_string())
+                    .or_default()
+                    .insert(filename.to_string());
+            }
+        }
+
+        let paths: Vec<String> = deletions
+            .iter()
+            .flat_map(|(dirpath, filenames)| {
+                filenames
+                    .iter()
+                    .map(|filename| utils::dirtree::join_paths(&[dirpath, filename]))
+            })
+            .collect();
+        if !confirm_pattern_matches(&paths, "deleted", self.no_confirm)? {
+            println!("Aborted deletion.");
+            return Ok(());
+        }
+
+        let mut n_deleted = 0;
+        for (dirpath, filenames) in deletions {
+            n_deleted += api::fs_files::delete_files(
+                &get_client()?,
+                &api::fs_files::DeleteFilesReqBody {
+                    dir_path: &dirpath,
+                    file_names: &filenames.into_iter().collect(),
+                },
+            )
+            .await
+            .with_context(|| format!("error occured while deleteing files in '{dirpath}'!"))?
+            .len();
+        }
 
         println!(
             "{}",
-            format!("Deleted {} files successfully.", deleted_files.len()).bold()
-        )
+            format!("Deleted {} files successfully.", n_deleted).bold()
+        );
+
+        Ok(())
     }
 }
 
-impl CliSubCmd for UrlCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+/// files matching a remote pattern, errors out if there are none
+pub async fn get_pattern_matches(
+    client: &SfsClient,
+    pattern: &str,
+) -> anyhow::Result<Vec<(String, FsFile)>> {
+    let matches = api::fs_files::get_files_from_pattern(client, pattern)
+        .await
+        .context("error occured while matching remote files!")?;
+    if matches.is_empty() {
+        return Err(SfsError::NotFound(format!("no remote files match '{pattern}'!")).into());
+    }
 
-        let abs_path = utils::dirtree::get_absolute_path(&self.path, wd);
-        let (dirpath, filename) = utils::dirtree::split_path(&abs_path);
+    Ok(matches)
+}
 
-        let filters = FilterGroup {
-            type_: FilterGroupType::And,
-            filters: vec![Filter(FilterCol::Name, FilterOp::Eq, json!(filename))],
-        };
-        let mut opts = GetFilesOpts::new(dirpath.to_string());
-        opts.filters = Some(vec![filters]);
+/// prints the paths matched by a pattern and asks before they get `action` (e.g. "deleted"),
+/// returns false if the user declined
+pub fn confirm_pattern_matches(
+    paths: &[String],
+    action: &str,
+    no_confirm: bool,
+) -> anyhow::Result<bool> {
+    println!();
+    for path in paths {
+        println!("{}", path.dimmed().blue());
+    }
+    println!();
 
-        let res_files = api::fs_files::get_files(Some(opts))
-            .await
-            .expect("error fetching file!");
+    if no_confirm {
+        return Ok(true);
+    }
+    prompts::ensure_interactive("a confirmation", "use --no-confirm to skip it")?;
 
-        let file = res_files.files.first().expect("no file found!");
+    Confirm::new(&format!("{} files will be {action}, confirm:", paths.len()))
+        .with_default(false)
+        .prompt()
+        .context("error occured while displaying confirm prompt!")
+}
 
+impl UrlCommand {
+    /// url of the file, with a new read-only access token unless the file is public
+    async fn get_share_url(
+        &self,
+        file: &FsFile,
+        abs_path: &str,
+    ) -> anyhow::Result<(url::Url, Option<String>)> {
         let access_token: Option<String> = match file.is_public {
             true => None,
             false => {
                 let perms: AccessTokenPermission = "r"
                     .parse()
-                    .expect("error occured while generating permissions for access token!");
-                let acpl = vec![utils::tokens::get_acp(perms, &abs_path)];
+                    .context("error occured while generating permissions for access token!")?;
+                let acpl = vec![utils::tokens::get_acp(perms, abs_path)];
 
-                let res_data =
-                    api::tokens::generate_access_token(&acpl, &self.exp_input.get_expires_at())
-                        .await
-                        .expect("error occured while generating access token!");
+                let res_data = api::tokens::generate_access_token(
+                    &get_client()?,
+                    &acpl,
+                    &self.exp_input.get_expires_at(),
+                )
+                .await
+                .context("error occured while generating access token!")?;
 
                 Some(res_data.access_token)
             }
         };
 
-        let share_url =
-            files::get_share_url(access_token.as_deref(), &file.storage_id).expect(&format!(
-                "error generating shareable url for specified path '{}'",
-                abs_path
-            ));
+        let share_url = files::get_share_url(access_token.as_deref(), &file.storage_id)
+            .with_context(|| {
+                format!(
+                    "error generating shareable url for specified path '{}'",
+                    abs_path
+                )
+            })?;
+
+        Ok((share_url, access_token))
+    }
+
+    /// one url per file matching the pattern, each with its own access token
+    async fn print_share_urls(&self, pattern: &str) -> anyhow::Result<()> {
+        let mut share_urls = vec![];
+        for (path, file) in get_pattern_matches(&get_client()?, pattern).await? {
+            let (share_url, access_token) = self.get_share_url(&file, &path).await?;
+            let mut share_url =
+                ShareUrlOutput::new(share_url.to_string(), access_token.as_deref())?;
+            share_url.path = Some(path);
+
+            share_urls.push(share_url);
+        }
+        if !output::is_text() {
+            return output::print_value(&share_urls);
+        }
+
+        let padding = share_urls.iter().fold(0, |acc, share_url| {
+            acc.max(share_url.path.as_deref().unwrap_or_default().len())
+        });
+        for share_url in share_urls {
+            println!(
+                "{0:<1$}  {2}",
+                share_url.path.unwrap_or_default(),
+                padding,
+                share_url.url.bold().cyan()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl CliSubCmd for UrlCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let abs_path = utils::dirtree::get_absolute_path(&self.path, wd);
+        if paths::is_pattern(&self.path) {
+            return self.print_share_urls(&abs_path).await;
+        }
+        let (dirpath, filename) = utils::dirtree::split_path(&abs_path);
+
+        let filters = FilterGroup {
+            type_: FilterGroupType::And,
+            filters: vec![Filter(FilterCol::Name, FilterOp::Eq, json!(filename))],
+        };
+        let mut opts = GetFilesOpts::new(dirpath.to_string());
+        opts.filters = Some(vec![filters]);
+
+        let res_files = api::fs_files::get_files(&get_client()?, Some(opts))
+            .await
+            .context("error fetching file!")?;
+
+        let file = res_files.files.first().ok_or(SfsError::NotFound(format!(
+            "no file found at path '{abs_path}'!"
+        )))?;
+
+        let (share_url, access_token) = self.get_share_url(file, &abs_path).await?;
+        if !output::is_text() {
+            return output::print_value(&ShareUrlOutput::new(
+                share_url.to_string(),
+                access_token.as_deref(),
+            )?);
+        }
+
         println!("{}", share_url.to_string().bold().cyan());
 
         if let Some(token) = access_token {
-            let access_token: AccessToken = token.parse().expect("generate access token seems invalid! this SHOULD NOT HAPPEN!!! please report this bug.");
+            let access_token: AccessToken = token.parse().context("generate access token seems invalid! this SHOULD NOT HAPPEN!!! please report this bug.")?;
 
             println!();
             println!(
@@ -628,41 +1383,284 @@ impl CliSubCmd for UrlCommand {
                 format!("acpl: {}", access_token.acpl.join(", ").blue()).dimmed()
             );
         }
+
+        Ok(())
+    }
+}
+
+impl MvCommand {
+    /// files matching the pattern are moved into the `new_dirpath` directory
+    async fn mv_matches(&self, pattern: &str, new_dirpath: &str) -> anyhow::Result<()> {
+        let matches = get_pattern_matches(&get_client()?, pattern).await?;
+
+        let mut filenames = BTreeSet::new();
+        for (path, file) in &matches {
+            if !filenames.insert(file.name.as_str()) {
+                return Err(SfsError::Usage(format!(
+                    "more than one matched file is named '{}' (e.g. '{path}'), they can't be moved into the same directory!",
+                    file.name
+                ))
+                .into());
+            }
+        }
+
+        let paths: Vec<String> = matches.iter().map(|(path, _)| path.clone()).collect();
+        if !confirm_pattern_matches(
+            &paths,
+            &format!("moved into '{new_dirpath}'"),
+            self.no_confirm,
+        )? {
+            println!("Aborted move.");
+            return Ok(());
+        }
+
+        for (path, file) in &matches {
+            api::dirtree::mv(
+                &get_client()?,
+                &MvOpts {
+                    file_path: path,
+                    new_file_path: &utils::dirtree::join_paths(&[new_dirpath, &file.name]),
+                },
+            )
+            .await
+            .with_context(|| format!("error occured while moving file '{path}'"))?;
+        }
+
+        println!(
+            "{}",
+            format!("Moved {} files into '{new_dirpath}'.", matches.len()).bold()
+        );
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for MvCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        if paths::is_pattern(&self.filepath) {
+            return self
+                .mv_matches(
+                    &utils::dirtree::get_absolute_path(&self.filepath, wd),
+                    &utils::dirtree::get_absolute_path(&self.new_filepath, wd),
+                )
+                .await;
+        }
 
-        let _ = api::dirtree::mv(&MvOpts {
-            file_path: &utils::dirtree::get_absolute_path(&self.filepath, wd),
-            new_file_path: &utils::dirtree::get_absolute_path(&self.new_filepath, wd),
-        })
+        let _ = api::dirtree::mv(
+            &get_client()?,
+            &MvOpts {
+                file_path: &utils::dirtree::get_absolute_path(&self.filepath, wd),
+                new_file_path: &utils::dirtree::get_absolute_path(&self.new_filepath, wd),
+            },
+        )
         .await
-        .expect(&format!(
-            "error occured while moving file from '{}' to '{}'",
-            self.filepath, self.new_filepath
-        ));
+        .with_context(|| {
+            format!(
+                "error occured while moving file from '{}' to '{}'",
+                self.filepath, self.new_filepath
+            )
+        })?;
 
         println!("new file location: {}", self.new_filepath.bold());
+
+        Ok(())
+    }
+}
+
+impl CpCommand {
+    /// client of the file system on one side of the copy & the absolute path on it
+    fn get_side(tag: Option<&str>, path: &str, wd: &str) -> anyhow::Result<(SfsClient, String)> {
+        match tag {
+            Some(tag) => Ok((
+                SfsClient::from_token_tag(tag)?,
+                utils::dirtree::get_absolute_path(path, "/"),
+            )),
+            None => Ok((get_client()?, utils::dirtree::get_absolute_path(path, wd))),
+        }
+    }
+}
+
+impl CliSubCmd for CpCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = STATE.read().unwrap().get_wd().to_string();
+        let (src_client, src_path) = Self::get_side(self.from.as_deref(), &self.src, &wd)?;
+        let (client, dest_path) = Self::get_side(self.to.as_deref(), &self.dest, &wd)?;
+
+        let dest_dirtree = api::dirtree::get_dirtree(&client)
+            .await
+            .context("error occured while fetching destination directory tree!")?
+            .dirtree;
+        let is_dest_dir = dest_dirtree.get_sub_tree(&dest_path).is_some();
+        let dest_root_id = dest_dirtree.id.clone();
+
+        if let Some(file) = api::fs_files::get_file(&src_client, &src_path)
+            .await
+            .context("error occured while fetching remote file!")?
+        {
+            let dest_filepath = match is_dest_dir {
+                true => utils::dirtree::join_paths(&[&dest_path, &file.name]),
+                false => dest_path,
+            };
+            let progress_bar = ProgressBar::new(file.file_size as u64)
+                .with_style(utils::misc::get_sized_throughput_progress_style(None));
+
+            api::uploads::copy_file(
+                &src_client,
+                &file,
+                &client,
+                &dest_filepath,
+                self.force,
+                self.transfer_params.get_rate_limiter(),
+                progress_bar.clone(),
+            )
+            .await
+            .with_context(|| {
+                format!("error occured while copying '{src_path}' to '{dest_filepath}'!")
+            })?;
+            progress_bar.finish_and_clear();
+
+            println!("copied to: {}", dest_filepath.bold());
+            return Ok(());
+        }
+
+        // `--from` & `--to` can name the active token too, what matters is the token in use
+        let src_dirtree = match src_client.get_token() == client.get_token() {
+            true => dest_dirtree,
+            false => {
+                api::dirtree::get_dirtree(&src_client)
+                    .await
+                    .context("error occured while fetching source directory tree!")?
+                    .dirtree
+            }
+        };
+        // tokens of the same file system share its root directory, whatever their ACPLs
+        let is_same_file_system = src_dirtree.id == dest_root_id;
+        let subtree = src_dirtree
+            .get_sub_tree(&src_path)
+            .ok_or(SfsError::NotFound(format!(
+                "no remote file or directory found at '{src_path}'!"
+            )))?;
+        if !self.recursive {
+            return Err(SfsError::Usage(format!(
+                "'{src_path}' is a directory, use -r to copy it along with its contents."
+            ))
+            .into());
+        }
+
+        let dest_dirpath = match is_dest_dir {
+            true => utils::dirtree::join_paths(&[&dest_path, &subtree.name]),
+            false => dest_path,
+        };
+        if is_same_file_system
+            && (dest_dirpath == src_path || dest_dirpath.starts_with(&format!("{src_path}/")))
+        {
+            return Err(SfsError::Usage(format!("cannot copy '{src_path}' into itself!")).into());
+        }
+
+        let spinner = ProgressBar::new_spinner().with_message("listing remote files");
+        spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+        let listings =
+            api::fs_files::get_all_files_in_dirs(&src_client, subtree.get_dir_paths(&src_path))
+                .await
+                .context("error occured while listing remote files!")?;
+
+        let get_dest_path = |path: &str| {
+            utils::dirtree::join_paths(&[
+                &dest_dirpath,
+                path.strip_prefix(&src_path).unwrap_or(path),
+            ])
+        };
+        spinner.set_message("creating directory tree");
+        for (dirpath, _) in &listings {
+            api::dirtree::mkdir(&client, &get_dest_path(dirpath))
+                .await
+                .context("error occured while creating directory!")?;
+        }
+        spinner.finish_and_clear();
+
+        let copies: Vec<(FsFile, String)> = listings
+            .into_iter()
+            .flat_map(|(dirpath, files)| {
+                let dest_dirpath = get_dest_path(&dirpath);
+                files.into_iter().map(move |file| {
+                    let dest_filepath = utils::dirtree::join_paths(&[&dest_dirpath, &file.name]);
+                    (file, dest_filepath)
+                })
+            })
+            .collect();
+        let n_copies = copies.len();
+        let padding = copies
+            .iter()
+            .fold(0, |acc, (_, filepath)| acc.max(filepath.len()));
+
+        let rate_limiter = self.transfer_params.get_rate_limiter();
+        let multi_progress_bar = MultiProgress::new();
+        let results = futures_util::stream::iter(copies.into_iter().map(|(file, filepath)| {
+            let progress_bar =
+                multi_progress_bar.add(ProgressBar::new(file.file_size as u64).with_style(
+                    utils::misc::get_sized_throughput_progress_style(Some(&format!(
+                        "{0:<1$}",
+                        filepath, padding
+                    ))),
+                ));
+            let src_client = &src_client;
+            let client = &client;
+            let rate_limiter = rate_limiter.clone();
+
+            async move {
+                api::uploads::copy_file(
+                    src_client,
+                    &file,
+                    client,
+                    &filepath,
+                    self.force,
+                    rate_limiter,
+                    progress_bar.clone(),
+                )
+                .await
+                .map_err(|err| anyhow!("'{filepath}': {err}"))?;
+                progress_bar.finish();
+
+                anyhow::Ok(())
+            }
+        }))
+        .buffer_unordered(self.transfer_params.get_jobs())
+        .collect::<Vec<_>>()
+        .await;
+
+        let err_msg: String = results
+            .into_iter()
+            .filter_map(|res| res.err())
+            .map(|err| format!("{err}\n"))
+            .collect();
+        if !err_msg.is_empty() {
+            return Err(anyhow!(err_msg)).context("some files failed to copy!");
+        }
+
+        println!(
+            "{}",
+            format!("{n_copies} files copied to '{dest_dirpath}'.").bold()
+        );
+
+        Ok(())
     }
 }
 
 impl CliSubCmd for TouchCommand {
-    async fn run(&self) {
-        let state = &STATE.read().unwrap();
-        let wd = state.get_wd();
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
 
         let abs_path = utils::dirtree::get_absolute_path(&self.path, wd);
         let (dirpath, filename) = utils::dirtree::split_path(&abs_path);
 
-        if let Some(_) = api::fs_files::get_file(&abs_path)
+        if api::fs_files::get_file(&get_client()?, &abs_path)
             .await
-            .expect("error occured while connecting to API!")
+            .context("error occured while connecting to API!")?
+            .is_some()
         {
-            return;
+            return Ok(());
         };
 
         let empty_stream = async_stream::try_stream! {
@@ -679,13 +1677,16 @@ impl CliSubCmd for TouchCommand {
             deleted_at: self.exp_input.as_ref().map(|exp| exp.get_expires_at()),
             encryption: None,
             cache_max_age_seconds: None,
+            checksum: Some(Checksum::default().get_digest()),
+            compression: None,
         };
 
-        api::uploads::upload_blob_stream(empty_stream, &upload_metadata)
+        api::uploads::upload_blob_stream(&get_client()?, empty_stream, &upload_metadata)
             .await
-            .expect(&format!(
-                "error occured while creating file at path '{}'",
-                self.path
-            ));
+            .with_context(|| {
+                format!("error occured while creating file at path '{}'", self.path)
+            })?;
+
+        Ok(())
     }
 }
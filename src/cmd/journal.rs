@@ -0,0 +1,111 @@
+use std::fs;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use sfs_cli::{constants, journal::UploadJournal, utils::x2str};
+
+use crate::cmd::CliSubCmd;
+
+#[derive(Parser)]
+pub struct JournalCommand {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// list journals of interrupted uploads, stale ones can not be resumed
+    Ls,
+
+    /// delete stale journals (changed/missing local file or older than the journal ttl)
+    Clean {
+        #[arg(long)]
+        /// delete all journals, interrupted uploads will have to start over
+        all: bool,
+    },
+}
+
+impl CliSubCmd for JournalCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        match &self.command {
+            Commands::Ls => handle_list_journals(),
+            Commands::Clean { all } => handle_clean_journals(*all),
+        }
+    }
+}
+
+fn handle_list_journals() -> anyhow::Result<()> {
+    let journals = UploadJournal::list().context("error occured while reading upload journals!")?;
+    if journals.is_empty() {
+        println!("no interrupted uploads.");
+        return Ok(());
+    }
+
+    for (path, journal) in journals {
+        let journal = match journal {
+            Ok(journal) => journal,
+            Err(err) => {
+                println!(
+                    "{}",
+                    format!("unreadable journal '{}': {err}", path.to_string_lossy()).red()
+                );
+                continue;
+            }
+        };
+
+        let status = match journal.is_stale() {
+            true => "stale".red(),
+            false => "resumable".green(),
+        };
+        println!(
+            "[{status}] {} -> {}",
+            journal.local_filepath.to_string_lossy().bold(),
+            journal.remote_filepath.bold()
+        );
+        println!(
+            "{}",
+            format!(
+                "    {}/{} parts, {}, last updated at {}",
+                journal.parts.len(),
+                journal.get_n_parts(),
+                x2str::bytes2str(journal.fingerprint.size),
+                journal
+                    .updated_at
+                    .with_timezone(&chrono::Local)
+                    .format(constants::LOCAL_DATETIME_FORMAT)
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_clean_journals(all: bool) -> anyhow::Result<()> {
+    let journals = UploadJournal::list().context("error occured while reading upload journals!")?;
+
+    let mut n_deleted = 0;
+    for (path, journal) in journals {
+        let is_stale = match journal {
+            Ok(journal) => journal.is_stale(),
+            Err(_) => true,
+        };
+        if !all && !is_stale {
+            continue;
+        }
+
+        match fs::remove_file(&path) {
+            Ok(_) => n_deleted += 1,
+            Err(err) => println!(
+                "WARNING: failed to delete journal '{}'\n{err}",
+                path.to_string_lossy()
+            ),
+        }
+    }
+
+    println!("deleted {n_deleted} upload journal(s).");
+
+    Ok(())
+}
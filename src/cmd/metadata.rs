@@ -1,17 +1,24 @@
+use anyhow::Context;
 use chrono::Duration;
 use clap::Parser;
 use colored::Colorize;
 
-use crate::{
+use sfs_cli::{
     api::fs_files::{set_file_metadata, SetMetadata},
-    shared_types::{self, CliSubCmd},
+    error::SfsError,
+    shared_types,
     state::STATE,
-    utils::{dirtree, str2x},
+    utils::{dirtree, paths, str2x},
+};
+
+use crate::cmd::{
+    dirtree::{confirm_pattern_matches, get_pattern_matches},
+    get_client, CliSubCmd,
 };
 
 #[derive(Parser)]
 pub struct MetadataCommand {
-    /// name of the file
+    /// name of the file, or a pattern of the files to set the metadata of (e.g. "**/*.html")
     filename: String,
 
     #[arg(long)]
@@ -29,36 +36,80 @@ pub struct MetadataCommand {
     #[arg(long, value_parser = str2x::str2duration)]
     /// set the 'max-age' value for cache, defaults to 0 (format: 12d23h34m45s)
     max_age: Option<Duration>,
-}
 
-impl CliSubCmd for MetadataCommand {
-    async fn run(&self) {
-        let state = STATE.read().unwrap();
-        let wd = state.get_wd();
-
-        let dirpath = match &self.dirpath {
-            Some(dirpath) => dirtree::get_absolute_path(&dirpath, wd),
-            None => wd.to_string(),
-        };
-        let path = format!("{}/{}", dirpath, &self.filename);
+    #[arg(long)]
+    /// do not ask before setting the metadata of the files matching a pattern
+    no_confirm: bool,
+}
 
-        let metadata = SetMetadata {
-            path: &path,
+impl MetadataCommand {
+    fn get_metadata<'a>(&'a self, path: &'a str) -> SetMetadata<'a> {
+        SetMetadata {
+            path,
             name: self.set_name.as_deref(),
             is_public: match self.visibility {
                 Some(shared_types::CmdVisibility::Public) => Some(true),
                 Some(shared_types::CmdVisibility::Private) => Some(false),
                 None => None,
             },
-            cache_max_age_seconds: match self.max_age {
-                Some(max_age) => Some(max_age.num_seconds().abs() as u64),
-                None => None,
-            },
+            cache_max_age_seconds: self
+                .max_age
+                .map(|max_age| max_age.num_seconds().unsigned_abs()),
+            checksum: None,
+        }
+    }
+}
+
+impl CliSubCmd for MetadataCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = &STATE.read().unwrap().get_wd().to_string();
+
+        let dirpath = match &self.dirpath {
+            Some(dirpath) => dirtree::get_absolute_path(dirpath, wd),
+            None => wd.to_string(),
         };
-        set_file_metadata(metadata)
+
+        if paths::is_pattern(&self.filename) {
+            if self.set_name.is_some() {
+                return Err(SfsError::Usage(String::from(
+                    "--set-name can't be used with a pattern, every matched file would get the same name!",
+                ))
+                .into());
+            }
+
+            let pattern = dirtree::get_absolute_path(&self.filename, &dirpath);
+            let paths: Vec<String> = get_pattern_matches(&get_client()?, &pattern)
+                .await?
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect();
+            if !confirm_pattern_matches(&paths, "updated", self.no_confirm)? {
+                println!("Aborted.");
+                return Ok(());
+            }
+
+            for path in &paths {
+                set_file_metadata(&get_client()?, self.get_metadata(path))
+                    .await
+                    .with_context(|| {
+                        format!("error occured while setting metadata of '{path}'!")
+                    })?;
+            }
+
+            println!(
+                "{}",
+                format!("Metadata of {} files set successfully!", paths.len()).bold()
+            );
+            return Ok(());
+        }
+
+        let path = format!("{}/{}", dirpath, &self.filename);
+        set_file_metadata(&get_client()?, self.get_metadata(&path))
             .await
-            .expect("error occured while setting file metadata!");
+            .context("error occured while setting file metadata!")?;
 
         println!("{}", String::from("File metadata set successfully!").bold());
+
+        Ok(())
     }
 }
@@ -1,9 +1,27 @@
+use anyhow::Context;
+use sfs_cli::SfsClient;
+
 pub mod auth;
 pub mod blob;
+pub mod completions;
 pub mod config;
+pub mod dev_server;
 pub mod dirtree;
+pub mod journal;
 pub mod metadata;
+pub mod output;
 pub mod serve;
+pub mod shell;
+pub mod sync;
 pub mod tokens;
 pub mod usage;
+pub mod webdav;
+
+pub trait CliSubCmd {
+    async fn run(&self) -> anyhow::Result<()>;
+}
 
+/// api client for the active token, built again on every call as the local state can change
+pub fn get_client() -> anyhow::Result<SfsClient> {
+    SfsClient::from_local_state().context("error occured while initializing api client!")
+}
@@ -0,0 +1,133 @@
+use std::sync::RwLock;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use sfs_cli::shared_types::{AccessToken, CmdOutputFormat, FsFile};
+
+static OUTPUT_FORMAT: RwLock<CmdOutputFormat> = RwLock::new(CmdOutputFormat::Text);
+
+/// set from the global `--output` flag (for every command in a shell), colors are turned off for
+/// anything but text
+pub fn set_format(format: CmdOutputFormat) {
+    match format {
+        CmdOutputFormat::Text => colored::control::unset_override(),
+        _ => colored::control::set_override(false),
+    }
+    *OUTPUT_FORMAT.write().unwrap() = format;
+}
+
+pub fn get_format() -> CmdOutputFormat {
+    *OUTPUT_FORMAT.read().unwrap()
+}
+
+/// commands print their usual colored output (and notes, hints, etc.) only in text mode
+pub fn is_text() -> bool {
+    get_format() == CmdOutputFormat::Text
+}
+
+fn to_json<T: Serialize>(value: &T, pretty: bool) -> anyhow::Result<String> {
+    match pretty {
+        true => serde_json::to_string_pretty(value),
+        false => serde_json::to_string(value),
+    }
+    .context("error occured while serializing output!")
+}
+
+/// prints a single document, on one line for ndjson
+pub fn print_value<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!(
+        "{}",
+        to_json(value, get_format() != CmdOutputFormat::Ndjson)?
+    );
+
+    Ok(())
+}
+
+/// prints a json array, or one line per item for ndjson
+pub fn print_list<T, I>(values: I) -> anyhow::Result<()>
+where
+    T: Serialize,
+    I: IntoIterator<Item = T>,
+{
+    match get_format() {
+        CmdOutputFormat::Ndjson => {
+            for value in values {
+                println!("{}", to_json(&value, false)?);
+            }
+        }
+        _ => {
+            let values: Vec<T> = values.into_iter().collect();
+            println!("{}", to_json(&values, true)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// result of `find`, the file along with its absolute path
+#[derive(Serialize)]
+pub struct FindOutput {
+    pub path: String,
+    #[serde(flatten)]
+    pub file: FsFile,
+}
+
+/// row of `du`, sizes & counts include the nested directories
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuOutput {
+    pub path: String,
+    pub size: u64,
+    pub file_count: u64,
+    /// only with `--trash`, not included in `size` & `file_count`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_file_count: Option<u64>,
+}
+
+/// node of `tree`, counts & sizes are rolled up through the children with `--cumulative`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeOutput {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub file_count: u64,
+    /// only when the files were listed, i.e. with `--files`, `--cumulative` or `--pattern`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FsFile>>,
+    pub children: Vec<TreeOutput>,
+}
+
+/// result of `url` and `upload --share`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareUrlOutput {
+    /// of the file the url is for, set when a pattern matched more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub url: String,
+    /// only for urls with an access token, public files don't need one
+    pub expires_at: Option<chrono::DateTime<chrono::Local>>,
+    pub acpl: Option<Vec<String>>,
+}
+
+impl ShareUrlOutput {
+    pub fn new(url: String, access_token: Option<&str>) -> anyhow::Result<Self> {
+        let access_token: Option<AccessToken> = access_token
+            .map(|token| token.parse())
+            .transpose()
+            .context("error occured while parsing access token!")?;
+
+        Ok(Self {
+            path: None,
+            url,
+            expires_at: access_token.as_ref().map(|token| token.expires_at),
+            acpl: access_token.map(|token| token.acpl),
+        })
+    }
+}
@@ -1,10 +1,14 @@
+use anyhow::{anyhow, Context};
 use clap::Parser;
 use std::fs;
 use std::path::Path;
 use tiny_http::Server;
 
-use crate::utils::net::get_local_addr;
-use crate::{shared_types::CliSubCmd, utils::paths::get_paths_from_pattern};
+use sfs_cli::error::SfsError;
+use sfs_cli::utils::net::get_local_addr;
+use sfs_cli::utils::paths::get_paths_from_pattern;
+
+use crate::cmd::CliSubCmd;
 
 #[derive(Parser)]
 pub struct ServeCommand {
@@ -13,33 +17,39 @@ pub struct ServeCommand {
 }
 
 impl CliSubCmd for ServeCommand {
-    async fn run(&self) {
-        let mut paths: Vec<_> = self
-            .patterns
-            .iter()
-            .flat_map(|patt| get_paths_from_pattern(patt).unwrap())
-            .collect();
+    async fn run(&self) -> anyhow::Result<()> {
+        let mut paths = vec![];
+        for patt in &self.patterns {
+            paths.extend(get_paths_from_pattern(patt).map_err(|err| {
+                SfsError::Usage(format!(
+                    "invalid pattern '{patt}', could not be resolved to a path! {err}"
+                ))
+            })?);
+        }
 
         paths.sort_by(|a, b| a.1.cmp(&b.1));
         paths.dedup();
 
         println!("Files selected: {}", paths.len());
 
-        let local_addr = get_local_addr().unwrap();
+        let local_addr = get_local_addr().context("cannot find a local network address!")?;
 
-        let server = Server::http(format!("{}:{}", local_addr.0, local_addr.1)).unwrap();
+        let server = Server::http(format!("{}:{}", local_addr.0, local_addr.1))
+            .map_err(|err| anyhow!("error occured while starting the server! {err}"))?;
         println!("Server started at http://{}:{}", local_addr.0, local_addr.1);
 
         loop {
-            let request = server.recv().unwrap();
+            let request = server
+                .recv()
+                .context("error occured while receiving a request!")?;
 
             if request.url() == "/" {
                 let mut html = String::from("<ol>");
                 for (_, path) in &paths {
-                    let path_str = path.to_str().unwrap();
+                    let path_str = path.to_string_lossy();
                     html += &format!(
                         "<li><a href=\"/file/{}\">{}</a></li>",
-                        urlencoding::encode(path_str),
+                        urlencoding::encode(&path_str),
                         path_str,
                     );
                 }
@@ -49,7 +59,7 @@ impl CliSubCmd for ServeCommand {
                 response.add_header(
                     tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap(),
                 );
-                request.respond(response).unwrap();
+                let _ = request.respond(response);
             } else if request.url().starts_with("/file/") {
                 let pathname = request.url().replace("/file/", "");
                 let pathname = match urlencoding::decode(pathname.as_str()) {
@@ -66,12 +76,10 @@ impl CliSubCmd for ServeCommand {
                 let file_data = match fs::read(Path::new(&pathname)) {
                     Ok(data) => data,
                     Err(e) => {
-                        request
-                            .respond(
-                                tiny_http::Response::from_string("File not found")
-                                    .with_status_code(404),
-                            )
-                            .unwrap();
+                        let _ = request.respond(
+                            tiny_http::Response::from_string("File not found")
+                                .with_status_code(404),
+                        );
 
                         println!("Error reading file: {}", e);
                         continue;
@@ -86,14 +94,12 @@ impl CliSubCmd for ServeCommand {
                     )
                     .unwrap(),
                 );
-                request.respond(response).unwrap();
+                let _ = request.respond(response);
             } else {
-                request
-                    .respond(
-                        tiny_http::Response::from_string("Requested path not found")
-                            .with_status_code(404),
-                    )
-                    .unwrap();
+                let _ = request.respond(
+                    tiny_http::Response::from_string("Requested path not found")
+                        .with_status_code(404),
+                );
             }
         }
     }
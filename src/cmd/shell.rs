@@ -0,0 +1,351 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, IsTerminal, Write},
+    iter,
+    path::PathBuf,
+    process,
+};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use dialoguer::{Completion, History, Input};
+use tokio::task;
+
+use sfs_cli::{
+    api,
+    config::{self, CONFIG},
+    constants, error,
+    shared_types::{CmdOutputFormat, DirTree},
+    state::STATE,
+    utils::{self, prompts},
+};
+
+use crate::{
+    cmd::{get_client, output, CliSubCmd},
+    Cli, Commands,
+};
+
+#[derive(Parser)]
+pub struct ShellCommand;
+
+/// commands of the shell itself, anything else is parsed as a regular subcommand
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct ShellBuiltin {
+    #[command(subcommand)]
+    command: ShellBuiltinCommands,
+}
+
+#[derive(Subcommand)]
+enum ShellBuiltinCommands {
+    /// leave the shell
+    #[command(alias = "quit")]
+    Exit,
+    /// fetch the directory tree used for completions again
+    Refresh,
+}
+
+/// history saved in the profile's directory, newest entries first
+struct ShellHistory {
+    entries: VecDeque<String>,
+    filepath: Option<PathBuf>,
+}
+
+impl ShellHistory {
+    fn load() -> Self {
+        let profile = CONFIG.read().unwrap().get_profile().to_string();
+        let filepath = config::get_profile_dirpath(&profile)
+            .ok()
+            .map(|dirpath| dirpath.join("shell_history"));
+
+        let entries = filepath
+            .as_ref()
+            .and_then(|filepath| fs::read_to_string(filepath).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .rev()
+                    .take(constants::SHELL_HISTORY_MAX_ENTRIES)
+                    .map(|line| line.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { entries, filepath }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let filepath = match &self.filepath {
+            Some(filepath) => filepath,
+            None => return Ok(()),
+        };
+        if let Some(dirpath) = filepath.parent() {
+            fs::create_dir_all(dirpath)?;
+        }
+
+        let mut file = fs::File::create(filepath)?;
+        for entry in self.entries.iter().rev() {
+            writeln!(file, "{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl History<String> for ShellHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.entries.get(pos).cloned()
+    }
+
+    fn write(&mut self, val: &String) {
+        if val.trim().is_empty() || self.entries.front() == Some(val) {
+            return;
+        }
+
+        self.entries.push_front(val.clone());
+        self.entries.truncate(constants::SHELL_HISTORY_MAX_ENTRIES);
+        // history is best effort, the shell works without it
+        _ = self.save();
+    }
+}
+
+/// completes the last word of the line with remote directory paths
+struct RemotePathCompletion {
+    dirtree: Option<DirTree>,
+    wd: String,
+}
+
+impl Completion for RemotePathCompletion {
+    fn get(&self, input: &str) -> Option<String> {
+        let dirtree = self.dirtree.as_ref()?;
+
+        // only arguments are completed, not the subcommand
+        let (head, word) = input.split_at(input.rfind(' ')? + 1);
+        let (parent, prefix) = match word.rfind('/') {
+            Some(i) => word.split_at(i + 1),
+            None => ("", word),
+        };
+
+        let parent_abs_path = utils::dirtree::get_absolute_path(
+            match parent {
+                "" => ".",
+                parent => parent,
+            },
+            &self.wd,
+        );
+        let matches: Vec<&str> = dirtree
+            .get_sub_tree(&parent_abs_path)?
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+
+        let completion = match matches.as_slice() {
+            [] => return None,
+            [name] => format!("{name}/"),
+            [first, rest @ ..] => rest.iter().fold(first.to_string(), |common, name| {
+                common
+                    .chars()
+                    .zip(name.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a)
+                    .collect()
+            }),
+        };
+        if completion == prefix {
+            return None;
+        }
+
+        Some(format!("{head}{parent}{completion}"))
+    }
+}
+
+impl ShellCommand {
+    fn get_prompt() -> String {
+        let state = STATE.read().unwrap();
+
+        format!(
+            "sfs [{}] {}",
+            state.get_active_token_label().magenta(),
+            state.get_wd().bold().cyan()
+        )
+    }
+
+    async fn fetch_dirtree() -> Option<DirTree> {
+        api::dirtree::get_dirtree(&get_client().ok()?)
+            .await
+            .ok()
+            .map(|res| res.dirtree)
+    }
+
+    /// commands after which the cached directory tree is out of date
+    fn changes_dirtree(commands: &Commands) -> bool {
+        matches!(
+            commands,
+            Commands::Mkdir(_)
+                | Commands::Rmdir(_)
+                | Commands::Mvdir(_)
+                | Commands::Cp(_)
+                | Commands::Upload(_)
+                | Commands::Sync(_)
+        )
+    }
+
+    /// the file system the shell talks to, changes with `tokens use`, `auth login`, etc.
+    fn get_active_token() -> Option<String> {
+        STATE
+            .read()
+            .unwrap()
+            .get_active_token()
+            .ok()
+            .flatten()
+            .map(|(token, _)| token)
+    }
+
+    /// piped commands end with the exit code of the last one that ran, like a script would
+    fn exit(is_terminal: bool, last_exit_code: i32) -> anyhow::Result<()> {
+        match (is_terminal, last_exit_code) {
+            (false, exit_code) if exit_code != 0 => process::exit(exit_code),
+            _ => Ok(()),
+        }
+    }
+
+    /// `cd` is served from the cached tree, it's fetched again only if the path isn't in it
+    async fn run_cd(cmd: &super::dirtree::Cd, dirtree: &mut Option<DirTree>) -> anyhow::Result<()> {
+        if let Some(cached) = dirtree {
+            if cmd.change_wd(cached).is_ok() {
+                return Ok(());
+            }
+        }
+
+        *dirtree = Self::fetch_dirtree().await;
+        match dirtree {
+            Some(dirtree) => cmd.change_wd(dirtree),
+            None => cmd.run().await,
+        }
+    }
+
+    /// runs a line as if it was passed to the binary, global flags apply to that line only
+    async fn run_line(args: Vec<String>, dirtree: &mut Option<DirTree>) -> anyhow::Result<()> {
+        let cli = match Cli::try_parse_from(iter::once(String::from("sfs")).chain(args)) {
+            Ok(cli) => cli,
+            Err(err) => {
+                _ = err.print();
+                return Ok(());
+            }
+        };
+        if cli.profile.is_some() {
+            println!(
+                "{}",
+                "profiles can't be switched inside the shell, start it again with --profile.".red()
+            );
+            return Ok(());
+        }
+        if matches!(cli.commands, Commands::Shell(_)) {
+            println!("{}", "already in a shell.".dimmed());
+            return Ok(());
+        }
+
+        let (session_format, session_non_interactive) =
+            (output::get_format(), prompts::is_non_interactive());
+        if cli.output_format != CmdOutputFormat::Text {
+            output::set_format(cli.output_format);
+        }
+        prompts::set_non_interactive(session_non_interactive || cli.non_interactive);
+
+        let active_token = Self::get_active_token();
+        let changes_dirtree = Self::changes_dirtree(&cli.commands);
+        let res = match &cli.commands {
+            Commands::Cd(cmd) => Self::run_cd(cmd, dirtree).await,
+            _ => Box::pin(crate::run(cli.commands)).await,
+        };
+
+        output::set_format(session_format);
+        prompts::set_non_interactive(session_non_interactive);
+        // a different file system's tree if the token changed
+        if changes_dirtree || Self::get_active_token() != active_token {
+            *dirtree = Self::fetch_dirtree().await;
+        }
+
+        res
+    }
+}
+
+impl CliSubCmd for ShellCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let mut history = ShellHistory::load();
+        let mut dirtree = Self::fetch_dirtree().await;
+
+        if io::stdin().is_terminal() {
+            println!(
+                "{}",
+                "run any subcommand without the 'sfs' prefix, 'exit' to leave. tab completes remote directories."
+                    .dimmed()
+            );
+        }
+
+        let is_terminal = io::stdin().is_terminal();
+        let mut lines = io::stdin().lines();
+        let mut last_exit_code = 0;
+
+        loop {
+            // piped commands (e.g. `sfs shell < cmds.txt`) are read as is, without prompts
+            let line = match is_terminal {
+                true => {
+                    let completion = RemotePathCompletion {
+                        dirtree: dirtree.take(),
+                        wd: STATE.read().unwrap().get_wd().to_string(),
+                    };
+                    let line = task::block_in_place(|| {
+                        Input::<String>::new()
+                            .with_prompt(Self::get_prompt())
+                            .allow_empty(true)
+                            .history_with(&mut history)
+                            .completion_with(&completion)
+                            .interact_text()
+                    });
+                    dirtree = completion.dirtree;
+
+                    line.context("error occured while reading input!")?
+                }
+                false => match lines.next() {
+                    Some(line) => line.context("error occured while reading input!")?,
+                    None => return Self::exit(is_terminal, last_exit_code),
+                },
+            };
+            let args = match shell_words::split(&line) {
+                Ok(args) => args,
+                Err(err) => {
+                    println!("{}", format!("invalid input: {err}").red());
+                    continue;
+                }
+            };
+            if args.is_empty() {
+                continue;
+            }
+
+            if let Ok(builtin) = ShellBuiltin::try_parse_from(&args) {
+                match builtin.command {
+                    ShellBuiltinCommands::Exit => return Self::exit(is_terminal, last_exit_code),
+                    ShellBuiltinCommands::Refresh => {
+                        dirtree = Self::fetch_dirtree().await;
+                        continue;
+                    }
+                }
+            }
+
+            last_exit_code = match Self::run_line(args, &mut dirtree).await {
+                Ok(()) => 0,
+                Err(err) => {
+                    let exit_code = error::get_exit_code(&err);
+                    eprintln!("{}", format!("{err:?}").red());
+                    eprintln!("{}", format!("exit code: {exit_code}").dimmed());
+                    exit_code
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    env::current_dir,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, Parser, Subcommand};
+use colored::Colorize;
+use futures_util::StreamExt;
+use indicatif::{MultiProgress, ProgressBar};
+use inquire::Confirm;
+use tokio::fs;
+use walkdir::WalkDir;
+
+use sfs_cli::{
+    api::{self, downloads::DownloadFilesOpts, uploads::UploadFileOpts},
+    constants,
+    error::SfsError,
+    journal::FileFingerprint,
+    shared_types::{CmdTransferParams, DirTree, FsFile},
+    state::STATE,
+    utils::{self, checksum, dirtree, paths, prompts, x2str},
+};
+
+use crate::cmd::{blob::CmdUploadParams, get_client, CliSubCmd};
+
+#[derive(Parser)]
+pub struct SyncCommand {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// upload new & changed files from a local directory into a remote directory
+    Push {
+        /// local directory to sync from
+        local_dirpath: PathBuf,
+
+        /// relative or absolute path to the remote directory to sync into. (default: currently set WD)
+        remote_dirpath: Option<String>,
+
+        #[command(flatten)]
+        sync_params: CmdSyncParams,
+
+        #[command(flatten)]
+        upload_params: CmdUploadParams,
+    },
+
+    /// download new & changed files from a remote directory into a local directory
+    Pull {
+        /// relative or absolute path to the remote directory to sync from
+        remote_dirpath: String,
+
+        /// local directory to sync into. (default: CWD)
+        local_dirpath: Option<PathBuf>,
+
+        #[command(flatten)]
+        sync_params: CmdSyncParams,
+    },
+}
+
+#[derive(Args)]
+pub struct CmdSyncParams {
+    #[arg(long)]
+    /// delete files in the destination directory that do not exist in the source directory
+    delete: bool,
+
+    #[arg(long)]
+    /// print the files that would be transferred or deleted without changing anything
+    dry_run: bool,
+
+    #[arg(long)]
+    /// compare file contents by their sha256 instead of modification times (local files are hashed, slower)
+    checksum: bool,
+
+    #[arg(long)]
+    /// do not display any confirm prompts
+    no_confirm: bool,
+
+    #[command(flatten)]
+    transfer_params: CmdTransferParams,
+}
+
+/// what's known about a file on either side of the sync
+struct SyncEntry {
+    /// unknown for compressed remote files
+    size: Option<u64>,
+    modified_at: DateTime<Utc>,
+    /// only filled in for local files when the contents need to be compared
+    checksum: Option<String>,
+}
+
+enum SyncReason {
+    New,
+    Changed,
+}
+
+/// files are keyed by their '/' separated path relative to the synced directories
+struct SyncPlan {
+    transfers: Vec<(String, SyncReason)>,
+    deletions: Vec<String>,
+    n_unchanged: usize,
+}
+
+impl SyncPlan {
+    /// a destination file is changed if its (known) size differs, or if the checksums of both
+    /// differ or (without checksums) the source file was modified after it. times are compared in whole
+    /// seconds, remote timestamps are less precise than local ones.
+    fn new(
+        src: &BTreeMap<String, SyncEntry>,
+        dest: &BTreeMap<String, SyncEntry>,
+        delete: bool,
+    ) -> Self {
+        let mut plan = Self {
+            transfers: vec![],
+            deletions: vec![],
+            n_unchanged: 0,
+        };
+
+        for (path, src_entry) in src {
+            match dest.get(path) {
+                None => plan.transfers.push((path.clone(), SyncReason::New)),
+                Some(dest_entry) if is_changed(src_entry, dest_entry) => {
+                    plan.transfers.push((path.clone(), SyncReason::Changed))
+                }
+                Some(_) => plan.n_unchanged += 1,
+            }
+        }
+
+        if delete {
+            plan.deletions = dest
+                .keys()
+                .filter(|path| !src.contains_key(*path))
+                .cloned()
+                .collect();
+        }
+
+        plan
+    }
+
+    fn is_empty(&self) -> bool {
+        self.transfers.is_empty() && self.deletions.is_empty()
+    }
+
+    fn print(&self, src: &BTreeMap<String, SyncEntry>, transfer_verb: &str) {
+        for (path, reason) in &self.transfers {
+            let size = src[path]
+                .size
+                .map(x2str::bytes2str)
+                .unwrap_or(String::from("?"));
+            match reason {
+                SyncReason::New => println!("{} {} ({size})", "+".green(), path),
+                SyncReason::Changed => println!("{} {} ({size})", "~".yellow(), path),
+            }
+        }
+        for path in &self.deletions {
+            println!("{} {}", "-".red(), path);
+        }
+
+        println!(
+            "\n{}",
+            format!(
+                "{} files to {transfer_verb}, {} files to delete, {} files unchanged",
+                self.transfers.len(),
+                self.deletions.len(),
+                self.n_unchanged
+            )
+            .bold()
+        );
+    }
+
+    /// returns false if the sync should not go ahead
+    fn confirm(&self, sync_params: &CmdSyncParams) -> anyhow::Result<bool> {
+        if sync_params.dry_run || self.is_empty() {
+            return Ok(false);
+        }
+        if self.deletions.is_empty() || sync_params.no_confirm {
+            return Ok(true);
+        }
+
+        prompts::ensure_interactive("a confirmation", "use --no-confirm to skip it")?;
+
+        Confirm::new(&format!(
+            "{} files will be deleted, confirm:",
+            self.deletions.len()
+        ))
+        .with_default(false)
+        .prompt()
+        .context("error occured while displaying confirm prompt!")
+    }
+}
+
+fn is_changed(src: &SyncEntry, dest: &SyncEntry) -> bool {
+    if let (Some(src_size), Some(dest_size)) = (src.size, dest.size) {
+        if src_size != dest_size {
+            return true;
+        }
+    }
+
+    match (&src.checksum, &dest.checksum) {
+        (Some(src_checksum), Some(dest_checksum)) => src_checksum != dest_checksum,
+        _ => src.modified_at.timestamp() > dest.modified_at.timestamp(),
+    }
+}
+
+/// split relative path into (dirpath, filename), dirpath is empty for files at the top level
+fn split_rel_path(rel_path: &str) -> (&str, &str) {
+    rel_path.rsplit_once('/').unwrap_or(("", rel_path))
+}
+
+/// joins a relative dirpath onto the synced remote directory
+fn join_remote_dirpath(remote_dirpath: &str, rel_dirpath: &str) -> String {
+    match dirtree::join_paths(&[remote_dirpath, rel_dirpath]) {
+        dirpath if dirpath.is_empty() => String::from("/"),
+        dirpath => dirpath,
+    }
+}
+
+fn get_local_entries(
+    dirpath: &Path,
+    with_checksums: bool,
+) -> anyhow::Result<BTreeMap<String, SyncEntry>> {
+    let mut entries = BTreeMap::new();
+    if !dirpath.exists() {
+        return Ok(entries);
+    }
+
+    for entry in WalkDir::new(dirpath) {
+        let entry = entry?;
+        if !entry.file_type().is_file()
+            || entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == constants::PART_FILE_EXT)
+        {
+            continue;
+        }
+
+        let rel_path = entry.path().strip_prefix(dirpath)?;
+        let rel_path = match rel_path.to_str() {
+            Some(p) => p.replace(std::path::MAIN_SEPARATOR, "/"),
+            None => {
+                eprintln!(
+                    "WARNING: path '{}' is not a valid utf8 string! skipping...",
+                    entry.path().to_string_lossy()
+                );
+                continue;
+            }
+        };
+
+        let fingerprint = FileFingerprint::from_path(entry.path())?;
+        let checksum = match with_checksums {
+            true => Some(checksum::get_file_checksum(entry.path())?),
+            false => None,
+        };
+        entries.insert(
+            rel_path,
+            SyncEntry {
+                size: Some(fingerprint.size),
+                modified_at: fingerprint.modified_at,
+                checksum,
+            },
+        );
+    }
+
+    Ok(entries)
+}
+
+/// lists the remote directory recursively, an empty map is returned if it doesn't exist
+async fn get_remote_files(
+    dirtree: &DirTree,
+    remote_dirpath: &str,
+) -> anyhow::Result<BTreeMap<String, FsFile>> {
+    let subtree = match dirtree.get_sub_tree(remote_dirpath) {
+        Some(subtree) => subtree,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    let spinner = ProgressBar::new_spinner().with_message("listing remote files");
+    spinner.enable_steady_tick(Duration::milliseconds(50).to_std().unwrap());
+    let listings =
+        api::fs_files::get_all_files_in_dirs(&get_client()?, subtree.get_dir_paths(remote_dirpath))
+            .await?;
+    spinner.finish_and_clear();
+
+    let mut remote_files = BTreeMap::new();
+    for (dirpath, files) in listings {
+        let rel_dirpath = dirpath
+            .strip_prefix(remote_dirpath)
+            .unwrap_or("")
+            .trim_matches('/')
+            .to_string();
+
+        for file in files {
+            let rel_path = match rel_dirpath.is_empty() {
+                true => file.name.clone(),
+                false => format!("{}/{}", rel_dirpath, file.name),
+            };
+            remote_files.insert(rel_path, file);
+        }
+    }
+
+    Ok(remote_files)
+}
+
+fn get_remote_entries(remote_files: &BTreeMap<String, FsFile>) -> BTreeMap<String, SyncEntry> {
+    remote_files
+        .iter()
+        .map(|(path, file)| {
+            (
+                path.clone(),
+                SyncEntry {
+                    size: file.get_plain_file_size(),
+                    modified_at: file.updated_at.with_timezone(&Utc),
+                    checksum: file.checksum.clone(),
+                },
+            )
+        })
+        .collect()
+}
+
+impl CliSubCmd for SyncCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let wd = STATE.read().unwrap().get_wd().to_string();
+
+        match &self.command {
+            Commands::Push {
+                local_dirpath,
+                remote_dirpath,
+                sync_params,
+                upload_params,
+            } => {
+                let local_dirpath = paths::get_absolute_path(local_dirpath)
+                    .context("invalid local dirpath provided!")?;
+                let remote_dirpath = match remote_dirpath {
+                    Some(dirpath) => dirtree::get_absolute_path(dirpath, &wd),
+                    None => wd,
+                };
+
+                handle_push(&local_dirpath, &remote_dirpath, sync_params, upload_params).await
+            }
+            Commands::Pull {
+                remote_dirpath,
+                local_dirpath,
+                sync_params,
+            } => {
+                let remote_dirpath = dirtree::get_absolute_path(remote_dirpath, &wd);
+                let local_dirpath = match local_dirpath {
+                    Some(dirpath) => paths::get_absolute_path(dirpath)
+                        .context("invalid local dirpath provided!")?,
+                    None => current_dir().context("cannot find os CWD!")?,
+                };
+
+                handle_pull(&remote_dirpath, &local_dirpath, sync_params).await
+            }
+        }
+    }
+}
+
+async fn handle_push(
+    local_dirpath: &Path,
+    remote_dirpath: &str,
+    sync_params: &CmdSyncParams,
+    upload_params: &CmdUploadParams,
+) -> anyhow::Result<()> {
+    if !local_dirpath.is_dir() {
+        return Err(SfsError::NotFound(format!(
+            "local directory '{}' does not exist!",
+            local_dirpath.to_string_lossy()
+        ))
+        .into());
+    }
+
+    let dirtree = api::dirtree::get_dirtree(&get_client()?)
+        .await
+        .context("error occured while fetching dirtree!")?
+        .dirtree;
+    let local_entries = get_local_entries(local_dirpath, sync_params.checksum)
+        .context("error occured while reading local files!")?;
+    let remote_files = get_remote_files(&dirtree, remote_dirpath)
+        .await
+        .context("error occured while listing remote files!")?;
+
+    let plan = SyncPlan::new(
+        &local_entries,
+        &get_remote_entries(&remote_files),
+        sync_params.delete,
+    );
+    plan.print(&local_entries, "upload");
+    if !plan.confirm(sync_params)? {
+        return Ok(());
+    }
+
+    let mkdir_paths = plan
+        .transfers
+        .iter()
+        .map(|(path, _)| join_remote_dirpath(remote_dirpath, split_rel_path(path).0))
+        .filter(|dirpath| dirtree.get_sub_tree(dirpath).is_none())
+        .collect::<HashSet<String>>();
+    for dirpath in mkdir_paths {
+        api::dirtree::mkdir(&get_client()?, &dirpath)
+            .await
+            .context("error occured while creating remote directory!")?;
+    }
+
+    let password = match plan.transfers.is_empty() {
+        true => None,
+        false => upload_params.get_password()?,
+    };
+    let padding = plan
+        .transfers
+        .iter()
+        .fold(0, |acc, (path, _)| acc.max(path.len()));
+    let rate_limiter = sync_params.transfer_params.get_rate_limiter();
+    let multi_progress_bar = MultiProgress::new();
+    let results = futures_util::stream::iter(plan.transfers.iter().map(|(path, _)| {
+        let (rel_dirpath, filename) = split_rel_path(path);
+        let upload_metadata = upload_params.get_upload_metadata(
+            filename.to_string(),
+            join_remote_dirpath(remote_dirpath, rel_dirpath),
+            true,
+            None,
+        );
+
+        let progress_bar = multi_progress_bar.add(
+            ProgressBar::new(local_entries[path].size.unwrap_or(0)).with_style(
+                utils::misc::get_sized_throughput_progress_style(Some(&format!(
+                    "{0:<1$}",
+                    path, padding
+                ))),
+            ),
+        );
+        let mut opts =
+            UploadFileOpts::new(local_dirpath.join(path), password.clone(), progress_bar);
+        opts.rate_limiter = rate_limiter.clone();
+
+        async move {
+            api::uploads::upload_file(&get_client()?, upload_metadata, opts)
+                .await
+                .map_err(|err| anyhow!("'{}': {err}", path))
+        }
+    }))
+    .buffer_unordered(sync_params.transfer_params.get_jobs())
+    .collect::<Vec<_>>()
+    .await;
+
+    let mut err_msg = String::new();
+    for res in results {
+        if let Err(err) = res {
+            err_msg += &format!("{err}\n");
+        }
+    }
+
+    let mut deletions: HashMap<String, Vec<String>> = HashMap::new();
+    for path in &plan.deletions {
+        let (rel_dirpath, filename) = split_rel_path(path);
+        deletions
+            .entry(join_remote_dirpath(remote_dirpath, rel_dirpath))
+            .or_default()
+            .push(filename.to_string());
+    }
+    for (dir_path, file_names) in deletions {
+        if let Err(err) = api::fs_files::delete_files(
+            &get_client()?,
+            &api::fs_files::DeleteFilesReqBody {
+                dir_path: &dir_path,
+                file_names: &file_names,
+            },
+        )
+        .await
+        {
+            err_msg += &format!("{err}\n");
+        }
+    }
+
+    if !err_msg.is_empty() {
+        return Err(anyhow!("sync did not complete!\n{}", err_msg.trim_end()));
+    }
+
+    println!("\n{}", format!("synced into {}", remote_dirpath).bold());
+
+    Ok(())
+}
+
+async fn handle_pull(
+    remote_dirpath: &str,
+    local_dirpath: &Path,
+    sync_params: &CmdSyncParams,
+) -> anyhow::Result<()> {
+    let dirtree = api::dirtree::get_dirtree(&get_client()?)
+        .await
+        .context("error occured while fetching dirtree!")?
+        .dirtree;
+    if dirtree.get_sub_tree(remote_dirpath).is_none() {
+        return Err(SfsError::NotFound(format!(
+            "remote directory '{}' does not exist!",
+            remote_dirpath
+        ))
+        .into());
+    }
+
+    let mut remote_files = get_remote_files(&dirtree, remote_dirpath)
+        .await
+        .context("error occured while listing remote files!")?;
+    let remote_entries = get_remote_entries(&remote_files);
+    let local_entries = get_local_entries(local_dirpath, sync_params.checksum)
+        .context("error occured while reading local files!")?;
+
+    let plan = SyncPlan::new(&remote_entries, &local_entries, sync_params.delete);
+    plan.print(&remote_entries, "download");
+    if !plan.confirm(sync_params)? {
+        return Ok(());
+    }
+
+    let mut downloads: Vec<(FsFile, PathBuf)> = vec![];
+    for (path, _) in &plan.transfers {
+        let filepath = local_dirpath.join(path);
+        if let Some(parent) = filepath.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .context("error occured while creating local directory!")?;
+        }
+
+        let file = remote_files.remove(path).unwrap();
+        downloads.push((file, filepath));
+    }
+
+    let password = downloads
+        .iter()
+        .any(|(file, _)| {
+            file.encryption
+                .as_ref()
+                .is_some_and(|e| e.attempt_decryption)
+        })
+        .then(utils::crypto::get_decryption_password)
+        .transpose()
+        .context("error occured while reading password!")?;
+
+    let mut opts = DownloadFilesOpts::new(password);
+    opts.preserve_mtime = true;
+    opts.max_concurrent = sync_params.transfer_params.get_jobs();
+    opts.rate_limiter = sync_params.transfer_params.get_rate_limiter();
+
+    let mut err_msg = String::new();
+    if let Err(err) =
+        api::downloads::download_files(&get_client()?, downloads, local_dirpath, opts).await
+    {
+        err_msg += &err.to_string();
+    }
+
+    for path in &plan.deletions {
+        if let Err(err) = fs::remove_file(local_dirpath.join(path)).await {
+            err_msg += &format!("'{}': {err}\n", path);
+        }
+    }
+
+    if !err_msg.is_empty() {
+        return Err(anyhow!("sync did not complete!\n{}", err_msg.trim_end()));
+    }
+
+    println!(
+        "\n{}",
+        format!("synced into {}", local_dirpath.to_string_lossy()).bold()
+    );
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use anyhow::{anyhow, Context};
 use chrono::{DateTime, Local, Utc};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
@@ -5,14 +6,18 @@ use nucleo_matcher::{
     pattern::{AtomKind, CaseMatching, Normalization, Pattern},
     Matcher,
 };
+use serde::Serialize;
 
-use crate::{
+use sfs_cli::{
     api, constants,
-    shared_types::{self, AccessToken, CliSubCmd},
+    error::SfsError,
+    shared_types::{self, AccessToken},
     state::{ActiveToken, PersistentState, STATE},
     utils::{self, dirtree::PrintDirTreeOpts, local_auth::LocalAuthData},
 };
 
+use crate::cmd::{get_client, output, CliSubCmd};
+
 #[derive(Parser)]
 pub struct TokensCommand {
     #[command(subcommand)]
@@ -55,17 +60,15 @@ pub enum Commands {
 }
 
 impl CliSubCmd for TokensCommand {
-    async fn run(&self) {
-        let mut state_mut = STATE.write().unwrap();
-        _ = delete_exp_tokens(&mut state_mut);
-        drop(state_mut);
+    async fn run(&self) -> anyhow::Result<()> {
+        _ = delete_exp_tokens(&mut STATE.write().unwrap());
 
         match &self.command {
             Commands::Generate {
                 acpl,
                 tag,
                 exp_input,
-            } => handle_generate(&acpl, tag.as_deref(), &exp_input).await,
+            } => handle_generate(acpl, tag.as_deref(), exp_input).await,
             Commands::Ls { tag: tagname, info } => {
                 handle_list_tokens(tagname.as_deref(), *info).await
             }
@@ -75,7 +78,7 @@ impl CliSubCmd for TokensCommand {
     }
 }
 
-pub async fn handle_use_token<S>(input: Option<S>)
+pub async fn handle_use_token<S>(input: Option<S>) -> anyhow::Result<()>
 where
     S: AsRef<str>,
 {
@@ -109,7 +112,8 @@ where
 
                     match state.tokens.get(tag) {
                         None => {
-                            println!("cannot find a locally saved tag with name '{tag}'");
+                            let mut err_msg =
+                                format!("cannot find a locally saved tag with name '{tag}'");
 
                             let matches = Pattern::new(
                                 tag,
@@ -123,11 +127,11 @@ where
                             .map(|(m, _)| format!("'{m}'"))
                             .collect::<Vec<String>>();
 
-                            if matches.len() > 0 {
-                                println!("did you mean: {}", matches.join(", "));
+                            if !matches.is_empty() {
+                                err_msg += &format!("\ndid you mean: {}", matches.join(", "));
                             }
 
-                            return;
+                            return Err(SfsError::NotFound(err_msg).into());
                         }
                         Some(_) => {
                             _ = state.guard_mutate(|state| {
@@ -143,9 +147,11 @@ where
 
     let active_token = &state.active_token;
     println!("'{prev_active_token}' -> '{active_token}'");
+
+    Ok(())
 }
 
-pub async fn handle_list_tokens<S>(tagname: Option<S>, info: bool)
+pub async fn handle_list_tokens<S>(tagname: Option<S>, info: bool) -> anyhow::Result<()>
 where
     S: AsRef<str>,
 {
@@ -176,11 +182,21 @@ where
         }
     };
 
-    if matches.len() == 0 {
+    if matches.is_empty() && output::is_text() {
         println!("no tokens saved locally, generate tokens with a 'tag' to save them locally.");
-        return;
+        return Ok(());
     }
 
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct TokenOutput {
+        tag: String,
+        is_active: bool,
+        #[serde(flatten)]
+        access_token: AccessToken,
+    }
+    let mut token_outputs: Vec<TokenOutput> = vec![];
+
     struct OutputData {
         name: String,
         exp: String,
@@ -194,7 +210,7 @@ where
 
     for active_token_match in matches {
         let (access_token, k) = match &active_token_match {
-            ActiveToken::RootAccessToken => match LocalAuthData::get() {
+            ActiveToken::RootAccessToken => match LocalAuthData::get()? {
                 Some(auth_data) => (
                     auth_data.access_token,
                     ActiveToken::RootAccessToken.to_string(),
@@ -206,10 +222,21 @@ where
                 None => continue,
             },
         };
-        let access_token: AccessToken = access_token.parse().expect(&format!(
-            "error occured while parsing access token! '{}' seems invalid.",
-            access_token,
-        ));
+        let access_token: AccessToken = access_token.parse().with_context(|| {
+            format!(
+                "error occured while parsing access token! '{}' seems invalid.",
+                access_token,
+            )
+        })?;
+
+        if !output::is_text() {
+            token_outputs.push(TokenOutput {
+                tag: k,
+                is_active: state.active_token.eq(&active_token_match),
+                access_token,
+            });
+            continue;
+        }
 
         let out_data = OutputData {
             name: k.bold().to_string(),
@@ -235,46 +262,49 @@ where
         output_tuples.push(out_data);
     }
 
+    if !output::is_text() {
+        return output::print_list(token_outputs);
+    }
+
     for out in output_tuples {
         let (name, exp, acpl, status) = (out.name, out.exp, out.acpl, out.status);
 
-        println!(
-            "{}",
-            format!("{status} {name:<name_padding$} {exp:<exp_padding$} {acpl:<acpl_padding$}")
-        );
+        println!("{status} {name:<name_padding$} {exp:<exp_padding$} {acpl:<acpl_padding$}");
     }
+
+    Ok(())
 }
 
-pub async fn handle_blacklist_token(tokens: &Vec<String>) {
-    api::tokens::blacklist_token(&tokens)
+pub async fn handle_blacklist_token(tokens: &Vec<String>) -> anyhow::Result<()> {
+    api::tokens::blacklist_token(&get_client()?, tokens)
         .await
-        .expect("error occured while blacklisting token!");
+        .context("error occured while blacklisting token!")?;
 
     println!("{}", "Token blacklisted successfully!".to_string().bold());
+
+    Ok(())
 }
 
 pub async fn handle_generate(
-    acpl: &Vec<String>,
+    acpl: &[String],
     tag: Option<&str>,
     exp_input: &shared_types::CmdExpiryParams,
-) {
-    if acpl.len() == 0 {
-        println!(
-            "{}",
-            String::from("at least 1 ACPL is required to generate an access token!").bold()
-        );
-        return;
+) -> anyhow::Result<()> {
+    if acpl.is_empty() {
+        return Err(anyhow!(
+            "at least 1 ACPL is required to generate an access token!"
+        ));
     }
 
     let expires_at: DateTime<Utc> = exp_input.get_expires_at();
 
-    let res_data = api::tokens::generate_access_token(&acpl, &expires_at)
+    let res_data = api::tokens::generate_access_token(&get_client()?, acpl, &expires_at)
         .await
-        .expect("error occured while requesting API for a new access token!");
+        .context("error occured while requesting API for a new access token!")?;
     let access_token_data: AccessToken = res_data
         .access_token
         .parse()
-        .expect("access token parsing error!");
+        .context("access token parsing error!")?;
 
     println!("{} ", res_data.access_token.cyan().bold());
 
@@ -303,7 +333,7 @@ pub async fn handle_generate(
         format!(
             "example usage in URL: {}",
             utils::files::get_share_url(Some(&res_data.access_token), "FILE_ID")
-                .expect("unexpected error occured while generating base url!")
+                .context("unexpected error occured while generating base url!")?
                 .to_string()
                 .blue()
         )
@@ -324,6 +354,8 @@ pub async fn handle_generate(
             Ok(())
         });
     }
+
+    Ok(())
 }
 
 pub fn delete_exp_tokens(state_mut: &mut PersistentState) -> anyhow::Result<()> {
@@ -349,13 +381,10 @@ pub fn delete_exp_tokens(state_mut: &mut PersistentState) -> anyhow::Result<()>
             state.tokens.remove(&k);
         }
 
-        match &state.active_token {
-            ActiveToken::Tag(tagname) => {
-                if state.tokens.get(tagname).is_none() {
-                    state.active_token = ActiveToken::RootAccessToken;
-                }
+        if let ActiveToken::Tag(tagname) = &state.active_token {
+            if !state.tokens.contains_key(tagname) {
+                state.active_token = ActiveToken::RootAccessToken;
             }
-            _ => {}
         }
 
         Ok(())
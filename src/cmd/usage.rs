@@ -1,26 +1,27 @@
-use std::u8;
-
+use anyhow::Context;
 use clap::Parser;
 use colored::{Color, Colorize};
 
-use crate::{api, shared_types::CliSubCmd, utils};
+use sfs_cli::{api, utils};
+
+use crate::cmd::{get_client, output, CliSubCmd};
 
 #[derive(Debug)]
-struct RGB(u8, u8, u8);
+struct Rgb(u8, u8, u8);
 
-impl Into<Color> for RGB {
-    fn into(self) -> Color {
-        return Color::TrueColor {
-            r: self.0,
-            g: self.1,
-            b: self.2,
-        };
+impl From<Rgb> for Color {
+    fn from(val: Rgb) -> Self {
+        Color::TrueColor {
+            r: val.0,
+            g: val.1,
+            b: val.2,
+        }
     }
 }
 
 /// fraction should be in range [0, 1]
-fn get_target_color(start: &RGB, end: &RGB, fraction: f32) -> RGB {
-    RGB(
+fn get_target_color(start: &Rgb, end: &Rgb, fraction: f32) -> Rgb {
+    Rgb(
         (start.0 as f32 + (end.0 as f32 - start.0 as f32) * fraction).min(u8::MAX as f32) as u8,
         (start.1 as f32 + (end.1 as f32 - start.1 as f32) * fraction).min(u8::MAX as f32) as u8,
         (start.2 as f32 + (end.2 as f32 - start.2 as f32) * fraction).min(u8::MAX as f32) as u8,
@@ -31,13 +32,17 @@ fn get_target_color(start: &RGB, end: &RGB, fraction: f32) -> RGB {
 pub struct UsageCommand;
 
 impl CliSubCmd for UsageCommand {
-    async fn run(&self) {
-        let usage = api::usage::get_api_usage()
+    async fn run(&self) -> anyhow::Result<()> {
+        let usage = api::usage::get_api_usage(&get_client()?)
             .await
-            .expect("error occured while fetching api key usage!");
+            .context("error occured while fetching api key usage!")?;
 
-        let start = RGB(20, 234, 37);
-        let end = RGB(234, 37, 20);
+        if !output::is_text() {
+            return output::print_value(&usage);
+        }
+
+        let start = Rgb(20, 234, 37);
+        let end = Rgb(234, 37, 20);
 
         let reads_color = get_target_color(
             &start,
@@ -57,47 +62,36 @@ impl CliSubCmd for UsageCommand {
 
         println!(
             "using: {}",
-            format!(
-                "{}",
-                utils::x2str::bytes2str((usage.storage_gb_used * 1024_i64.pow(3) as f32) as u64)
-            )
-            .bold()
+            utils::x2str::bytes2str((usage.storage_gb_used * 1024_i64.pow(3) as f32) as u64).bold()
         );
         println!();
 
         println!(
-            "{}",
-            format!(
-                "{} {} / {} {}",
-                "-".dimmed(),
-                usage.reads_used.to_string().bold().color(reads_color),
-                usage.reads_limit.to_string().bold(),
-                "reads".dimmed()
-            )
+            "{} {} / {} {}",
+            "-".dimmed(),
+            usage.reads_used.to_string().bold().color(reads_color),
+            usage.reads_limit.to_string().bold(),
+            "reads".dimmed()
         );
         println!(
-            "{}",
-            format!(
-                "{} {} / {} {}",
-                "-".dimmed(),
-                usage.writes_used.to_string().bold().color(writes_color),
-                usage.writes_limit.to_string().bold(),
-                "writes".dimmed()
-            )
+            "{} {} / {} {}",
+            "-".dimmed(),
+            usage.writes_used.to_string().bold().color(writes_color),
+            usage.writes_limit.to_string().bold(),
+            "writes".dimmed()
         );
         println!(
-            "{}",
-            format!(
-                "{} {} / {} {}",
-                "-".dimmed(),
-                usage
-                    .storage_gb_hour_used
-                    .to_string()
-                    .bold()
-                    .color(storage_gbh_color),
-                usage.storage_gb_hour_limit.to_string().bold(),
-                "GB-hr".dimmed()
-            )
+            "{} {} / {} {}",
+            "-".dimmed(),
+            usage
+                .storage_gb_hour_used
+                .to_string()
+                .bold()
+                .color(storage_gbh_color),
+            usage.storage_gb_hour_limit.to_string().bold(),
+            "GB-hr".dimmed()
         );
+
+        Ok(())
     }
 }
@@ -0,0 +1,539 @@
+use std::{
+    env, fs,
+    io::{self, Read},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context};
+use base64::prelude::*;
+use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use colored::Colorize;
+use indicatif::ProgressBar;
+use rand::distributions::{Alphanumeric, DistString};
+use tiny_http::{Header, Request, Response, ResponseBox, Server};
+use tokio::task;
+
+use sfs_cli::{
+    api::{self, dirtree::MvOpts, downloads::DownloadFileOpts, fs_files::DeleteFilesReqBody},
+    constants,
+    error::SfsError,
+    shared_types::{DirTree, FsFile, UploadBlobMetadata},
+    utils::{self, checksum},
+    SfsClient,
+};
+
+use crate::cmd::{get_client, CliSubCmd};
+
+const ALLOWED_METHODS: &str = "OPTIONS, PROPFIND, GET, HEAD, PUT, DELETE, MKCOL, MOVE";
+
+#[derive(Parser)]
+pub struct WebdavCommand {
+    #[arg(long, default_value_t = constants::WEBDAV_DEFAULT_PORT)]
+    /// port to listen on, only connections from this machine are accepted. clients log in with
+    /// the user & password printed at startup
+    port: u16,
+
+    #[arg(long)]
+    /// ask for the password of encrypted files once at startup, they can't be read without it (also: PASSWORD)
+    password: bool,
+}
+
+/// serves the remote file system over WebDAV, every request is sent to the api with the token
+/// that was active when the server started
+struct WebdavServer {
+    client: SfsClient,
+    password: Option<String>,
+    /// `Host` headers the server answers to, anything else could be a DNS rebinding attempt
+    allowed_hosts: [String; 2],
+    /// expected `Authorization` header, with the password generated for this run
+    authorization: String,
+}
+
+/// temporary file served as a response body, deleted once the response is dropped
+struct TempFileReader {
+    file: Option<fs::File>,
+    path: PathBuf,
+}
+
+impl Read for TempFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.file {
+            Some(file) => file.read(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for TempFileReader {
+    fn drop(&mut self) {
+        // closed first, open files can't be deleted on windows
+        self.file.take();
+        _ = fs::remove_file(&self.path);
+    }
+}
+
+/// entry of a PROPFIND response
+struct DavEntry<'a> {
+    path: String,
+    file: Option<&'a FsFile>,
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// e.g. "Mon, 01 Jan 2024 00:00:00 GMT"
+fn get_http_date(date: &DateTime<Local>) -> String {
+    date.with_timezone(&Utc)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+fn get_header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("invalid header!")
+}
+
+fn get_request_header(request: &Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.to_string())
+}
+
+/// absolute remote path of a request url or a `Destination` header, without the trailing "/"
+fn get_remote_path(url: &str) -> anyhow::Result<String> {
+    let path = match url::Url::parse(url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => url.split('?').next().unwrap_or_default().to_string(),
+    };
+    let path = urlencoding::decode(&path).context("malformed path!")?;
+
+    Ok(utils::dirtree::get_absolute_path(
+        path.trim_start_matches('/'),
+        "/",
+    ))
+}
+
+fn get_href(path: &str, is_dir: bool) -> String {
+    let mut href = path
+        .split('/')
+        .map(|seg| urlencoding::encode(seg).into_owned())
+        .collect::<Vec<String>>()
+        .join("/");
+    if is_dir && !href.ends_with('/') {
+        href.push('/');
+    }
+
+    href
+}
+
+/// status for a failed api call, the same errors that set the CLI's exit code
+fn get_status_code(err: &anyhow::Error) -> u16 {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<SfsError>() {
+            return match err {
+                SfsError::Usage(_) => 400,
+                SfsError::Auth(_) => 401,
+                SfsError::PermissionDenied(_) | SfsError::Decryption(_) => 403,
+                SfsError::NotFound(_) => 404,
+                SfsError::AlreadyExists(_) => 409,
+                SfsError::Quota(_) => 507,
+                SfsError::Network(_) | SfsError::Api { .. } => 502,
+            };
+        }
+    }
+
+    500
+}
+
+fn get_error_response(status_code: u16, message: &str) -> ResponseBox {
+    Response::from_string(message)
+        .with_status_code(status_code)
+        .boxed()
+}
+
+fn get_propfind_xml(entries: &[DavEntry]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">\n",
+    );
+
+    for entry in entries {
+        let (_, name) = utils::dirtree::split_path(&entry.path);
+        let props = match entry.file {
+            Some(file) => format!(
+                "<D:displayname>{}</D:displayname><D:resourcetype/><D:getcontentlength>{}</D:getcontentlength><D:getcontenttype>{}</D:getcontenttype><D:getlastmodified>{}</D:getlastmodified><D:creationdate>{}</D:creationdate><D:getetag>\"{}-{}\"</D:getetag>",
+                xml_escape(name),
+                file.get_plain_file_size().unwrap_or(file.file_size as u64),
+                xml_escape(file.content_type.as_deref().unwrap_or(file.get_filetype())),
+                get_http_date(&file.updated_at),
+                file.created_at.to_rfc3339(),
+                xml_escape(&file.storage_id),
+                file.updated_at.timestamp(),
+            ),
+            None => format!(
+                "<D:displayname>{}</D:displayname><D:resourcetype><D:collection/></D:resourcetype>",
+                xml_escape(name)
+            ),
+        };
+
+        xml += &format!(
+            "<D:response><D:href>{}</D:href><D:propstat><D:prop>{props}</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>\n",
+            xml_escape(&get_href(&entry.path, entry.file.is_none()))
+        );
+    }
+    xml += "</D:multistatus>\n";
+
+    xml
+}
+
+impl WebdavServer {
+    async fn get_dirtree(&self) -> anyhow::Result<DirTree> {
+        Ok(api::dirtree::get_dirtree(&self.client).await?.dirtree)
+    }
+
+    async fn get_file(&self, path: &str) -> anyhow::Result<FsFile> {
+        api::fs_files::get_file(&self.client, path)
+            .await?
+            .ok_or(SfsError::NotFound(format!("'{path}' not found!")).into())
+    }
+
+    /// `depth` "infinity" is served as 1, walking the whole tree for one request is too expensive
+    async fn handle_propfind(&self, path: &str, depth: &str) -> anyhow::Result<ResponseBox> {
+        let dirtree = self.get_dirtree().await?;
+        let xml = match dirtree.get_sub_tree(path) {
+            Some(dir) => {
+                let files = match depth {
+                    "0" => vec![],
+                    _ => self.client.list_files(path).await?,
+                };
+
+                let mut entries = vec![DavEntry {
+                    path: path.to_string(),
+                    file: None,
+                }];
+                if depth != "0" {
+                    entries.extend(dir.children.iter().map(|child| DavEntry {
+                        path: utils::dirtree::join_paths(&[path, &child.name]),
+                        file: None,
+                    }));
+                    entries.extend(files.iter().map(|file| DavEntry {
+                        path: utils::dirtree::join_paths(&[path, &file.name]),
+                        file: Some(file),
+                    }));
+                }
+
+                get_propfind_xml(&entries)
+            }
+            None => {
+                let file = self.get_file(path).await?;
+                get_propfind_xml(&[DavEntry {
+                    path: path.to_string(),
+                    file: Some(&file),
+                }])
+            }
+        };
+
+        Ok(Response::from_string(xml)
+            .with_status_code(207)
+            .with_header(get_header("Content-Type", "application/xml; charset=utf-8"))
+            .boxed())
+    }
+
+    /// files are downloaded to a temporary file first, decryption & decompression need the whole
+    /// file anyway
+    async fn handle_get(&self, path: &str, is_head: bool) -> anyhow::Result<ResponseBox> {
+        let file = self.get_file(path).await?;
+        let headers = vec![
+            get_header(
+                "Content-Type",
+                file.content_type.as_deref().unwrap_or(file.get_filetype()),
+            ),
+            get_header("Last-Modified", &get_http_date(&file.updated_at)),
+        ];
+
+        if is_head {
+            let content_length = file.get_plain_file_size().map(|size| size as usize);
+            return Ok(
+                Response::new(200.into(), headers, io::empty(), content_length, None).boxed(),
+            );
+        }
+
+        // deleted once dropped, whether the download fails or the response is sent
+        let mut body = TempFileReader {
+            file: None,
+            path: env::temp_dir().join(format!("sfs-webdav-{}", api::utils::get_random_filename())),
+        };
+        api::downloads::download_file(
+            &self.client,
+            &file,
+            self.client.get_token(),
+            DownloadFileOpts::new(
+                body.path.clone(),
+                self.password.clone(),
+                ProgressBar::hidden(),
+            ),
+        )
+        .await?;
+
+        let download_file = fs::File::open(&body.path)?;
+        let content_length = download_file.metadata()?.len() as usize;
+        body.file = Some(download_file);
+
+        Ok(Response::new(200.into(), headers, body, Some(content_length), None).boxed())
+    }
+
+    /// the body is written to a temporary file first, uploads are streamed from it
+    async fn handle_put(
+        &self,
+        request: &mut Request,
+        path: &str,
+        content_type: Option<String>,
+    ) -> anyhow::Result<ResponseBox> {
+        let (dirpath, filename) = utils::dirtree::split_path(path);
+        let dirpath = dirpath.trim_end_matches('/');
+        let dirpath = if dirpath.is_empty() { "/" } else { dirpath };
+        if filename.is_empty() {
+            return Ok(get_error_response(409, "cannot PUT to a directory!"));
+        }
+
+        let upload_filepath =
+            env::temp_dir().join(format!("sfs-webdav-{}", api::utils::get_random_filename()));
+        let res = self
+            .upload_request_body(request, &upload_filepath, dirpath, filename, content_type)
+            .await;
+        _ = fs::remove_file(&upload_filepath);
+        res?;
+
+        Ok(Response::empty(201).boxed())
+    }
+
+    async fn upload_request_body(
+        &self,
+        request: &mut Request,
+        upload_filepath: &PathBuf,
+        dirpath: &str,
+        filename: &str,
+        content_type: Option<String>,
+    ) -> anyhow::Result<()> {
+        task::block_in_place(|| -> io::Result<u64> {
+            io::copy(request.as_reader(), &mut fs::File::create(upload_filepath)?)
+        })
+        .context("error occured while reading request body!")?;
+
+        let checksum = task::block_in_place(|| checksum::get_file_checksum(upload_filepath))
+            .context("error occured while hashing request body!")?;
+        let stream = utils::streams::read_into_stream(
+            tokio::fs::File::open(upload_filepath).await?,
+            constants::FILE_STREAM_READ_BUF_SIZE,
+            None,
+            None,
+        );
+        let upload_metadata = UploadBlobMetadata {
+            name: filename.to_string(),
+            content_type,
+            is_public: false,
+            encryption: None,
+            cache_max_age_seconds: None,
+            dir_path: dirpath.to_string(),
+            // PUT replaces the file if it exists
+            force_write: true,
+            deleted_at: None,
+            checksum: Some(checksum),
+            compression: None,
+        };
+
+        api::uploads::upload_blob_stream(&self.client, stream, &upload_metadata)
+            .await
+            .context("error occured while uploading file!")?;
+
+        Ok(())
+    }
+
+    async fn handle_move(
+        &self,
+        path: &str,
+        destination: Option<String>,
+    ) -> anyhow::Result<ResponseBox> {
+        let new_path = match destination {
+            Some(destination) => get_remote_path(&destination)?,
+            None => return Ok(get_error_response(400, "'Destination' header is missing!")),
+        };
+
+        let dirtree = self.get_dirtree().await?;
+        match dirtree.get_sub_tree(path).is_some() && path != "/" {
+            true => {
+                api::dirtree::mvdir(&self.client, path, &new_path).await?;
+            }
+            false => {
+                api::dirtree::mv(
+                    &self.client,
+                    &MvOpts {
+                        file_path: path,
+                        new_file_path: &new_path,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        Ok(Response::empty(201).boxed())
+    }
+
+    async fn handle_delete(&self, path: &str) -> anyhow::Result<ResponseBox> {
+        if path == "/" {
+            return Ok(get_error_response(403, "root directory cannot be deleted!"));
+        }
+
+        let dirtree = self.get_dirtree().await?;
+        match dirtree.get_sub_tree(path).is_some() {
+            true => {
+                api::dirtree::rmdir(&self.client, path).await?;
+            }
+            false => {
+                let (dirpath, filename) = utils::dirtree::split_path(path);
+                api::fs_files::delete_files(
+                    &self.client,
+                    &DeleteFilesReqBody {
+                        dir_path: dirpath,
+                        file_names: &vec![filename.to_string()],
+                    },
+                )
+                .await?;
+            }
+        }
+
+        Ok(Response::empty(204).boxed())
+    }
+
+    /// error response for requests from anything but a local client that knows the password
+    fn check_request(&self, request: &Request) -> Option<ResponseBox> {
+        let host = get_request_header(request, "Host").unwrap_or_default();
+        if !self.allowed_hosts.contains(&host) {
+            return Some(get_error_response(403, "invalid 'Host' header!"));
+        }
+
+        let authorization = get_request_header(request, "Authorization").unwrap_or_default();
+        match orion::util::secure_cmp(authorization.as_bytes(), self.authorization.as_bytes()) {
+            Ok(()) => None,
+            Err(_) => Some(get_error_response(401, "invalid credentials!").with_header(
+                get_header("WWW-Authenticate", "Basic realm=\"sfs\", charset=\"UTF-8\""),
+            )),
+        }
+    }
+
+    async fn handle(&self, request: &mut Request) -> anyhow::Result<ResponseBox> {
+        if let Some(response) = self.check_request(request) {
+            return Ok(response);
+        }
+
+        let path = match get_remote_path(request.url()) {
+            Ok(path) => path,
+            Err(err) => return Ok(get_error_response(400, &err.to_string())),
+        };
+
+        // headers are read before any request is sent, `Request` can't be shared across threads
+        let method = request.method().to_string();
+        let depth = get_request_header(request, "Depth");
+        let destination = get_request_header(request, "Destination");
+        let content_type = get_request_header(request, "Content-Type");
+
+        match method.as_str() {
+            "OPTIONS" => Ok(Response::empty(200)
+                .with_header(get_header("DAV", "1"))
+                .with_header(get_header("Allow", ALLOWED_METHODS))
+                .boxed()),
+            "PROPFIND" => {
+                self.handle_propfind(&path, depth.as_deref().unwrap_or("1"))
+                    .await
+            }
+            "GET" => self.handle_get(&path, false).await,
+            "HEAD" => self.handle_get(&path, true).await,
+            "PUT" => self.handle_put(request, &path, content_type).await,
+            "MKCOL" => {
+                api::dirtree::mkdir(&self.client, &path).await?;
+                Ok(Response::empty(201).boxed())
+            }
+            "MOVE" => self.handle_move(&path, destination).await,
+            "DELETE" => self.handle_delete(&path).await,
+            _ => Ok(Response::empty(405)
+                .with_header(get_header("Allow", ALLOWED_METHODS))
+                .boxed()),
+        }
+    }
+}
+
+impl CliSubCmd for WebdavCommand {
+    async fn run(&self) -> anyhow::Result<()> {
+        let password = match self.password || env::var("PASSWORD").is_ok() {
+            true => Some(
+                utils::crypto::get_decryption_password()
+                    .context("error occured while reading password!")?,
+            ),
+            false => None,
+        };
+        let webdav_password =
+            Alphanumeric.sample_string(&mut rand::thread_rng(), constants::WEBDAV_PASSWORD_LEN);
+        let webdav = Arc::new(WebdavServer {
+            client: get_client()?,
+            password,
+            allowed_hosts: [
+                format!("127.0.0.1:{}", self.port),
+                format!("localhost:{}", self.port),
+            ],
+            authorization: format!(
+                "Basic {}",
+                BASE64_STANDARD.encode(format!("{}:{webdav_password}", constants::WEBDAV_USERNAME))
+            ),
+        });
+
+        let server = Arc::new(
+            Server::http(("127.0.0.1", self.port))
+                .map_err(|err| anyhow!("error occured while starting server!\n{err}"))?,
+        );
+        println!(
+            "WebDAV server started at {}",
+            format!("http://127.0.0.1:{}", self.port).bold()
+        );
+        println!(
+            "user: {}, password: {} (generated for this run only)",
+            constants::WEBDAV_USERNAME.bold(),
+            webdav_password.bold()
+        );
+
+        loop {
+            let server = Arc::clone(&server);
+            let mut request = task::spawn_blocking(move || server.recv())
+                .await?
+                .context("error occured while receiving request!")?;
+
+            let webdav = Arc::clone(&webdav);
+            task::spawn(async move {
+                let method = request.method().to_string();
+                let url = request.url().to_string();
+
+                let response = match webdav.handle(&mut request).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        eprintln!("{}", format!("{method} {url}: {err:#}").red());
+                        get_error_response(get_status_code(&err), &err.to_string())
+                    }
+                };
+                println!(
+                    "{} {} {}",
+                    method,
+                    url,
+                    response.status_code().0.to_string().dimmed()
+                );
+
+                _ = task::spawn_blocking(move || request.respond(response)).await;
+            });
+        }
+    }
+}
@@ -1,41 +1,144 @@
 use clap::ValueEnum;
 use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fmt::Display,
     fs, io, path,
-    sync::{Arc, LazyLock, RwLock},
+    sync::{LazyLock, OnceLock, RwLock},
+    time::Duration,
 };
 
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::utils::paths::get_absolute_path;
+use crate::{constants, error::SfsError, utils::paths::get_absolute_path};
 
-#[derive(Serialize, Deserialize, PartialEq, Clone, ValueEnum)]
+#[derive(Serialize, Deserialize, PartialEq, Clone, Default, ValueEnum)]
 pub enum LogLevel {
+    #[default]
     Chirpy,
     Normal,
     Stfu,
 }
 
-impl Default for LogLevel {
-    fn default() -> Self {
-        Self::Chirpy
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
 pub struct CliConfig {
-    #[serde(skip_serializing_if = "is_default")]
     base_url: String,
 
-    #[serde(skip_serializing_if = "is_default")]
     github_client_id: String,
 
-    #[serde(skip_serializing_if = "is_default")]
     log_level: LogLevel,
+
+    /// failed api requests that are safe to repeat are retried this many times
+    max_retries: u32,
+
+    connect_timeout_secs: u64,
+
+    /// max wait for a response or the next bytes of its body, time spent sending the request's
+    /// body (e.g. a throttled upload) doesn't count
+    read_timeout_secs: u64,
+
+    /// limit on a whole request including its body, unset by default as large transfers can
+    /// take arbitrarily long
+    timeout_secs: Option<u64>,
+
+    /// profile used when neither `--profile` nor `SFS_PROFILE` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_profile: Option<String>,
+
+    /// named profiles besides the default one made of the top level values
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, ProfileConfig>,
+
+    /// `SFS_BASE_URL`, never saved to the config file
+    #[serde(skip)]
+    base_url_override: Option<String>,
+
+    /// name of the selected profile, resolved when the config is loaded
+    #[serde(skip)]
+    profile: String,
+
+    /// settings present in the config file, the rest are defaults and aren't written to it
+    #[serde(skip)]
+    file_keys: BTreeSet<String>,
 }
 
-fn is_default<T: Default + PartialEq>(value: &T) -> bool {
-    value == &T::default()
+/// settings that can be changed with `config get/set/unset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum ConfigKey {
+    BaseUrl,
+    GithubClientId,
+    LogLevel,
+    MaxRetries,
+    ConnectTimeoutSecs,
+    ReadTimeoutSecs,
+    TimeoutSecs,
+}
+
+impl ConfigKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BaseUrl => "base_url",
+            Self::GithubClientId => "github_client_id",
+            Self::LogLevel => "log_level",
+            Self::MaxRetries => "max_retries",
+            Self::ConnectTimeoutSecs => "connect_timeout_secs",
+            Self::ReadTimeoutSecs => "read_timeout_secs",
+            Self::TimeoutSecs => "timeout_secs",
+        }
+    }
+}
+
+/// where the effective value of a setting comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueSource {
+    Default,
+    File,
+    /// the selected profile's value
+    Profile,
+    /// an `SFS_*` environment variable
+    Env,
+}
+
+impl Display for ConfigValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File => write!(f, "file"),
+            Self::Profile => write!(f, "profile"),
+            Self::Env => write!(f, "env"),
+        }
+    }
+}
+
+/// a deployment to talk to, each profile has its own login, tokens, working directory and upload
+/// journals
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileConfig {
+    pub base_url: String,
+
+    /// defaults to the top level `github_client_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_client_id: Option<String>,
+}
+
+/// `--profile`, set before the config is first read
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+pub fn set_profile_override(profile: String) {
+    _ = PROFILE_OVERRIDE.set(profile);
+}
+
+/// directory holding the profile's local state, `~/.sfs` for the default profile
+pub fn get_profile_dirpath(profile: &str) -> io::Result<path::PathBuf> {
+    match profile == constants::DEFAULT_PROFILE {
+        true => get_absolute_path("~/.sfs"),
+        false => get_absolute_path(format!("~/.sfs/profiles/{profile}")),
+    }
 }
 
 impl Default for CliConfig {
@@ -44,6 +147,15 @@ impl Default for CliConfig {
             base_url: "https://api.simplefs.io".to_string(),
             github_client_id: "Ov23li5rbT6pIfVXc7Rv".to_string(),
             log_level: LogLevel::Chirpy,
+            max_retries: constants::API_MAX_RETRIES,
+            connect_timeout_secs: constants::HTTP_CONNECT_TIMEOUT_SECS,
+            read_timeout_secs: constants::HTTP_READ_TIMEOUT_SECS,
+            timeout_secs: None,
+            active_profile: None,
+            profiles: BTreeMap::new(),
+            base_url_override: None,
+            profile: constants::DEFAULT_PROFILE.to_string(),
+            file_keys: BTreeSet::new(),
         }
     }
 }
@@ -59,7 +171,10 @@ impl CliConfig {
             .recursive(true)
             .create(config_filepath.parent().unwrap())?;
 
-        let toml_str = toml::to_string(&self)?;
+        let mut table = toml::Table::try_from(self)?;
+        table.retain(|k, _| self.file_keys.contains(k) || ConfigKey::from_str(k, false).is_err());
+
+        let toml_str = toml::to_string(&table)?;
         fs::write(config_filepath, toml_str)?;
 
         Ok(())
@@ -68,21 +183,248 @@ impl CliConfig {
     pub fn get_gh_login_uri(&self) -> String {
         format!(
             "https://github.com/login/oauth/authorize?client_id={}&redirect_uri={}/auth/gh-callback&scope=repo,user,user:email",
-            self.github_client_id,
-            self.base_url,
+            self.get_profile_config()
+                .and_then(|p| p.github_client_id.as_deref())
+                .unwrap_or(&self.github_client_id),
+            self.get_base_url(),
         )
     }
 
     pub fn get_base_url(&self) -> &str {
-        self.base_url.as_str()
+        if let Some(base_url) = &self.base_url_override {
+            return base_url;
+        }
+
+        match self.get_profile_config() {
+            Some(profile_config) => &profile_config.base_url,
+            None => &self.base_url,
+        }
+    }
+
+    /// selected by `--profile`, `SFS_PROFILE` or `config profiles use`, in that order
+    pub fn get_profile(&self) -> &str {
+        &self.profile
+    }
+
+    fn get_profile_config(&self) -> Option<&ProfileConfig> {
+        self.profiles.get(&self.profile)
+    }
+
+    /// errors if the selected profile was never added
+    pub fn check_profile(&self) -> anyhow::Result<()> {
+        if self.profile != constants::DEFAULT_PROFILE && self.get_profile_config().is_none() {
+            return Err(SfsError::NotFound(format!(
+                "profile '{}' does not exist! add it with `config profiles add`.",
+                self.profile
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// (name, base url, is selected), the default profile comes first
+    pub fn get_profiles(&self) -> Vec<(&str, &str, bool)> {
+        let mut profiles = vec![(
+            constants::DEFAULT_PROFILE,
+            self.base_url.as_str(),
+            self.profile == constants::DEFAULT_PROFILE,
+        )];
+        profiles.extend(self.profiles.iter().map(|(name, profile_config)| {
+            (
+                name.as_str(),
+                profile_config.base_url.as_str(),
+                *name == self.profile,
+            )
+        }));
+
+        profiles
+    }
+
+    /// adds the profile or replaces the one with the same name
+    pub fn add_profile(&mut self, name: &str, profile_config: ProfileConfig) -> anyhow::Result<()> {
+        let is_valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_valid_name || name == constants::DEFAULT_PROFILE {
+            return Err(anyhow!(
+                "invalid profile name '{name}'! use letters, digits, '-' and '_' only ('{}' is reserved).",
+                constants::DEFAULT_PROFILE
+            ));
+        }
+        self.profiles.insert(name.to_string(), profile_config);
+
+        self.save_to_file()?;
+
+        Ok(())
+    }
+
+    /// the default profile is selected again if the removed one was
+    pub fn remove_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        if self.profiles.remove(name).is_none() {
+            return Err(SfsError::NotFound(format!("profile '{name}' does not exist!")).into());
+        }
+        if self.active_profile.as_deref() == Some(name) {
+            self.active_profile = None;
+        }
+
+        self.save_to_file()?;
+
+        Ok(())
+    }
+
+    pub fn set_active_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        if name != constants::DEFAULT_PROFILE && !self.profiles.contains_key(name) {
+            return Err(SfsError::NotFound(format!("profile '{name}' does not exist!")).into());
+        }
+        self.active_profile = match name == constants::DEFAULT_PROFILE {
+            true => None,
+            false => Some(name.to_string()),
+        };
+
+        self.save_to_file()?;
+
+        Ok(())
     }
 
     pub fn get_log_level(&self) -> LogLevel {
         self.log_level.clone()
     }
 
+    pub fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn get_connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    pub fn get_read_timeout(&self) -> Duration {
+        Duration::from_secs(self.read_timeout_secs)
+    }
+
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
     pub fn set_log_level(&mut self, log_level: LogLevel) -> anyhow::Result<()> {
         self.log_level = log_level;
+        self.file_keys
+            .insert(ConfigKey::LogLevel.as_str().to_string());
+
+        self.save_to_file()?;
+
+        Ok(())
+    }
+
+    /// value as written in the config file, `None` for unset optional settings. top level values
+    /// only, see `get_effective_value` for the ones in use.
+    pub fn get_value(&self, key: ConfigKey) -> anyhow::Result<Option<String>> {
+        let table = toml::Table::try_from(self)?;
+
+        Ok(table.get(key.as_str()).map(|value| match value {
+            toml::Value::String(s) => s.clone(),
+            value => value.to_string(),
+        }))
+    }
+
+    /// value in use after applying the selected profile & env overrides
+    pub fn get_effective_value(
+        &self,
+        key: ConfigKey,
+    ) -> anyhow::Result<(Option<String>, ConfigValueSource)> {
+        if key == ConfigKey::BaseUrl {
+            if let Some(base_url) = &self.base_url_override {
+                return Ok((Some(base_url.clone()), ConfigValueSource::Env));
+            }
+        }
+
+        let profile_value = self.get_profile_config().and_then(|p| match key {
+            ConfigKey::BaseUrl => Some(p.base_url.clone()),
+            ConfigKey::GithubClientId => p.github_client_id.clone(),
+            _ => None,
+        });
+        if profile_value.is_some() {
+            return Ok((profile_value, ConfigValueSource::Profile));
+        }
+
+        let source = match self.file_keys.contains(key.as_str()) {
+            true => ConfigValueSource::File,
+            false => ConfigValueSource::Default,
+        };
+
+        Ok((self.get_value(key)?, source))
+    }
+
+    /// validates and saves the value to the config file
+    pub fn set_value(&mut self, key: ConfigKey, value: &str) -> anyhow::Result<()> {
+        let usage_err = |reason: &str| {
+            SfsError::Usage(format!(
+                "invalid value '{value}' for '{}', {reason}",
+                key.as_str()
+            ))
+        };
+        let parse_number = |min: u64, max: u64| {
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| usage_err(&format!("expected a number from {min} to {max}")))
+        };
+
+        match key {
+            ConfigKey::BaseUrl => {
+                Url::parse(value).map_err(|err| usage_err(&err.to_string()))?;
+                self.base_url = value.trim_end_matches('/').to_string();
+            }
+            ConfigKey::GithubClientId => self.github_client_id = value.to_string(),
+            ConfigKey::LogLevel => {
+                self.log_level = LogLevel::from_str(value, true).map_err(|_| {
+                    let possible_values = LogLevel::value_variants()
+                        .iter()
+                        .filter_map(|v| v.to_possible_value())
+                        .map(|v| v.get_name().to_string())
+                        .collect::<Vec<String>>();
+                    usage_err(&format!("possible values: {}", possible_values.join(", ")))
+                })?
+            }
+            ConfigKey::MaxRetries => {
+                self.max_retries = parse_number(0, constants::CONFIG_MAX_RETRIES_LIMIT)? as u32
+            }
+            ConfigKey::ConnectTimeoutSecs => {
+                self.connect_timeout_secs = parse_number(1, constants::CONFIG_TIMEOUT_SECS_LIMIT)?
+            }
+            ConfigKey::ReadTimeoutSecs => {
+                self.read_timeout_secs = parse_number(1, constants::CONFIG_TIMEOUT_SECS_LIMIT)?
+            }
+            ConfigKey::TimeoutSecs => {
+                self.timeout_secs = Some(parse_number(1, constants::CONFIG_TIMEOUT_SECS_LIMIT)?)
+            }
+        }
+        self.file_keys.insert(key.as_str().to_string());
+
+        self.save_to_file()?;
+
+        Ok(())
+    }
+
+    /// removes the value from the config file, the default is used again
+    pub fn unset_value(&mut self, key: ConfigKey) -> anyhow::Result<()> {
+        let default_config = Self::default();
+
+        match key {
+            ConfigKey::BaseUrl => self.base_url = default_config.base_url,
+            ConfigKey::GithubClientId => self.github_client_id = default_config.github_client_id,
+            ConfigKey::LogLevel => self.log_level = default_config.log_level,
+            ConfigKey::MaxRetries => self.max_retries = default_config.max_retries,
+            ConfigKey::ConnectTimeoutSecs => {
+                self.connect_timeout_secs = default_config.connect_timeout_secs
+            }
+            ConfigKey::ReadTimeoutSecs => self.read_timeout_secs = default_config.read_timeout_secs,
+            ConfigKey::TimeoutSecs => self.timeout_secs = default_config.timeout_secs,
+        }
+        self.file_keys.remove(key.as_str());
 
         self.save_to_file()?;
 
@@ -92,17 +434,32 @@ impl CliConfig {
 
 pub static CONFIG: LazyLock<RwLock<CliConfig>> = LazyLock::new(|| {
     let config_filepath = CliConfig::get_config_filepath();
-    let file_contents = match fs::read_to_string(config_filepath) {
-        Ok(contents) => contents,
+    let mut config: CliConfig = match fs::read_to_string(config_filepath) {
+        Ok(contents) => {
+            let table: toml::Table = toml::from_str(&contents).unwrap();
+            let mut config: CliConfig = table.clone().try_into().unwrap();
+            config.file_keys = table.into_iter().map(|(k, _)| k).collect();
+            config
+        }
         Err(err) if err.kind() == io::ErrorKind::NotFound => {
             let default_config = CliConfig::default();
             default_config
                 .save_to_file()
                 .expect("error occured while saving default config to file");
-            return default_config.into();
+            default_config
         }
         Err(err) => panic!("{}", err),
     };
+    config.base_url_override = env::var(constants::ENV_BASE_URL)
+        .ok()
+        .filter(|base_url| !base_url.is_empty());
+    config.profile = PROFILE_OVERRIDE
+        .get()
+        .cloned()
+        .or(env::var(constants::ENV_PROFILE).ok())
+        .filter(|profile| !profile.is_empty())
+        .or(config.active_profile.clone())
+        .unwrap_or(constants::DEFAULT_PROFILE.to_string());
 
-    toml::from_str(&file_contents).unwrap()
+    config.into()
 });
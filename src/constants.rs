@@ -6,12 +6,75 @@ pub const UNTITLED_TAG_PREFX: &str = "untitled_";
 
 pub const ROOT_ACCESS_TOKEN_TAG: &str = "login";
 
+/// access token used instead of the keyring & the local state, e.g. in CI
+pub const ENV_TOKEN: &str = "SFS_TOKEN";
+/// owner's api key, only needed along with `SFS_TOKEN` for owner only actions
+pub const ENV_API_KEY: &str = "SFS_API_KEY";
+pub const ENV_API_SECRET: &str = "SFS_API_SECRET";
+/// overrides `base_url` of the config
+pub const ENV_BASE_URL: &str = "SFS_BASE_URL";
+/// selects a profile, `--profile` takes precedence
+pub const ENV_PROFILE: &str = "SFS_PROFILE";
+
+/// profile made of the top level values of the config, its files live directly in `~/.sfs`
+pub const DEFAULT_PROFILE: &str = "default";
+
 pub const LOCAL_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 pub const FILE_STREAM_READ_BUF_SIZE: u32 = 256 * 1024;
 
+/// files at least this big are uploaded in parts
+pub const MIN_MULTIPART_UPLOAD_SIZE: u64 = 64 * 1024 * 1024;
+/// needs to be a multiple of the read buffer size, parts are made of whole (encrypted) blocks
+pub const MULTIPART_UPLOAD_PART_SIZE: u32 = 32 * FILE_STREAM_READ_BUF_SIZE;
+pub const MAX_CONCURRENT_PART_UPLOADS: usize = 4;
+/// default number of files uploaded or downloaded at once (see `--jobs`)
+pub const MAX_CONCURRENT_TRANSFERS: usize = 4;
+/// bodies sent under a rate limit are split into chunks of this size
+pub const RATE_LIMIT_CHUNK_SIZE: usize = 64 * 1024;
+/// unfinished upload journals untouched for longer are considered stale
+pub const UPLOAD_JOURNAL_TTL_DAYS: i64 = 7;
+
+/// hex digits of a file's checksum shown in listings
+pub const SHORT_CHECKSUM_LEN: usize = 8;
+
 pub const HEADER_UPLOAD_METADATA: &str = "upload-metadata";
 
+pub const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const HTTP_READ_TIMEOUT_SECS: u64 = 60;
+/// idle pooled connections are closed after this long
+pub const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+pub const HTTP_TCP_KEEPALIVE_SECS: u64 = 60;
+
+/// upper bounds accepted by `config set`, anything more is surely a typo
+pub const CONFIG_TIMEOUT_SECS_LIMIT: u64 = 24 * 60 * 60;
+pub const CONFIG_MAX_RETRIES_LIMIT: u64 = 100;
+
+/// default number of retries for failed api requests (see `max_retries` in the config)
+pub const API_MAX_RETRIES: u32 = 4;
+pub const RETRY_BASE_DELAY_MS: u64 = 500;
+pub const RETRY_MAX_DELAY_MS: u64 = 30_000;
+/// longer `Retry-After` waits asked for by the server are cut down to this
+pub const RETRY_AFTER_MAX_SECS: u64 = 120;
+
+pub const DOWNLOAD_MAX_RETRIES: u32 = 5;
+pub const PART_FILE_EXT: &str = "sfs-part";
+pub const MAX_CONCURRENT_LISTINGS: usize = 8;
+
+/// lines of `shell` history kept per profile
+pub const SHELL_HISTORY_MAX_ENTRIES: usize = 1000;
+/// remote names fetched for shell completions are reused for this long
+pub const COMPLETION_CACHE_TTL_SECS: i64 = 60;
+pub const WEBDAV_DEFAULT_PORT: u16 = 4918;
+/// basic auth user of the WebDAV server, its password is generated every time it starts
+pub const WEBDAV_USERNAME: &str = "sfs";
+pub const WEBDAV_PASSWORD_LEN: usize = 24;
+pub const DEV_SERVER_DEFAULT_PORT: u16 = 4919;
+/// files per page of `fs/get-files` responses of the dev server, if no limit is asked for
+pub const DEV_SERVER_PAGE_SIZE: usize = 100;
+/// lifetime of the owner token the dev server prints at startup
+pub const DEV_SERVER_ROOT_TOKEN_TTL_DAYS: i64 = 365;
+
 pub const UNKNOWN_FILE_EXT: &str = "bin";
 pub const UNKNOWN_MIME_TYPE: &str = "application/octet-stream";
 pub const ZIPFILE_MIME_TYPE: &str = "application/zip";
@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use base64::prelude::*;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use rand::distributions::{Alphanumeric, DistString};
+use regex::Regex;
+use sha2::Sha256;
+
+use crate::{
+    shared_types::{AccessToken, AccessTokenPermission, PermissionChar},
+    utils::{paths, str2x, x2str},
+};
+
+/// `perms:pattern` entry of an access token's ACPL, e.g. "r:/photos/**"
+pub struct AccessControlPath {
+    perms: String,
+    regex: Regex,
+    /// see `paths::get_remote_pattern_prefix`
+    prefix: String,
+}
+
+impl FromStr for AccessControlPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (perms, pattern) = s
+            .split_once(':')
+            .ok_or(anyhow!("'{s}' is not in the 'perms:pattern' format."))?;
+        let perms: AccessTokenPermission = perms.parse()?;
+        if !pattern.starts_with('/') {
+            return Err(anyhow!("pattern of '{s}' must be an absolute path."));
+        }
+
+        Ok(Self {
+            perms: perms.to_string(),
+            regex: paths::get_remote_pattern_regex(pattern)?,
+            prefix: paths::get_remote_pattern_prefix(pattern).to_string(),
+        })
+    }
+}
+
+impl AccessControlPath {
+    pub fn allows(&self, perm: &PermissionChar, path: &str) -> bool {
+        self.perms.contains(&perm.to_string()) && self.regex.is_match(path)
+    }
+
+    /// directories leading to, or lying under the fixed part of the pattern are visible in the
+    /// directory tree of readable paths
+    pub fn reaches_dir(&self, dirpath: &str) -> bool {
+        if !self.perms.contains(&PermissionChar::Read.to_string()) {
+            return false;
+        }
+        let dirpath = format!("{}/", dirpath.trim_end_matches('/'));
+
+        self.prefix.starts_with(&dirpath) || dirpath.starts_with(&self.prefix)
+    }
+}
+
+fn get_signature(secret: &str, contents: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(contents.as_bytes());
+
+    x2str::bytes2hex(&mac.finalize().into_bytes())
+}
+
+/// token in the format the api issues, `str2x::str2at` reads it back. the random last line makes
+/// every token unique even with the same ACPL & expiry.
+pub fn encode_token(secret: &str, acpl: &[String], expires_at: &DateTime<Utc>) -> String {
+    let contents = format!(
+        "{}\n{}\n{}",
+        expires_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        serde_json::to_string(acpl).expect("string lists are always serializable"),
+        Alphanumeric.sample_string(&mut rand::thread_rng(), 16)
+    );
+    let contents = BASE64_STANDARD_NO_PAD
+        .encode(contents)
+        .replace('+', "-")
+        .replace('/', "~");
+    let signature = get_signature(secret, &contents);
+
+    format!("{contents}_{signature}")
+}
+
+/// checks the signature & expiry of a token issued by `encode_token`
+pub fn decode_token(secret: &str, token: &str) -> anyhow::Result<AccessToken> {
+    let (contents, signature) = token
+        .split_once('_')
+        .ok_or(anyhow!("access token is malformed."))?;
+    if get_signature(secret, contents) != signature {
+        return Err(anyhow!("access token signature is invalid."));
+    }
+
+    let access_token = str2x::str2at(token)?;
+    if access_token.expires_at <= Local::now() {
+        return Err(anyhow!("access token is expired."));
+    }
+
+    Ok(access_token)
+}
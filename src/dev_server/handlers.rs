@@ -0,0 +1,970 @@
+use std::{cmp::Ordering, collections::BTreeMap, fs, io};
+
+use base64::prelude::*;
+use chrono::{DateTime, Local, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        fs_files::{
+            Filter, FilterCol, FilterGroup, FilterGroupType, FilterOp, GetFilesOpts, Order,
+        },
+        uploads::UploadPartResult,
+    },
+    constants,
+    shared_types::{ApiKeyUsage, DirTree, FsFile, PermissionChar, UploadBlobMetadata},
+    utils,
+};
+
+use super::{
+    acp::{self, AccessControlPath},
+    store::{self, Store},
+    ApiError, DevRequest, DevResponse, DevServerState,
+};
+
+/// limits reported for reads, writes & GB-hours, nothing is enforced
+const USAGE_LIMIT: u64 = 1_000_000;
+
+/// who is making the request
+enum Access {
+    /// api key in the `Authorization` header
+    Owner,
+    Token(Vec<AccessControlPath>),
+    /// can only read public files
+    Anonymous,
+}
+
+impl Access {
+    fn get(state: &DevServerState, store: &Store, req: &DevRequest) -> Result<Self, ApiError> {
+        if let Some(auth) = req.get_header("authorization") {
+            let creds = auth
+                .strip_prefix("Bearer ")
+                .and_then(|creds| BASE64_STANDARD.decode(creds).ok())
+                .and_then(|creds| String::from_utf8(creds).ok());
+            if creds != Some(format!("{}:{}", state.api_key, state.api_secret)) {
+                return Err(ApiError::unauthorized("invalid api key!"));
+            }
+
+            return Ok(Self::Owner);
+        }
+
+        let token = match req.get_query("token") {
+            Some(token) if !token.is_empty() => token,
+            _ => return Ok(Self::Anonymous),
+        };
+        if store.is_blacklisted(token) {
+            return Err(ApiError::unauthorized("access token is blacklisted!"));
+        }
+        let access_token = acp::decode_token(&state.api_secret, token)
+            .map_err(|err| ApiError::unauthorized(err.to_string()))?;
+        let acpl = access_token
+            .acpl
+            .iter()
+            .map(|acp| acp.parse())
+            .collect::<anyhow::Result<_>>()
+            .map_err(|err| ApiError::unauthorized(err.to_string()))?;
+
+        Ok(Self::Token(acpl))
+    }
+
+    fn allows(&self, perm: PermissionChar, path: &str) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Token(acpl) => acpl.iter().any(|acp| acp.allows(&perm, path)),
+            Self::Anonymous => false,
+        }
+    }
+
+    fn check(&self, perm: PermissionChar, path: &str) -> Result<(), ApiError> {
+        let perm_str = perm.to_string();
+        match self.allows(perm, path) {
+            true => Ok(()),
+            false => match self {
+                Self::Anonymous => Err(ApiError::unauthorized("an access token is required!")),
+                _ => Err(ApiError::forbidden(format!(
+                    "access token doesn't allow '{perm_str}' on '{path}'!"
+                ))),
+            },
+        }
+    }
+
+    fn check_token(&self) -> Result<(), ApiError> {
+        match self {
+            Self::Anonymous => Err(ApiError::unauthorized("an access token is required!")),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_owner(&self) -> Result<(), ApiError> {
+        match self {
+            Self::Owner => Ok(()),
+            Self::Token(_) => Err(ApiError::forbidden(
+                "only the file system owner can do this, the api key is required!",
+            )),
+            Self::Anonymous => Err(ApiError::unauthorized("the api key is required!")),
+        }
+    }
+
+    fn reaches_dir(&self, dirpath: &str) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Token(acpl) => acpl.iter().any(|acp| acp.reaches_dir(dirpath)),
+            Self::Anonymous => false,
+        }
+    }
+}
+
+/// absolute path without "." & ".." segments or a trailing "/", relative paths start at the root
+fn normalize_path(path: &str) -> String {
+    let mut segs = vec![];
+    for seg in path.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                segs.pop();
+            }
+            seg => segs.push(seg),
+        }
+    }
+
+    format!("/{}", segs.join("/"))
+}
+
+pub(super) fn handle(state: &DevServerState, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    let mut store = state.store.lock().unwrap();
+    let access = Access::get(state, &store, req)?;
+
+    let res = match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/fs/tree") => get_tree(&mut store, &access),
+        ("POST", "/fs/get-files") => get_files(&mut store, &access, req),
+        ("POST", "/fs/mkdir") => mkdir(&mut store, &access, req),
+        ("POST", "/fs/rmdir") => rmdir(&mut store, &access, req),
+        ("POST", "/fs/mvdir") => mvdir(&mut store, &access, req),
+        ("POST", "/fs/mv") => mv(&mut store, &access, req),
+        ("POST", "/blob/upload") => upload(&mut store, &access, req),
+        ("POST", "/blob/create-multipart-upload") => {
+            create_multipart_upload(&mut store, &access, req)
+        }
+        ("POST", "/blob/upload-part") => upload_part(&mut store, &access, req),
+        ("POST", "/blob/complete-multipart-upload") => {
+            complete_multipart_upload(&mut store, &access, req)
+        }
+        ("POST", "/blob/delete") => delete(&mut store, &access, req),
+        ("POST", "/blob/set-metadata") => set_metadata(&mut store, &access, req),
+        ("POST", "/access/generate-token") => generate_token(state, &mut store, &access, req),
+        ("POST", "/access/blacklist-token") => blacklist_token(&mut store, &access, req),
+        ("GET", "/usage") => get_usage(&store, &access),
+        ("GET", path) if path.starts_with("/metadata/") => {
+            get_metadata(&mut store, &access, &path["/metadata/".len()..])
+        }
+        ("GET", path) if path.len() > 1 && !path[1..].contains('/') => {
+            download(&mut store, &access, req, &path[1..])
+        }
+        (method, path) => Err(ApiError::not_found(format!(
+            "'{method} {path}' isn't served by the dev server!"
+        ))),
+    };
+    store.save()?;
+
+    res
+}
+
+fn get_visible_dirtree(store: &Store, access: &Access) -> DirTree {
+    store.get_dirtree(|dirpath| access.reaches_dir(dirpath))
+}
+
+fn get_tree(store: &mut Store, access: &Access) -> Result<DevResponse, ApiError> {
+    access.check_token()?;
+    store.add_read();
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct FileCount {
+        dir_id: String,
+        count: u32,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResData {
+        dir_tree: DirTree,
+        file_counts: Vec<FileCount>,
+    }
+
+    let mut file_counts: BTreeMap<String, u32> = BTreeMap::new();
+    for file in store.get_files().filter(|file| !store::is_trashed(file)) {
+        if let Some(path) = store.get_file_path(file) {
+            if access.allows(PermissionChar::Read, &path) {
+                *file_counts.entry(file.dir_id.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    DevResponse::json(
+        "directory tree",
+        ResData {
+            dir_tree: get_visible_dirtree(store, access),
+            file_counts: file_counts
+                .into_iter()
+                .map(|(dir_id, count)| FileCount { dir_id, count })
+                .collect(),
+        },
+    )
+}
+
+/// compares json values of file columns, date strings by their time
+fn compare_values(a: &serde_json::Value, b: &serde_json::Value) -> Option<Ordering> {
+    use serde_json::Value;
+
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => {
+            match (
+                DateTime::parse_from_rfc3339(a),
+                DateTime::parse_from_rfc3339(b),
+            ) {
+                (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+                _ => Some(a.cmp(b)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `%` matches any number of characters & `_` a single one, like SQL's LIKE
+fn matches_like(value: &serde_json::Value, pattern: &serde_json::Value) -> bool {
+    let (Some(value), Some(pattern)) = (value.as_str(), pattern.as_str()) else {
+        return false;
+    };
+    let pattern = regex::escape(pattern).replace('%', ".*").replace('_', ".");
+
+    Regex::new(&format!("^{pattern}$")).is_ok_and(|re| re.is_match(value))
+}
+
+fn get_col_name<T: Serialize>(col: &T) -> String {
+    serde_json::to_value(col)
+        .ok()
+        .and_then(|col| col.as_str().map(|col| col.to_string()))
+        .unwrap_or_default()
+}
+
+fn matches_filter(file: &serde_json::Value, Filter(col, op, value): &Filter) -> bool {
+    let field = &file[get_col_name(col)];
+
+    match op {
+        FilterOp::IsNull => field.is_null(),
+        FilterOp::IsNotNull => !field.is_null(),
+        FilterOp::Eq => compare_values(field, value) == Some(Ordering::Equal),
+        FilterOp::Ne => compare_values(field, value) != Some(Ordering::Equal),
+        FilterOp::Gt => compare_values(field, value) == Some(Ordering::Greater),
+        FilterOp::Lt => compare_values(field, value) == Some(Ordering::Less),
+        FilterOp::Like => matches_like(field, value),
+    }
+}
+
+fn matches_filter_group(file: &serde_json::Value, group: &FilterGroup) -> bool {
+    match group.type_ {
+        FilterGroupType::And => group.filters.iter().all(|f| matches_filter(file, f)),
+        FilterGroupType::Or => {
+            group.filters.is_empty() || group.filters.iter().any(|f| matches_filter(file, f))
+        }
+    }
+}
+
+fn get_files(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    let opts: GetFilesOpts = req.get_json()?;
+    access.check_token()?;
+    store.add_read();
+
+    let dirpath = normalize_path(&opts.dir_path);
+    let filter_groups = opts.filters.unwrap_or_default();
+    // trashed files are only listed when asked for
+    let includes_trash = filter_groups
+        .iter()
+        .flat_map(|group| &group.filters)
+        .any(|Filter(col, ..)| matches!(col, FilterCol::DeletedAt));
+
+    let mut files: Vec<(serde_json::Value, &FsFile)> = match store.get_dir_id(&dirpath) {
+        Some(dir_id) => store
+            .get_files()
+            .filter(|file| file.dir_id == dir_id)
+            .filter(|file| includes_trash || !store::is_trashed(file))
+            .filter(|file| {
+                access.allows(
+                    PermissionChar::Read,
+                    &utils::dirtree::join_paths(&[&dirpath, &file.name]),
+                )
+            })
+            .map(|file| (serde_json::to_value(file).unwrap_or_default(), file))
+            .filter(|(file_json, _)| {
+                filter_groups
+                    .iter()
+                    .all(|group| matches_filter_group(file_json, group))
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    let order_col = match opts.order_by {
+        Some(order_by) => get_col_name(&order_by),
+        None => get_col_name(&FilterCol::CreatedAt),
+    };
+    files.sort_by(|(a, _), (b, _)| {
+        compare_values(&a[&order_col], &b[&order_col]).unwrap_or(Ordering::Equal)
+    });
+    if let Some(Order::Desc) = opts.order {
+        files.reverse();
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResData<'a> {
+        files: Vec<&'a FsFile>,
+        count: usize,
+        page_size: usize,
+    }
+
+    let page_size = opts
+        .limit
+        .filter(|limit| *limit > 0)
+        .unwrap_or(constants::DEV_SERVER_PAGE_SIZE);
+    let page = opts.page.unwrap_or(1).max(1);
+
+    DevResponse::json(
+        "files",
+        ResData {
+            count: files.len(),
+            files: files
+                .into_iter()
+                .skip((page - 1) * page_size)
+                .take(page_size)
+                .map(|(_, file)| file)
+                .collect(),
+            page_size,
+        },
+    )
+}
+
+#[derive(Deserialize)]
+struct PathReq {
+    path: String,
+}
+
+fn mkdir(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    let path = normalize_path(&req.get_json::<PathReq>()?.path);
+    access.check_token()?;
+
+    if store.get_dir_id(&path).is_none() {
+        access.check(PermissionChar::Create, &path)?;
+        store.mkdir(&path);
+        store.add_write();
+    }
+
+    DevResponse::json("directory created", get_visible_dirtree(store, access))
+}
+
+fn rmdir(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    let path = normalize_path(&req.get_json::<PathReq>()?.path);
+    if path == "/" {
+        return Err(ApiError::bad_request(
+            "the root directory can't be removed!",
+        ));
+    }
+    if store.get_dir_id(&path).is_none() {
+        return Err(ApiError::not_found(format!(
+            "directory '{path}' not found!"
+        )));
+    }
+    access.check(PermissionChar::Delete, &path)?;
+
+    store.rmdir(&path)?;
+    store.add_write();
+
+    DevResponse::json("directory removed", get_visible_dirtree(store, access))
+}
+
+fn mvdir(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody {
+        path: String,
+        new_path: String,
+    }
+
+    let body: ReqBody = req.get_json()?;
+    let (path, new_path) = (normalize_path(&body.path), normalize_path(&body.new_path));
+    if path == "/" {
+        return Err(ApiError::bad_request("the root directory can't be moved!"));
+    }
+    if store.get_dir_id(&path).is_none() {
+        return Err(ApiError::not_found(format!(
+            "directory '{path}' not found!"
+        )));
+    }
+    if store.get_dir_id(&new_path).is_some() {
+        return Err(ApiError::already_exists(format!(
+            "directory '{new_path}' already exists!"
+        )));
+    }
+    if new_path.starts_with(&format!("{path}/")) {
+        return Err(ApiError::bad_request(
+            "a directory can't be moved into itself!",
+        ));
+    }
+    access.check(PermissionChar::Update, &path)?;
+    access.check(PermissionChar::Create, &new_path)?;
+
+    store.mvdir(&path, &new_path);
+    store.add_write();
+
+    DevResponse::json("directory moved", get_visible_dirtree(store, access))
+}
+
+/// a file can only be created where there isn't one already, unless it's to be overwritten
+fn check_new_file_path(
+    store: &Store,
+    access: &Access,
+    path: &str,
+    force_write: bool,
+) -> Result<Option<String>, ApiError> {
+    access.check(PermissionChar::Create, path)?;
+
+    match store.get_live_file(path) {
+        Some(file) if force_write => {
+            access.check(PermissionChar::Update, path)?;
+            Ok(Some(file.storage_id.clone()))
+        }
+        Some(_) => Err(ApiError::already_exists(format!(
+            "file '{path}' already exists!"
+        ))),
+        None => Ok(None),
+    }
+}
+
+fn get_upload_path(metadata: &UploadBlobMetadata) -> Result<String, ApiError> {
+    if metadata.name.is_empty() || metadata.name.contains('/') || metadata.name == ".." {
+        return Err(ApiError::bad_request(format!(
+            "'{}' isn't a valid file name!",
+            metadata.name
+        )));
+    }
+
+    Ok(utils::dirtree::join_paths(&[
+        &normalize_path(&metadata.dir_path),
+        &metadata.name,
+    ]))
+}
+
+/// records a file whose contents were written to the blob of `storage_id`, replacing the live
+/// file at its path
+fn create_file(
+    store: &mut Store,
+    access: &Access,
+    metadata: &UploadBlobMetadata,
+    storage_id: String,
+) -> Result<FsFile, ApiError> {
+    let path = get_upload_path(metadata)?;
+    let replaced_storage_id = match check_new_file_path(store, access, &path, metadata.force_write)
+    {
+        Ok(replaced_storage_id) => replaced_storage_id,
+        Err(err) => {
+            store.remove_file(&storage_id)?;
+            return Err(err);
+        }
+    };
+    if let Some(replaced_storage_id) = replaced_storage_id {
+        store.remove_file(&replaced_storage_id)?;
+    }
+
+    let now = Local::now();
+    let file = FsFile {
+        name: metadata.name.clone(),
+        content_type: metadata.content_type.clone(),
+        cache_max_age_seconds: metadata.cache_max_age_seconds.unwrap_or(0),
+        file_system_id: store.get_file_system_id().to_string(),
+        dir_id: store.mkdir(store::split_path(&path).0),
+        file_size: fs::metadata(store.get_blob_path(&storage_id))?.len() as usize,
+        encryption: metadata.encryption.clone(),
+        is_public: metadata.is_public,
+        created_at: now,
+        updated_at: now,
+        deleted_at: metadata.deleted_at.map(|deleted_at| deleted_at.into()),
+        checksum: metadata.checksum.clone(),
+        compression: metadata.compression,
+        storage_id,
+    };
+    store.add_file(file.clone());
+    store.add_write();
+
+    Ok(file)
+}
+
+fn upload(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    let metadata: UploadBlobMetadata = req
+        .get_header(constants::HEADER_UPLOAD_METADATA)
+        .ok_or(ApiError::bad_request("upload metadata header is missing!"))
+        .and_then(|metadata| {
+            serde_json::from_str(metadata)
+                .map_err(|err| ApiError::bad_request(format!("invalid upload metadata: {err}")))
+        })?;
+    check_new_file_path(
+        store,
+        access,
+        &get_upload_path(&metadata)?,
+        metadata.force_write,
+    )?;
+
+    let storage_id = store::get_random_id();
+    fs::write(store.get_blob_path(&storage_id), &req.body)?;
+
+    DevResponse::json(
+        "file uploaded",
+        create_file(store, access, &metadata, storage_id)?,
+    )
+}
+
+/// contents of the form field `name` in a multipart/form-data body
+fn get_form_field<'a>(req: &'a DevRequest, name: &str) -> Option<&'a [u8]> {
+    let boundary = req
+        .get_header("content-type")?
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{boundary}");
+    let disposition = format!("name=\"{name}\"");
+
+    let body = &req.body;
+    let mut start = 0;
+    while let Some(i) = find_bytes(&body[start..], delimiter.as_bytes()) {
+        let part_start = start + i + delimiter.len();
+        let part_end = find_bytes(&body[part_start..], delimiter.as_bytes())
+            .map(|i| part_start + i)
+            .unwrap_or(body.len());
+        let part = &body[part_start..part_end];
+
+        if let Some(headers_end) = find_bytes(part, b"\r\n\r\n") {
+            let headers = String::from_utf8_lossy(&part[..headers_end]);
+            if headers.contains(&disposition) {
+                let contents = &part[headers_end + 4..];
+                return Some(contents.strip_suffix(b"\r\n").unwrap_or(contents));
+            }
+        }
+        start = part_end;
+    }
+
+    None
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+const UPLOAD_METADATA_FILENAME: &str = "metadata.json";
+
+fn create_multipart_upload(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    let metadata: UploadBlobMetadata = get_form_field(req, "metadata")
+        .ok_or(ApiError::bad_request("'metadata' field is missing!"))
+        .and_then(|metadata| {
+            serde_json::from_slice(metadata)
+                .map_err(|err| ApiError::bad_request(format!("invalid upload metadata: {err}")))
+        })?;
+    check_new_file_path(
+        store,
+        access,
+        &get_upload_path(&metadata)?,
+        metadata.force_write,
+    )?;
+
+    let upload_id = store::get_random_id();
+    let upload_dirpath = store.get_upload_dirpath(&upload_id);
+    fs::create_dir_all(&upload_dirpath)?;
+    fs::write(
+        upload_dirpath.join(UPLOAD_METADATA_FILENAME),
+        serde_json::to_vec(&metadata).map_err(ApiError::internal)?,
+    )?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResData {
+        upload_id: String,
+    }
+
+    DevResponse::json("multipart upload created", ResData { upload_id })
+}
+
+/// directory of an upload created with `create_multipart_upload`
+fn get_upload_dirpath(store: &Store, req: &DevRequest) -> Result<std::path::PathBuf, ApiError> {
+    let upload_id = req
+        .get_query("id")
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric()))
+        .ok_or(ApiError::bad_request("upload id is missing!"))?;
+    let upload_dirpath = store.get_upload_dirpath(upload_id);
+    if !upload_dirpath.is_dir() {
+        return Err(ApiError::not_found(format!(
+            "upload '{upload_id}' not found!"
+        )));
+    }
+
+    Ok(upload_dirpath)
+}
+
+fn upload_part(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    access.check_token()?;
+    let upload_dirpath = get_upload_dirpath(store, req)?;
+    let part_number: u32 = req
+        .get_query("n")
+        .and_then(|n| n.parse().ok())
+        .filter(|n| *n > 0)
+        .ok_or(ApiError::bad_request("part number is missing!"))?;
+
+    fs::write(upload_dirpath.join(part_number.to_string()), &req.body)?;
+
+    let checksum = utils::checksum::Checksum::default();
+    checksum.update(&req.body);
+    DevResponse::json(
+        "part uploaded",
+        UploadPartResult {
+            part_number,
+            etag: checksum.get_digest(),
+        },
+    )
+}
+
+fn complete_multipart_upload(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    access.check_token()?;
+    let upload_dirpath = get_upload_dirpath(store, req)?;
+    let parts: Vec<UploadPartResult> = req.get_json()?;
+    let metadata: UploadBlobMetadata =
+        serde_json::from_slice(&fs::read(upload_dirpath.join(UPLOAD_METADATA_FILENAME))?)
+            .map_err(ApiError::internal)?;
+
+    let storage_id = store::get_random_id();
+    let mut blob = fs::File::create(store.get_blob_path(&storage_id))?;
+    for part in &parts {
+        let part_filepath = upload_dirpath.join(part.part_number.to_string());
+        match fs::File::open(part_filepath) {
+            Ok(mut part_file) => _ = io::copy(&mut part_file, &mut blob)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                store.remove_file(&storage_id)?;
+                return Err(ApiError::bad_request(format!(
+                    "part number {} wasn't uploaded!",
+                    part.part_number
+                )));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    drop(blob);
+
+    let file = create_file(store, access, &metadata, storage_id)?;
+    fs::remove_dir_all(upload_dirpath)?;
+
+    #[derive(Serialize)]
+    struct ResData {
+        file: FsFile,
+    }
+
+    DevResponse::json("multipart upload completed", ResData { file })
+}
+
+/// files are moved to the trash, they're kept until their directory is removed
+fn delete(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody {
+        dir_path: String,
+        file_names: Vec<String>,
+    }
+
+    let body: ReqBody = req.get_json()?;
+    let dirpath = normalize_path(&body.dir_path);
+
+    let mut storage_ids = vec![];
+    for name in &body.file_names {
+        let path = utils::dirtree::join_paths(&[&dirpath, name]);
+        if let Some(file) = store.get_live_file(&path) {
+            access.check(PermissionChar::Delete, &path)?;
+            storage_ids.push(file.storage_id.clone());
+        }
+    }
+
+    let mut deleted_files = vec![];
+    for storage_id in storage_ids {
+        if let Some(file) = store.get_file_mut(&storage_id) {
+            file.deleted_at = Some(Local::now());
+            deleted_files.push(file.clone());
+        }
+        store.add_write();
+    }
+
+    DevResponse::json("files deleted", deleted_files)
+}
+
+fn mv(store: &mut Store, access: &Access, req: &DevRequest) -> Result<DevResponse, ApiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody {
+        file_path: String,
+        new_file_path: String,
+    }
+
+    let body: ReqBody = req.get_json()?;
+    let (path, new_path) = (
+        normalize_path(&body.file_path),
+        normalize_path(&body.new_file_path),
+    );
+    let storage_id = store
+        .get_live_file(&path)
+        .map(|file| file.storage_id.clone())
+        .ok_or(ApiError::not_found(format!("file '{path}' not found!")))?;
+    access.check(PermissionChar::Update, &path)?;
+    check_new_file_path(store, access, &new_path, false)?;
+
+    let (new_dirpath, new_name) = store::split_path(&new_path);
+    let dir_id = store.mkdir(new_dirpath);
+    let new_name = new_name.to_string();
+    let file = store
+        .get_file_mut(&storage_id)
+        .expect("file was found above");
+    file.name = new_name;
+    file.dir_id = dir_id;
+    file.updated_at = Local::now();
+    let file = file.clone();
+    store.add_write();
+
+    DevResponse::json("file moved", file)
+}
+
+fn set_metadata(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody {
+        path: String,
+        is_public: Option<bool>,
+        cache_max_age_seconds: Option<u64>,
+        name: Option<String>,
+        checksum: Option<String>,
+    }
+
+    let body: ReqBody = req.get_json()?;
+    let path = normalize_path(&body.path);
+    let storage_id = store
+        .get_live_file(&path)
+        .map(|file| file.storage_id.clone())
+        .ok_or(ApiError::not_found(format!("file '{path}' not found!")))?;
+    access.check(PermissionChar::Update, &path)?;
+    if let Some(name) = &body.name {
+        let new_path = utils::dirtree::join_paths(&[store::split_path(&path).0, name]);
+        if new_path != path {
+            check_new_file_path(store, access, &new_path, false)?;
+        }
+    }
+
+    let file = store
+        .get_file_mut(&storage_id)
+        .expect("file was found above");
+    if let Some(is_public) = body.is_public {
+        file.is_public = is_public;
+    }
+    if let Some(cache_max_age_seconds) = body.cache_max_age_seconds {
+        file.cache_max_age_seconds = cache_max_age_seconds;
+    }
+    if let Some(name) = body.name {
+        file.name = name;
+    }
+    if let Some(checksum) = body.checksum {
+        file.checksum = Some(checksum);
+    }
+    file.updated_at = Local::now();
+    let file = file.clone();
+    store.add_write();
+
+    DevResponse::json("metadata updated", file)
+}
+
+fn generate_token(
+    state: &DevServerState,
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    access.check_owner()?;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReqBody {
+        acpl: Vec<String>,
+        expires_at: DateTime<Utc>,
+    }
+
+    let body: ReqBody = req.get_json()?;
+    if body.acpl.is_empty() {
+        return Err(ApiError::bad_request("at least 1 ACP is required!"));
+    }
+    let acpl = body
+        .acpl
+        .iter()
+        .map(|acp| acp.parse())
+        .collect::<anyhow::Result<Vec<AccessControlPath>>>()
+        .map_err(|err| ApiError::bad_request(format!("invalid ACPL: {err}")))?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResData {
+        access_token: String,
+        dirtree: DirTree,
+    }
+
+    DevResponse::json(
+        "access token generated",
+        ResData {
+            access_token: acp::encode_token(&state.api_secret, &body.acpl, &body.expires_at),
+            dirtree: get_visible_dirtree(store, &Access::Token(acpl)),
+        },
+    )
+}
+
+fn blacklist_token(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+) -> Result<DevResponse, ApiError> {
+    access.check_owner()?;
+
+    #[derive(Deserialize)]
+    struct ReqBody {
+        tokens: Vec<String>,
+    }
+
+    store.blacklist(&req.get_json::<ReqBody>()?.tokens);
+
+    DevResponse::json("tokens blacklisted", ())
+}
+
+fn get_usage(store: &Store, access: &Access) -> Result<DevResponse, ApiError> {
+    access.check_owner()?;
+
+    let (reads_used, writes_used) = store.get_usage();
+    DevResponse::json(
+        "usage",
+        ApiKeyUsage {
+            reads_limit: USAGE_LIMIT,
+            writes_limit: USAGE_LIMIT,
+            storage_gb_hour_limit: USAGE_LIMIT as f32,
+            storage_gb_hour_used: 0.0,
+            storage_gb_used: store.get_storage_bytes() as f32 / 1e9,
+            reads_used,
+            writes_used,
+        },
+    )
+}
+
+/// public files can be read without a token
+fn get_readable_file<'a>(
+    store: &'a Store,
+    access: &Access,
+    storage_id: &str,
+) -> Result<&'a FsFile, ApiError> {
+    let file = store
+        .get_file(storage_id)
+        .ok_or(ApiError::not_found(format!(
+            "file '{storage_id}' not found!"
+        )))?;
+    if !file.is_public {
+        let path = store
+            .get_file_path(file)
+            .ok_or(ApiError::not_found(format!(
+                "file '{storage_id}' not found!"
+            )))?;
+        access.check(PermissionChar::Read, &path)?;
+    }
+
+    Ok(file)
+}
+
+fn get_metadata(
+    store: &mut Store,
+    access: &Access,
+    storage_id: &str,
+) -> Result<DevResponse, ApiError> {
+    let file = get_readable_file(store, access, storage_id)?.clone();
+    store.add_read();
+
+    DevResponse::json("file metadata", file)
+}
+
+/// `Range: bytes=N-` requests are answered with the rest of the blob from N onwards
+fn download(
+    store: &mut Store,
+    access: &Access,
+    req: &DevRequest,
+    storage_id: &str,
+) -> Result<DevResponse, ApiError> {
+    let file = get_readable_file(store, access, storage_id)?.clone();
+    store.add_read();
+
+    let mut blob = fs::File::open(store.get_blob_path(storage_id))?;
+    let blob_len = blob.metadata()?.len();
+    let range_start = req
+        .get_header("range")
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.strip_suffix('-'))
+        .and_then(|range_start| range_start.parse::<u64>().ok())
+        .filter(|range_start| *range_start < blob_len);
+
+    let mut headers = vec![
+        (
+            String::from("Content-Type"),
+            file.content_type
+                .clone()
+                .unwrap_or(constants::UNKNOWN_MIME_TYPE.to_string()),
+        ),
+        (
+            String::from("metadata"),
+            serde_json::to_string(&file).map_err(ApiError::internal)?,
+        ),
+    ];
+    let status = match range_start {
+        Some(range_start) => {
+            io::Seek::seek(&mut blob, io::SeekFrom::Start(range_start))?;
+            headers.push((
+                String::from("Content-Range"),
+                format!("bytes {range_start}-{}/{blob_len}", blob_len - 1),
+            ));
+            206
+        }
+        None => 200,
+    };
+
+    Ok(DevResponse {
+        status,
+        headers,
+        body_len: (blob_len - range_start.unwrap_or(0)) as usize,
+        body: Box::new(blob),
+    })
+}
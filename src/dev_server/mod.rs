@@ -0,0 +1,349 @@
+//! stand-in for the SFS api that keeps the file system in a local directory, to try the CLI
+//! without an account and to run the integration tests against. only the endpoints the CLI calls
+//! are served, access tokens are signed with the api secret and their ACPLs are enforced.
+
+mod acp;
+mod handlers;
+mod store;
+
+use std::{
+    io::{self, Read},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use anyhow::anyhow;
+use chrono::{Duration, Utc};
+use tiny_http::{Header, Request, Response, Server, StatusCode};
+
+use crate::{constants, shared_types::ApiResponse};
+
+pub use acp::AccessControlPath;
+use store::Store;
+
+const N_WORKERS: usize = 4;
+
+/// failed request, sent as an `ApiResponse` with the `error` code set
+struct ApiError {
+    status: u16,
+    error: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            error: "ERR_BAD_REQUEST",
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            status: 401,
+            error: "ERR_UNAUTHORIZED",
+            message: message.into(),
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: 403,
+            error: "ERR_FORBIDDEN",
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: 404,
+            error: "ERR_NOT_FOUND",
+            message: message.into(),
+        }
+    }
+
+    fn already_exists(message: impl Into<String>) -> Self {
+        Self {
+            status: 409,
+            error: constants::API_ERR_ALREADY_EXISTS,
+            message: message.into(),
+        }
+    }
+
+    fn internal(err: impl std::fmt::Display) -> Self {
+        Self {
+            status: 500,
+            error: "ERR_INTERNAL",
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for ApiError {
+    fn from(err: io::Error) -> Self {
+        Self::internal(err)
+    }
+}
+
+/// the parts of a request the handlers need, read before handling so that the request can be
+/// answered afterwards
+struct DevRequest {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl DevRequest {
+    fn read(request: &mut Request) -> io::Result<Self> {
+        let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        let path = urlencoding::decode(path)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+            .into_owned();
+        let query = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| {
+                (
+                    key.to_string(),
+                    urlencoding::decode(value)
+                        .map(|value| value.into_owned())
+                        .unwrap_or(value.to_string()),
+                )
+            })
+            .collect();
+        let headers = request
+            .headers()
+            .iter()
+            .map(|header| {
+                (
+                    header.field.as_str().as_str().to_ascii_lowercase(),
+                    header.value.to_string(),
+                )
+            })
+            .collect();
+
+        let mut body = vec![];
+        request.as_reader().read_to_end(&mut body)?;
+
+        Ok(Self {
+            method: request.method().as_str().to_string(),
+            path,
+            query,
+            headers,
+            body,
+        })
+    }
+
+    fn get_query(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|err| ApiError::bad_request(format!("invalid request body: {err}")))
+    }
+}
+
+struct DevResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Box<dyn Read + Send>,
+    body_len: usize,
+}
+
+impl DevResponse {
+    fn json<T: serde::Serialize>(message: &str, data: T) -> Result<Self, ApiError> {
+        let body = serde_json::to_vec(&ApiResponse {
+            message: message.to_string(),
+            data: Some(data),
+            error: None,
+        })
+        .map_err(ApiError::internal)?;
+
+        Ok(Self::bytes(200, body))
+    }
+
+    fn bytes(status: u16, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: vec![(
+                String::from("Content-Type"),
+                String::from("application/json"),
+            )],
+            body_len: body.len(),
+            body: Box::new(io::Cursor::new(body)),
+        }
+    }
+
+    fn from_err(err: ApiError) -> Self {
+        let body = serde_json::to_vec(&ApiResponse::<()> {
+            message: err.message,
+            data: None,
+            error: Some(err.error.to_string()),
+        })
+        .expect("error responses are always serializable");
+
+        Self::bytes(err.status, body)
+    }
+
+    fn respond(self, request: Request) -> io::Result<()> {
+        let headers = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+            .collect();
+
+        request.respond(Response::new(
+            StatusCode(self.status),
+            headers,
+            self.body,
+            Some(self.body_len),
+            None,
+        ))
+    }
+}
+
+/// state shared by the worker threads
+struct DevServerState {
+    store: Mutex<Store>,
+    api_key: String,
+    api_secret: String,
+}
+
+/// credentials to use the CLI with a running dev server, see `DevServer::get_env`
+pub struct DevServerCreds {
+    pub base_url: String,
+    /// owner token with every permission on the whole file system
+    pub token: String,
+    pub api_key: String,
+    pub api_secret: String,
+}
+
+/// stops serving when dropped
+pub struct DevServer {
+    server: Arc<Server>,
+    is_closed: Arc<AtomicBool>,
+    workers: Vec<thread::JoinHandle<()>>,
+    creds: DevServerCreds,
+}
+
+impl DevServer {
+    /// serves the file system kept in `dirpath` (created if missing) on localhost, port 0 picks a
+    /// free port. the api key is generated on the first start and kept in `dirpath`.
+    pub fn start(dirpath: &Path, port: u16) -> anyhow::Result<Self> {
+        let store = Store::open(dirpath)?;
+        let (api_key, api_secret) = store.get_api_key();
+        let (api_key, api_secret) = (api_key.to_string(), api_secret.to_string());
+
+        let server = Server::http(("127.0.0.1", port)).map_err(|err| anyhow!(err))?;
+        let addr = server
+            .server_addr()
+            .to_ip()
+            .ok_or(anyhow!("dev server isn't listening on an ip address!"))?;
+
+        let token = acp::encode_token(
+            &api_secret,
+            &[String::from("curd:/**")],
+            &(Utc::now() + Duration::days(constants::DEV_SERVER_ROOT_TOKEN_TTL_DAYS)),
+        );
+        let creds = DevServerCreds {
+            base_url: format!("http://{addr}"),
+            token,
+            api_key: api_key.clone(),
+            api_secret: api_secret.clone(),
+        };
+
+        let server = Arc::new(server);
+        let is_closed = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(DevServerState {
+            store: Mutex::new(store),
+            api_key,
+            api_secret,
+        });
+        let workers = (0..N_WORKERS)
+            .map(|_| {
+                let server = server.clone();
+                let is_closed = is_closed.clone();
+                let state = state.clone();
+                thread::spawn(move || loop {
+                    match server.recv() {
+                        Ok(request) => handle_request(&state, request),
+                        Err(_) if is_closed.load(Ordering::SeqCst) => break,
+                        Err(err) => eprintln!("dev server: {err}"),
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            server,
+            is_closed,
+            workers,
+            creds,
+        })
+    }
+
+    pub fn get_creds(&self) -> &DevServerCreds {
+        &self.creds
+    }
+
+    /// environment variables to point the CLI at this server with the owner's token & api key
+    pub fn get_env(&self) -> Vec<(&'static str, &str)> {
+        vec![
+            (constants::ENV_BASE_URL, &self.creds.base_url),
+            (constants::ENV_TOKEN, &self.creds.token),
+            (constants::ENV_API_KEY, &self.creds.api_key),
+            (constants::ENV_API_SECRET, &self.creds.api_secret),
+        ]
+    }
+
+    /// blocks until the worker threads stop, which they only do once the server is dropped
+    pub fn wait(mut self) {
+        for worker in self.workers.drain(..) {
+            _ = worker.join();
+        }
+    }
+}
+
+impl Drop for DevServer {
+    fn drop(&mut self) {
+        self.is_closed.store(true, Ordering::SeqCst);
+        for _ in 0..self.workers.len() {
+            self.server.unblock();
+        }
+        for worker in self.workers.drain(..) {
+            _ = worker.join();
+        }
+    }
+}
+
+fn handle_request(state: &DevServerState, mut request: Request) {
+    let res = match DevRequest::read(&mut request) {
+        Ok(req) => handlers::handle(state, &req),
+        Err(err) => Err(ApiError::bad_request(format!("unreadable request: {err}"))),
+    };
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => DevResponse::from_err(err),
+    };
+
+    // the client hung up, nothing left to do
+    _ = res.respond(request);
+}
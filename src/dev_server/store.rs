@@ -0,0 +1,301 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    shared_types::{DirTree, FsFile},
+    utils,
+};
+
+const DB_FILENAME: &str = "db.json";
+const BLOBS_DIRNAME: &str = "blobs";
+const UPLOADS_DIRNAME: &str = "uploads";
+const ID_LEN: usize = 24;
+
+pub fn get_random_id() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), ID_LEN)
+}
+
+/// parent directory & name of a normalized absolute path, e.g. ("/", "a.txt") of "/a.txt"
+pub fn split_path(path: &str) -> (&str, &str) {
+    let (dirpath, name) = utils::dirtree::split_path(path);
+    match dirpath.trim_end_matches('/') {
+        "" => ("/", name),
+        dirpath => (dirpath, name),
+    }
+}
+
+/// everything but the blobs, saved as json after every change
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Db {
+    api_key: String,
+    api_secret: String,
+    file_system_id: String,
+    /// absolute paths of the directories to their ids, "/" is the root
+    dirs: BTreeMap<String, String>,
+    /// files by storage id, trashed files are kept until their directory is removed
+    files: BTreeMap<String, FsFile>,
+    blacklisted_tokens: BTreeSet<String>,
+    reads_used: u64,
+    writes_used: u64,
+}
+
+impl Db {
+    fn new() -> Self {
+        Self {
+            api_key: get_random_id(),
+            api_secret: get_random_id(),
+            file_system_id: get_random_id(),
+            dirs: BTreeMap::from([(String::from("/"), get_random_id())]),
+            files: BTreeMap::new(),
+            blacklisted_tokens: BTreeSet::new(),
+            reads_used: 0,
+            writes_used: 0,
+        }
+    }
+}
+
+/// file system of the dev server in a local directory
+pub struct Store {
+    dirpath: PathBuf,
+    db: Db,
+}
+
+impl Store {
+    pub fn open(dirpath: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(dirpath.join(BLOBS_DIRNAME))?;
+        fs::create_dir_all(dirpath.join(UPLOADS_DIRNAME))?;
+
+        let db = match fs::read_to_string(dirpath.join(DB_FILENAME)) {
+            Ok(db_str) => serde_json::from_str(&db_str)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Db::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let store = Self {
+            dirpath: dirpath.to_path_buf(),
+            db,
+        };
+        store.save()?;
+
+        Ok(store)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(
+            self.dirpath.join(DB_FILENAME),
+            serde_json::to_vec_pretty(&self.db)?,
+        )
+    }
+
+    pub fn get_api_key(&self) -> (&str, &str) {
+        (&self.db.api_key, &self.db.api_secret)
+    }
+
+    pub fn get_file_system_id(&self) -> &str {
+        &self.db.file_system_id
+    }
+
+    pub fn get_blob_path(&self, storage_id: &str) -> PathBuf {
+        self.dirpath.join(BLOBS_DIRNAME).join(storage_id)
+    }
+
+    /// parts of a multipart upload are kept in here until it's completed
+    pub fn get_upload_dirpath(&self, upload_id: &str) -> PathBuf {
+        self.dirpath.join(UPLOADS_DIRNAME).join(upload_id)
+    }
+
+    pub fn is_blacklisted(&self, token: &str) -> bool {
+        self.db.blacklisted_tokens.contains(token)
+    }
+
+    pub fn blacklist(&mut self, tokens: &[String]) {
+        self.db.blacklisted_tokens.extend(tokens.iter().cloned());
+    }
+
+    /// reads & writes used
+    pub fn get_usage(&self) -> (u64, u64) {
+        (self.db.reads_used, self.db.writes_used)
+    }
+
+    pub fn add_read(&mut self) {
+        self.db.reads_used += 1;
+    }
+
+    pub fn add_write(&mut self) {
+        self.db.writes_used += 1;
+    }
+
+    pub fn get_dir_id(&self, dirpath: &str) -> Option<&str> {
+        self.db.dirs.get(dirpath).map(|id| id.as_str())
+    }
+
+    pub fn get_dir_path(&self, dir_id: &str) -> Option<&str> {
+        self.db
+            .dirs
+            .iter()
+            .find(|(_, id)| *id == dir_id)
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// creates missing parent directories too, returns the id of the directory
+    pub fn mkdir(&mut self, dirpath: &str) -> String {
+        let mut path = String::new();
+        for seg in dirpath.split('/').filter(|seg| !seg.is_empty()) {
+            path = utils::dirtree::join_paths(&[&path, seg]);
+            self.db
+                .dirs
+                .entry(path.clone())
+                .or_insert_with(get_random_id);
+        }
+
+        self.db.dirs[dirpath].clone()
+    }
+
+    fn is_in_dir(path: &str, dirpath: &str) -> bool {
+        path == dirpath || path.starts_with(&format!("{dirpath}/"))
+    }
+
+    /// removes the directory along with its nested directories & files
+    pub fn rmdir(&mut self, dirpath: &str) -> io::Result<()> {
+        let removed_dir_ids: BTreeSet<String> = self
+            .db
+            .dirs
+            .iter()
+            .filter(|(path, _)| Self::is_in_dir(path, dirpath))
+            .map(|(_, id)| id.clone())
+            .collect();
+        self.db
+            .dirs
+            .retain(|path, _| !Self::is_in_dir(path, dirpath));
+
+        let removed_storage_ids: Vec<String> = self
+            .db
+            .files
+            .values()
+            .filter(|file| removed_dir_ids.contains(&file.dir_id))
+            .map(|file| file.storage_id.clone())
+            .collect();
+        for storage_id in removed_storage_ids {
+            self.remove_file(&storage_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// files keep their directory ids, only the paths change
+    pub fn mvdir(&mut self, dirpath: &str, new_dirpath: &str) {
+        let moved: Vec<(String, String)> = self
+            .db
+            .dirs
+            .iter()
+            .filter(|(path, _)| Self::is_in_dir(path, dirpath))
+            .map(|(path, id)| (path.clone(), id.clone()))
+            .collect();
+
+        self.mkdir(split_path(new_dirpath).0);
+        for (path, id) in moved {
+            self.db.dirs.remove(&path);
+            self.db
+                .dirs
+                .insert(format!("{new_dirpath}{}", &path[dirpath.len()..]), id);
+        }
+    }
+
+    /// `is_visible` decides which directories are included, along with all of their parents
+    pub fn get_dirtree<F>(&self, is_visible: F) -> DirTree
+    where
+        F: Fn(&str) -> bool,
+    {
+        let visible_dirpaths: BTreeSet<&str> = self
+            .db
+            .dirs
+            .keys()
+            .filter(|path| is_visible(path))
+            .flat_map(|path| {
+                path.match_indices('/')
+                    .map(|(i, _)| &path[..i.max(1)])
+                    .chain([path.as_str()])
+            })
+            .collect();
+
+        self.get_sub_tree("/", &visible_dirpaths)
+    }
+
+    fn get_sub_tree(&self, dirpath: &str, visible_dirpaths: &BTreeSet<&str>) -> DirTree {
+        let children = visible_dirpaths
+            .iter()
+            .filter(|path| {
+                let (parent_dirpath, name) = split_path(path);
+                !name.is_empty() && parent_dirpath == dirpath
+            })
+            .map(|path| self.get_sub_tree(path, visible_dirpaths))
+            .collect();
+
+        DirTree {
+            id: self.db.dirs[dirpath].clone(),
+            name: split_path(dirpath).1.to_string(),
+            children,
+        }
+    }
+
+    pub fn get_files(&self) -> impl Iterator<Item = &FsFile> {
+        self.db.files.values()
+    }
+
+    pub fn get_file(&self, storage_id: &str) -> Option<&FsFile> {
+        self.db.files.get(storage_id)
+    }
+
+    pub fn get_file_mut(&mut self, storage_id: &str) -> Option<&mut FsFile> {
+        self.db.files.get_mut(storage_id)
+    }
+
+    /// absolute path of a file, `None` if its directory was removed in the meantime
+    pub fn get_file_path(&self, file: &FsFile) -> Option<String> {
+        self.get_dir_path(&file.dir_id)
+            .map(|dirpath| utils::dirtree::join_paths(&[dirpath, &file.name]))
+    }
+
+    /// the file at the path that isn't in the trash
+    pub fn get_live_file(&self, path: &str) -> Option<&FsFile> {
+        let (dirpath, name) = split_path(path);
+        let dir_id = self.get_dir_id(dirpath)?;
+
+        self.db
+            .files
+            .values()
+            .find(|file| file.dir_id == dir_id && file.name == name && !is_trashed(file))
+    }
+
+    pub fn add_file(&mut self, file: FsFile) {
+        self.db.files.insert(file.storage_id.clone(), file);
+    }
+
+    pub fn remove_file(&mut self, storage_id: &str) -> io::Result<()> {
+        self.db.files.remove(storage_id);
+        match fs::remove_file(self.get_blob_path(storage_id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get_storage_bytes(&self) -> u64 {
+        self.db
+            .files
+            .values()
+            .map(|file| file.file_size as u64)
+            .sum()
+    }
+}
+
+/// deleted files, and files whose expiry set at upload has passed
+pub fn is_trashed(file: &FsFile) -> bool {
+    file.deleted_at
+        .is_some_and(|deleted_at| deleted_at <= chrono::Local::now())
+}
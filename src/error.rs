@@ -0,0 +1,119 @@
+use reqwest::StatusCode;
+
+use crate::{constants, shared_types::ApiResponse};
+
+/// failures that callers (and scripts, through the exit code) can tell apart. anything else is
+/// a plain `anyhow` error with exit code 1.
+///
+/// | exit code | error              |
+/// |-----------|--------------------|
+/// | 0         | success            |
+/// | 1         | other errors       |
+/// | 2         | `Usage`            |
+/// | 3         | `Auth`             |
+/// | 4         | `PermissionDenied` |
+/// | 5         | `NotFound`         |
+/// | 6         | `AlreadyExists`    |
+/// | 7         | `Quota`            |
+/// | 8         | `Network`          |
+/// | 9         | `Decryption`       |
+#[derive(Debug, thiserror::Error)]
+pub enum SfsError {
+    /// e.g. a prompt was needed in non-interactive mode
+    #[error("{0}")]
+    Usage(String),
+
+    /// not logged in, or the token is invalid/expired
+    #[error("{0}")]
+    Auth(String),
+
+    /// the token's access control paths or the active token's role don't allow the action
+    #[error("{0}")]
+    PermissionDenied(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    AlreadyExists(String),
+
+    /// api key limits (reads, writes, storage) are used up
+    #[error("{0}")]
+    Quota(String),
+
+    /// the server couldn't be reached, the connection stalled or requests are being rate limited
+    #[error("{0}")]
+    Network(String),
+
+    /// wrong password or corrupted encrypted contents
+    #[error("{0}")]
+    Decryption(String),
+
+    /// any other non-success api response, `message` already starts with the status
+    #[error("{message}")]
+    Api { status: StatusCode, message: String },
+}
+
+pub const EXIT_CODE_ERROR: i32 = 1;
+pub const EXIT_CODE_USAGE: i32 = 2;
+
+impl SfsError {
+    /// classifies a non-success api response by its status and the `error` code in the body
+    pub fn from_response(status: StatusCode, res_text: &str) -> Self {
+        let (error, message) =
+            match serde_json::from_str::<ApiResponse<serde_json::Value>>(res_text) {
+                Ok(res) => (res.error, res.message),
+                Err(_) => (None, res_text.to_string()),
+            };
+        let message = match &error {
+            Some(error) if !message.contains(error.as_str()) => {
+                format!("({status}) {message} [{error}]")
+            }
+            _ => format!("({status}) {message}"),
+        };
+
+        if error.as_deref() == Some(constants::API_ERR_ALREADY_EXISTS) {
+            return Self::AlreadyExists(message);
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Auth(message),
+            StatusCode::FORBIDDEN => Self::PermissionDenied(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::CONFLICT => Self::AlreadyExists(message),
+            StatusCode::PAYMENT_REQUIRED => Self::Quota(message),
+            // rate limited, worth trying again later unlike a used up quota
+            StatusCode::TOO_MANY_REQUESTS => Self::Network(message),
+            _ => Self::Api { status, message },
+        }
+    }
+
+    pub fn get_exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => EXIT_CODE_USAGE,
+            Self::Auth(_) => 3,
+            Self::PermissionDenied(_) => 4,
+            Self::NotFound(_) => 5,
+            Self::AlreadyExists(_) => 6,
+            Self::Quota(_) => 7,
+            Self::Network(_) => 8,
+            Self::Decryption(_) => 9,
+            Self::Api { .. } => EXIT_CODE_ERROR,
+        }
+    }
+}
+
+/// exit code of the first typed error in the chain, failed requests count as network errors
+pub fn get_exit_code(err: &anyhow::Error) -> i32 {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<SfsError>() {
+            return err.get_exit_code();
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            if !err.is_builder() && !err.is_decode() {
+                return SfsError::Network(String::new()).get_exit_code();
+            }
+        }
+    }
+
+    EXIT_CODE_ERROR
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::uploads::UploadPartResult,
+    config::{self, CONFIG},
+    constants,
+    shared_types::{CompressionCodec, EncryptionMetadata},
+    utils::x2str,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified_at: DateTime<Utc>,
+}
+
+impl FileFingerprint {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+
+        Ok(Self {
+            size: metadata.len(),
+            modified_at: metadata.modified()?.into(),
+        })
+    }
+}
+
+/// progress of a multipart upload, saved after every uploaded part so an interrupted upload can
+/// be continued with `upload --resume`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadJournal {
+    pub upload_id: String,
+    pub local_filepath: PathBuf,
+    pub remote_filepath: String,
+    pub fingerprint: FileFingerprint,
+    /// plaintext bytes per part, a journal can only be resumed with the same part size
+    pub part_size: u32,
+    /// salt & nonce are needed to continue sealing an encrypted upload
+    pub encryption: Option<EncryptionMetadata>,
+    /// digest of the derived encryption key, to reject a different password on resume
+    pub key_digest: Option<String>,
+    /// already uploaded parts were compressed with this codec, resuming needs the same one
+    #[serde(default)]
+    pub compression: Option<CompressionCodec>,
+    pub parts: Vec<UploadPartResult>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UploadJournal {
+    /// journals are kept per profile, upload ids only make sense to the deployment they came from
+    pub fn get_journals_dirpath() -> io::Result<PathBuf> {
+        let profile = CONFIG.read().unwrap().get_profile().to_string();
+        Ok(config::get_profile_dirpath(&profile)?.join("uploads"))
+    }
+
+    fn get_journal_filepath(local_filepath: &Path, remote_filepath: &str) -> io::Result<PathBuf> {
+        let mut hasher = Sha256::new();
+        hasher.update(local_filepath.to_string_lossy().as_bytes());
+        hasher.update(b"\n");
+        hasher.update(remote_filepath.as_bytes());
+        let digest = hasher.finalize();
+
+        Ok(Self::get_journals_dirpath()?.join(format!("{}.json", x2str::bytes2hex(&digest[..8]))))
+    }
+
+    pub fn new(
+        upload_id: String,
+        local_filepath: PathBuf,
+        remote_filepath: String,
+        fingerprint: FileFingerprint,
+        part_size: u32,
+    ) -> Self {
+        Self {
+            upload_id,
+            local_filepath,
+            remote_filepath,
+            fingerprint,
+            part_size,
+            encryption: None,
+            key_digest: None,
+            compression: None,
+            parts: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn load(local_filepath: &Path, remote_filepath: &str) -> anyhow::Result<Option<Self>> {
+        let journal_filepath = Self::get_journal_filepath(local_filepath, remote_filepath)?;
+
+        match fs::read_to_string(journal_filepath) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(anyhow!(err)),
+        }
+    }
+
+    /// all journals along with their file paths, unreadable journals are returned as errors
+    pub fn list() -> anyhow::Result<Vec<(PathBuf, anyhow::Result<Self>)>> {
+        let dirpath = Self::get_journals_dirpath()?;
+        let entries = match fs::read_dir(dirpath) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(anyhow!(err)),
+        };
+
+        let mut journals = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let journal = fs::read_to_string(&path)
+                .map_err(|err| anyhow!(err))
+                .and_then(|contents| Ok(serde_json::from_str(&contents)?));
+            journals.push((path, journal));
+        }
+
+        Ok(journals)
+    }
+
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        self.updated_at = Utc::now();
+
+        let journal_filepath =
+            Self::get_journal_filepath(&self.local_filepath, &self.remote_filepath)?;
+        fs::DirBuilder::new()
+            .recursive(true)
+            .create(Self::get_journals_dirpath()?)?;
+        fs::write(journal_filepath, serde_json::to_vec_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn delete(&self) -> anyhow::Result<()> {
+        let journal_filepath =
+            Self::get_journal_filepath(&self.local_filepath, &self.remote_filepath)?;
+
+        match fs::remove_file(journal_filepath) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(anyhow!(err)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get_n_parts(&self) -> u64 {
+        self.fingerprint.size.div_ceil(self.part_size as u64).max(1)
+    }
+
+    /// the local file changed or disappeared, or the upload was left untouched for too long
+    pub fn is_stale(&self) -> bool {
+        let is_changed = match FileFingerprint::from_path(&self.local_filepath) {
+            Ok(fingerprint) => fingerprint != self.fingerprint,
+            Err(_) => true,
+        };
+
+        is_changed
+            || Utc::now() - self.updated_at > Duration::days(constants::UPLOAD_JOURNAL_TTL_DAYS)
+    }
+}
@@ -0,0 +1,13 @@
+pub mod api;
+pub mod client;
+pub mod config;
+pub mod constants;
+pub mod dev_server;
+pub mod error;
+pub mod journal;
+pub mod shared_types;
+pub mod state;
+pub mod utils;
+
+pub use client::SfsClient;
+pub use error::SfsError;
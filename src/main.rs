@@ -1,29 +1,68 @@
+use std::process;
+
 use clap::{Parser, Subcommand};
 use cmd::blob;
 use cmd::metadata::MetadataCommand;
 use cmd::tokens::TokensCommand;
 use cmd::usage::UsageCommand;
-use utils::local_auth::LocalAuthData;
+use colored::Colorize;
+use sfs_cli::{
+    config::{self, CONFIG},
+    error,
+    shared_types::CmdOutputFormat,
+    utils::prompts,
+};
 
-mod api;
 mod cmd;
-mod config;
-mod constants;
-mod shared_types;
-mod state;
-mod utils;
 
 use crate::cmd::auth::AuthCommand;
+use crate::cmd::completions::{CompleteCommand, CompletionsCommand};
 use crate::cmd::config::ConfigCommand;
+use crate::cmd::dev_server::DevServerCommand;
 use crate::cmd::dirtree;
+use crate::cmd::journal::JournalCommand;
 use crate::cmd::serve::ServeCommand;
-use crate::shared_types::CliSubCmd;
+use crate::cmd::shell::ShellCommand;
+use crate::cmd::sync::SyncCommand;
+use crate::cmd::webdav::WebdavCommand;
+use crate::cmd::CliSubCmd;
+
+const AFTER_HELP: &str = "Environment:
+  SFS_TOKEN                       access token to use instead of the login & the local state
+  SFS_API_KEY, SFS_API_SECRET     owner's api key, for owner only actions along with SFS_TOKEN
+  SFS_BASE_URL                    overrides the api base url of the config
+  SFS_PROFILE                     profile to use, see `config profiles`
+  PASSWORD                        password of encrypted files, no prompt is shown if set
+
+Exit codes:
+  0  success
+  1  other errors
+  2  invalid usage, or a prompt was needed with --non-interactive
+  3  not logged in, or the token is invalid/expired
+  4  permission denied
+  5  not found
+  6  already exists
+  7  api key quota exceeded
+  8  network error, or requests are being rate limited
+  9  decryption failed (wrong password?)";
 
 #[derive(Parser)]
-#[command(about = "CLI to manage your SFS file system.")]
+#[command(about = "CLI to manage your SFS file system.", after_help = AFTER_HELP)]
 struct Cli {
     #[command(subcommand)]
     commands: Commands,
+
+    #[arg(long = "output", global = true, value_enum, default_value_t = CmdOutputFormat::Text)]
+    /// print results of `ls`, `find`, `du`, `tree`, `tokens ls`, `usage`, `url` and `upload` as json
+    output_format: CmdOutputFormat,
+
+    #[arg(long, global = true)]
+    /// fail instead of prompting for confirmations, passwords, etc. (e.g. in CI)
+    non_interactive: bool,
+
+    #[arg(long, global = true)]
+    /// profile to use instead of the one selected with `config profiles use` (also: SFS_PROFILE)
+    profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -38,17 +77,29 @@ enum Commands {
     Config(ConfigCommand),
     /// get your api key usage (you need to be the file system owner to run this command)
     Usage(UsageCommand),
+    /// list or clean up local journals of interrupted uploads
+    Journal(JournalCommand),
 
     /// list remote files in a remote directory (default: currently selected WD)
     Ls(dirtree::LsCommand),
-    /// print remote directory structure
+    /// search for remote files in a remote directory and all of its nested directories
+    Find(dirtree::FindCommand),
+    /// print remote directory structure, optionally with its files, cumulative counts & sizes
     Tree(dirtree::TreeCommand),
+    /// print the size & file count of a remote directory and its nested directories, largest first
+    Du(dirtree::DuCommand),
     /// create an empty file in a remote directory (default: currently selected WD)
     Touch(dirtree::TouchCommand),
     /// select a file to push contents into. (e.g. echo "Hello, World!" | sfs select "./hw.txt")
     Select(blob::SelectCommand),
     /// print remote file's contents to stdout. use '>' to redirect to a file
     Cat(blob::CatCommand),
+    /// download a remote file to disk, interrupted downloads are resumed when run again
+    Get(blob::GetCommand),
+    /// recursively download a remote directory into a local directory
+    Pull(blob::PullCommand),
+    /// upload or download only new & changed files between a local and a remote directory
+    Sync(SyncCommand),
     /// remove a remote file
     Rm(dirtree::RmCommand),
     /// move a remote file to a different remote location
@@ -59,6 +110,8 @@ enum Commands {
     Rmdir(dirtree::RmdirCommand),
     /// move a remote directory to a new remote location
     Mvdir(dirtree::MvdirCommand),
+    /// copy a remote file, or a remote directory with -r, also between file systems of saved tokens
+    Cp(dirtree::CpCommand),
     /// print currently selected remote working directory (WD)
     Pwd(dirtree::PwdCommand),
     /// change currently selected WD
@@ -70,21 +123,39 @@ enum Commands {
     Upload(blob::UploadBlobCommand),
     /// manage metadata for remote files
     Metadata(MetadataCommand),
-}
 
-#[tokio::main]
-pub async fn main() {
-    let cli = Cli::parse();
+    /// interactive shell running the commands above in one session, with history and completion
+    /// of remote directories
+    Shell(ShellCommand),
+
+    /// serve the remote file system over WebDAV on localhost, to browse it from file managers &
+    /// editors
+    Webdav(WebdavCommand),
 
-    LocalAuthData::load().expect("error occured while initializing auth!");
+    /// run a local stand-in for the SFS api on disk, to try the CLI out without an account
+    DevServer(DevServerCommand),
 
-    match cli.commands {
+    /// print the completion script for a shell
+    Completions(CompletionsCommand),
+
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteCommand),
+}
+
+async fn run(commands: Commands) -> anyhow::Result<()> {
+    match commands {
         Commands::Serve(_cmd) => _cmd.run().await,
         Commands::Config(_cmd) => _cmd.run().await,
         Commands::Auth(_cmd) => _cmd.run().await,
         Commands::Metadata(_cmd) => _cmd.run().await,
         Commands::Tokens(_cmd) => _cmd.run().await,
         Commands::Usage(_cmd) => _cmd.run().await,
+        Commands::Journal(_cmd) => _cmd.run().await,
+        Commands::Shell(_cmd) => _cmd.run().await,
+        Commands::Webdav(_cmd) => _cmd.run().await,
+        Commands::DevServer(_cmd) => _cmd.run().await,
+        Commands::Completions(_cmd) => _cmd.run().await,
+        Commands::Complete(_cmd) => _cmd.run().await,
 
         // fs commands
         Commands::Select(_cmd) => _cmd.run().await,
@@ -93,15 +164,47 @@ pub async fn main() {
         Commands::Rm(_cmd) => _cmd.run().await,
         Commands::Mvdir(_cmd) => _cmd.run().await,
         Commands::Mv(_cmd) => _cmd.run().await,
+        Commands::Cp(_cmd) => _cmd.run().await,
         Commands::Tree(_cmd) => _cmd.run().await,
+        Commands::Du(_cmd) => _cmd.run().await,
         Commands::Cd(_cmd) => _cmd.run().await,
         Commands::Pwd(_cmd) => _cmd.run().await,
         Commands::Ls(_cmd) => _cmd.run().await,
+        Commands::Find(_cmd) => _cmd.run().await,
         Commands::Touch(_cmd) => _cmd.run().await,
         Commands::Url(_cmd) => _cmd.run().await,
 
         // blob commands
         Commands::Upload(_cmd) => _cmd.run().await,
         Commands::Cat(_cmd) => _cmd.run().await,
+        Commands::Get(_cmd) => _cmd.run().await,
+        Commands::Pull(_cmd) => _cmd.run().await,
+        Commands::Sync(_cmd) => _cmd.run().await,
+    }
+}
+
+#[tokio::main]
+pub async fn main() {
+    let cli = Cli::parse();
+    cmd::output::set_format(cli.output_format);
+    prompts::set_non_interactive(cli.non_interactive);
+    let profile = match &cli.commands {
+        Commands::Complete(cmd) => cmd.get_profile_arg().or(cli.profile),
+        _ => cli.profile,
     };
+    if let Some(profile) = profile {
+        config::set_profile_override(profile);
+    }
+
+    // `config` still runs with a missing profile, e.g. to add it
+    let profile_check = CONFIG.read().unwrap().check_profile();
+    let res = match (&cli.commands, profile_check) {
+        (Commands::Config(_), _) | (_, Ok(_)) => run(cli.commands).await,
+        (_, Err(err)) => Err(err),
+    };
+
+    if let Err(err) = res {
+        eprintln!("{}", format!("{err:?}").red());
+        process::exit(error::get_exit_code(&err));
+    }
 }
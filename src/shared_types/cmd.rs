@@ -1,7 +1,12 @@
+use std::sync::Arc;
+
 use chrono::{DateTime, Duration, Local, Utc};
 use clap::{Args, ValueEnum};
 
-use crate::utils::str2x;
+use crate::{
+    constants,
+    utils::{rate_limit::RateLimiter, str2x},
+};
 
 #[derive(Debug, Args)]
 #[group(multiple = false)]
@@ -24,12 +29,7 @@ impl CmdExpiryParams {
     pub fn get_expires_at(&self) -> DateTime<Utc> {
         match self.expires_at {
             Some(exp) => exp.to_owned().into(),
-            None => (Utc::now()
-                + match self.ttl {
-                    Some(ttl) => ttl.to_owned(),
-                    None => Duration::minutes(30),
-                })
-            .into(),
+            None => Utc::now() + self.ttl.unwrap_or(Duration::minutes(30)),
         }
     }
 }
@@ -39,3 +39,64 @@ pub enum CmdVisibility {
     Public,
     Private,
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CmdOutputFormat {
+    /// colored, human readable output
+    #[default]
+    Text,
+    /// a single (pretty printed) json document
+    Json,
+    /// one json document per line, lists are streamed item by item
+    Ndjson,
+}
+
+/// layouts `tree` can print, by default the one matching the global `--output` flag
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TreeFormat {
+    /// colored, indented tree
+    #[default]
+    Text,
+    /// nested json document, same as `--output json`
+    Json,
+    /// graphviz digraph, e.g. for `dot -Tsvg`
+    Dot,
+    /// nested markdown list
+    Markdown,
+}
+
+#[derive(Args)]
+pub struct CmdRateLimitParams {
+    #[arg(long, value_parser = str2x::str2bytes)]
+    /// cap the combined transfer speed of all files, in bytes per second (e.g. "500kb", "5mb")
+    pub limit_rate: Option<f32>,
+}
+
+impl CmdRateLimitParams {
+    /// one limiter is shared by all the transfers of a command
+    pub fn get_rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.limit_rate
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| Arc::new(RateLimiter::new(rate as f64)))
+    }
+}
+
+#[derive(Args)]
+pub struct CmdTransferParams {
+    #[arg(long, default_value_t = constants::MAX_CONCURRENT_TRANSFERS)]
+    /// max number of files transferred at once
+    pub jobs: usize,
+
+    #[command(flatten)]
+    pub rate_limit_params: CmdRateLimitParams,
+}
+
+impl CmdTransferParams {
+    pub fn get_jobs(&self) -> usize {
+        self.jobs.max(1)
+    }
+
+    pub fn get_rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.rate_limit_params.get_rate_limiter()
+    }
+}
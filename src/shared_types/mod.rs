@@ -12,10 +12,6 @@ pub use tokens::*;
 pub use uploads::*;
 pub use usage::*;
 
-pub trait CliSubCmd {
-    async fn run(&self);
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub message: String,
@@ -1,8 +1,10 @@
 use colored::*;
 use serde::{Deserialize, Serialize};
-use std::iter;
 
-use crate::utils::dirtree::PrintDirTreeOpts;
+use crate::utils::{
+    dirtree::{self, PrintDirTreeOpts},
+    x2str,
+};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct DirTree {
@@ -17,34 +19,50 @@ impl DirTree {
             return "".to_string();
         }
 
+        let padding = std::iter::repeat_n(" ", opts.indent).collect::<String>();
+        let count = opts
+            .file_counts
+            .map(|file_counts| *file_counts.get(&self.id).unwrap_or(&0_u32));
+        let size = opts.sizes.map(|sizes| *sizes.get(&self.id).unwrap_or(&0));
         let mut res = format!(
             "{}— {}/ {}\n",
-            iter::repeat(" ").take(opts.indent).collect::<String>(),
+            padding,
             if full_path == opts.cwd_dir_path {
                 self.name.bold().cyan().to_string()
             } else {
                 self.name.clone()
             },
-            if let Some(file_counts) = opts.file_counts {
-                let count = file_counts.get(&self.id).unwrap_or(&(0 as u32));
-                let file_count_str = format!("({})", count).bright_black();
-
-                file_count_str.to_string()
-            } else {
-                String::new()
+            match (count, size) {
+                (Some(count), Some(size)) => format!("({}, {})", count, x2str::bytes2str(size))
+                    .bright_black()
+                    .to_string(),
+                (Some(count), None) => format!("({})", count).bright_black().to_string(),
+                _ => String::new(),
             },
         );
+        let files = opts.files.and_then(|files| files.get(&self.id));
+        for file in files.into_iter().flatten() {
+            res.push_str(&format!(
+                "{}  · {} {}\n",
+                padding,
+                file.name,
+                x2str::bytes2str(file.file_size as u64).bright_black()
+            ));
+        }
         for child in &self.children {
             res.push_str(&Self::_print_dir_tree(
-                &child,
+                child,
                 &PrintDirTreeOpts {
                     file_counts: opts.file_counts,
+                    sizes: opts.sizes,
+                    files: opts.files,
                     level: opts.level - 1,
                     indent: opts.indent + 2,
                     print_note: opts.print_note,
                     cwd_dir_path: opts.cwd_dir_path,
+                    dir_path: opts.dir_path,
                 },
-                format!("{}/{}", full_path, child.name),
+                dirtree::join_paths(&[&full_path, &child.name]),
             ));
         }
 
@@ -52,13 +70,13 @@ impl DirTree {
     }
 
     pub fn print_dir_tree(&self, opts: &PrintDirTreeOpts) -> String {
-        let mut dirtree_str = self._print_dir_tree(opts, String::new());
+        let mut dirtree_str = self._print_dir_tree(opts, opts.dir_path.to_string());
         if opts.print_note {
-            let note = String::from("\nNOTE: file counts displayed as '(..)' do not represent cumulative file counts in subsequent children directories.");
+            let note = String::from("\nNOTE: file counts displayed as '(..)' do not include the files in nested directories, use `--cumulative` for that.");
             dirtree_str.push_str(note.bright_black().to_string().as_str());
         }
 
-        return dirtree_str;
+        dirtree_str
     }
 
     pub fn get_sub_tree(&self, dirpath: &str) -> Option<&Self> {
@@ -69,19 +87,32 @@ impl DirTree {
 
         let mut currentdir = self;
         for path_segment in dirpath.split("/") {
-            currentdir = match currentdir
+            currentdir = currentdir
                 .children
                 .iter()
-                .find(|item| item.name == path_segment)
-            {
-                Some(dir) => dir,
-                None => return None,
-            }
+                .find(|item| item.name == path_segment)?
         }
 
         Some(currentdir)
     }
 
+    fn _get_dir_paths(&self, dirpath: &str, dir_paths: &mut Vec<String>) {
+        for child in &self.children {
+            let child_dirpath = dirtree::join_paths(&[dirpath, &child.name]);
+            dir_paths.push(child_dirpath.clone());
+            child._get_dir_paths(&child_dirpath, dir_paths);
+        }
+    }
+
+    /// absolute paths of this directory and all of its nested directories, `dirpath` is the
+    /// absolute path of `self`
+    pub fn get_dir_paths(&self, dirpath: &str) -> Vec<String> {
+        let mut dir_paths = vec![dirpath.to_string()];
+        self._get_dir_paths(dirpath, &mut dir_paths);
+
+        dir_paths
+    }
+
     pub fn split<'a>(&self, dirpath: &'a str) -> Option<(&Self, Option<&'a str>)> {
         if let Some(subtree) = self.get_sub_tree(dirpath) {
             Some((subtree, None))
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Local};
-use serde::Deserialize;
+use orion::aead::streaming;
+use serde::{Deserialize, Serialize};
 
 use crate::{constants, shared_types, utils::files};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FsFile {
     pub name: String,
@@ -18,11 +19,34 @@ pub struct FsFile {
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
     pub deleted_at: Option<DateTime<Local>>,
+    /// hex sha256 of the plaintext contents
+    #[serde(default)]
+    pub checksum: Option<String>,
+    #[serde(default)]
+    pub compression: Option<shared_types::CompressionCodec>,
 }
 
 impl FsFile {
     pub fn get_filetype(&self) -> &'static str {
         let mimetype = constants::MIME_TYPES.get(files::get_file_ext(&self.name));
-        *mimetype.unwrap_or(&constants::UNKNOWN_MIME_TYPE)
+        mimetype.unwrap_or(&constants::UNKNOWN_MIME_TYPE)
+    }
+
+    /// size of the file's contents, `file_size` includes the auth tags of encrypted blocks. the
+    /// size of compressed contents is not known without decompressing them.
+    pub fn get_plain_file_size(&self) -> Option<u64> {
+        if self.compression.is_some() {
+            return None;
+        }
+        let file_size = self.file_size as u64;
+
+        match self.encryption.as_ref().filter(|e| e.attempt_decryption) {
+            Some(enc_metadata) => {
+                let sealed_block_size = enc_metadata.get_sealed_block_size() as u64;
+                let n_blocks = file_size.div_ceil(sealed_block_size);
+                Some(file_size.saturating_sub(n_blocks * streaming::ABYTES as u64))
+            }
+            None => Some(file_size),
+        }
     }
 }
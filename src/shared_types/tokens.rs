@@ -30,7 +30,7 @@ impl FromStr for PermissionChar {
             "r" => Ok(PermissionChar::Read),
             "u" => Ok(PermissionChar::Update),
             "d" => Ok(PermissionChar::Delete),
-            _ => return Err(anyhow!("Invalid permission character.")),
+            _ => Err(anyhow!("Invalid permission character.")),
         }
     }
 }
@@ -52,7 +52,7 @@ impl FromStr for AccessTokenPermission {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() < 1 || s.len() > 4 {
+        if s.is_empty() || s.len() > 4 {
             return Err(anyhow!("Invalid AccessControlPath permission length."));
         }
 
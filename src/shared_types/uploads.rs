@@ -1,6 +1,10 @@
 use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use orion::aead::streaming;
 use serde::{Deserialize, Serialize};
 
+use crate::constants;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct EncryptionMetadata {
@@ -22,9 +26,24 @@ impl EncryptionMetadata {
             block_size: None,
         }
     }
+
+    /// size of one encrypted block as it is stored remotely (`block_size` + auth tag)
+    pub fn get_sealed_block_size(&self) -> u32 {
+        self.block_size
+            .unwrap_or(constants::FILE_STREAM_READ_BUF_SIZE)
+            + streaming::ABYTES as u32
+    }
 }
 
-#[derive(Serialize, Debug, Clone)]
+/// contents are compressed with this codec before being encrypted
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionCodec {
+    Zstd,
+    Gzip,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UploadBlobMetadata {
     pub name: String,
@@ -35,4 +54,8 @@ pub struct UploadBlobMetadata {
     pub dir_path: String,
     pub force_write: bool,
     pub deleted_at: Option<DateTime<Utc>>,
+    /// hex sha256 of the plaintext contents, if it's known before the upload starts. streamed
+    /// uploads are hashed on the fly and get their checksum set once the stream is read
+    pub checksum: Option<String>,
+    pub compression: Option<CompressionCodec>,
 }
@@ -10,9 +10,11 @@ use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::{self, CONFIG},
     constants,
+    error::SfsError,
     shared_types::AccessToken,
-    utils::{local_auth::LocalAuthData, paths::get_absolute_path},
+    utils::local_auth::{EnvAuthData, LocalAuthData},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -52,9 +54,18 @@ impl Default for PersistentState {
     }
 }
 
+/// a token that can't be parsed is as good as none, `label` says where it came from
+fn parse_access_token(token: &str, label: &str) -> anyhow::Result<AccessToken> {
+    token
+        .parse()
+        .map_err(|err| SfsError::Auth(format!("access token '{label}' is invalid! {err}")).into())
+}
+
 impl PersistentState {
+    /// every profile has its own state
     pub fn get_state_filepath() -> io::Result<PathBuf> {
-        get_absolute_path("~/.sfs/state.json")
+        let profile = CONFIG.read().unwrap().get_profile().to_string();
+        Ok(config::get_profile_dirpath(&profile)?.join("state.json"))
     }
 
     pub fn get_wd(&self) -> &str {
@@ -73,6 +84,18 @@ impl PersistentState {
         })
     }
 
+    /// tag of the token in use, `SFS_TOKEN` if it comes from the environment
+    pub fn get_active_token_label(&self) -> &str {
+        if EnvAuthData::get().is_some() {
+            return constants::ENV_TOKEN;
+        }
+
+        match &self.active_token {
+            ActiveToken::RootAccessToken => constants::ROOT_ACCESS_TOKEN_TAG,
+            ActiveToken::Tag(tag) => tag,
+        }
+    }
+
     pub fn get_untitled_token_tag(&self) -> String {
         let mut counter = 0;
         for k in self.tokens.keys() {
@@ -81,34 +104,45 @@ impl PersistentState {
             }
         }
 
-        return format!("{}{}", constants::UNTITLED_TAG_PREFX, counter + 1);
+        format!("{}{}", constants::UNTITLED_TAG_PREFX, counter + 1)
     }
 
+    /// `SFS_TOKEN` always takes precedence over the selected token
     pub fn get_active_token(&self) -> anyhow::Result<Option<(String, AccessToken)>> {
+        if let Some(env_auth) = EnvAuthData::get() {
+            let access_token = parse_access_token(&env_auth.access_token, constants::ENV_TOKEN)?;
+            return Ok(Some((env_auth.access_token, access_token)));
+        }
+
         match &self.active_token {
             ActiveToken::RootAccessToken => {
-                let local_auth = match LocalAuthData::get() {
+                let local_auth = match LocalAuthData::get()? {
                     Some(auth_data) => auth_data,
                     None => return Ok(None),
                 };
 
-                let access_token: AccessToken = local_auth.access_token.parse()?;
+                let access_token =
+                    parse_access_token(&local_auth.access_token, constants::ROOT_ACCESS_TOKEN_TAG)?;
                 Ok(Some((local_auth.access_token, access_token)))
             }
             ActiveToken::Tag(tagname) => {
-                let token = self
-                    .tokens
-                    .get(tagname)
-                    .ok_or(anyhow!("token tag '{tagname}' not found!"))?;
+                let token = self.tokens.get(tagname).ok_or(SfsError::NotFound(format!(
+                    "token tag '{tagname}' not found!"
+                )))?;
 
-                let parsed_access_token: AccessToken = token.parse()?;
+                let parsed_access_token = parse_access_token(token, tagname)?;
 
                 Ok(Some((token.to_string(), parsed_access_token)))
             }
         }
     }
 
+    /// state is kept in memory only if credentials come from the environment
     pub fn load() -> anyhow::Result<Self> {
+        if EnvAuthData::get().is_some() {
+            return Ok(Self::default());
+        }
+
         let contents = match fs::read_to_string(Self::get_state_filepath()?) {
             Ok(contents) => contents,
             Err(err) if err.kind() == io::ErrorKind::NotFound => {
@@ -132,6 +166,10 @@ impl PersistentState {
     }
 
     pub fn save(&self) -> anyhow::Result<()> {
+        if EnvAuthData::get().is_some() {
+            return Ok(());
+        }
+
         let contents = serde_json::to_vec_pretty(self)?;
         fs::write(Self::get_state_filepath()?, contents)?;
 
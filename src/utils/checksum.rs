@@ -0,0 +1,117 @@
+use std::{
+    fs, io,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::x2str;
+
+/// running sha256 of a file's contents, clones share the same hasher so a reader moved into a
+/// stream can feed it while the caller keeps a handle to read the digest once it's done
+#[derive(Clone, Default)]
+pub struct Checksum(Arc<Mutex<Sha256>>);
+
+impl Checksum {
+    pub fn update(&self, data: &[u8]) {
+        self.0.lock().unwrap().update(data);
+    }
+
+    /// hex digest of everything fed so far
+    pub fn get_digest(&self) -> String {
+        x2str::bytes2hex(&self.0.lock().unwrap().clone().finalize())
+    }
+
+    /// files uploaded before checksums were recorded have nothing to verify against
+    pub fn verify(&self, expected: Option<&str>) -> anyhow::Result<()> {
+        let expected = match expected {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+
+        let digest = self.get_digest();
+        if digest != expected {
+            return Err(anyhow!(
+                "checksum mismatch! contents are corrupted.\nexpected: {expected}\nreceived: {digest}"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// hex sha256 of a local file's contents
+pub fn get_file_checksum<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+
+    Ok(x2str::bytes2hex(&hasher.finalize()))
+}
+
+/// hashes all bytes read through it
+pub struct ChecksumReader<R> {
+    inner: R,
+    checksum: Checksum,
+}
+
+impl<R> ChecksumReader<R> {
+    pub fn new(inner: R, checksum: Checksum) -> Self {
+        Self { inner, checksum }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ChecksumReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            self.checksum.update(&buf.filled()[filled..]);
+        }
+
+        poll
+    }
+}
+
+/// hashes all bytes written through it
+pub struct ChecksumWriter<W> {
+    inner: W,
+    checksum: Checksum,
+}
+
+impl<W> ChecksumWriter<W> {
+    pub fn new(inner: W, checksum: Checksum) -> Self {
+        Self { inner, checksum }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ChecksumWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            self.checksum.update(&buf[..n]);
+        }
+
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -1,9 +1,22 @@
+use std::env::var;
+
+use anyhow::anyhow;
 use orion::{
-    aead::streaming::{self, StreamSealer},
+    aead::streaming,
+    errors::UnknownCryptoError,
+    hazardous::{self, aead::streaming::StreamXChaCha20Poly1305},
     kdf,
 };
+use sha2::{Digest, Sha256};
+
+use crate::{
+    shared_types,
+    utils::{prompts, x2str},
+};
 
-use crate::shared_types;
+/// high level `streaming::StreamSealer` always generates a new nonce, the hazardous sealer it
+/// wraps can continue a stream from a known nonce (needed to resume uploads)
+pub type StreamSealer = StreamXChaCha20Poly1305;
 
 // takes in stream_sealer or stream_opener as `T`
 pub struct CryptoStream<T> {
@@ -29,20 +42,95 @@ impl CryptoStream<StreamSealer> {
 pub fn derive_key_from_password(
     pwd: &[u8],
     salt: &kdf::Salt,
-) -> Result<kdf::SecretKey, orion::errors::UnknownCryptoError> {
+) -> Result<kdf::SecretKey, UnknownCryptoError> {
     let pwd = kdf::Password::from_slice(pwd)?;
     kdf::derive_key(&pwd, salt, 3, 8, 32)
 }
 
-pub fn new_encryptor(
+fn new_sealer(
     password: &str,
-) -> Result<CryptoStream<streaming::StreamSealer>, orion::errors::UnknownCryptoError> {
-    let salt = kdf::Salt::default();
-    let (sealer, nonce) =
-        streaming::StreamSealer::new(&derive_key_from_password(password.as_bytes(), &salt)?)?;
-    Ok(CryptoStream {
-        e: sealer,
-        salt,
-        nonce,
-    })
+    salt: kdf::Salt,
+    nonce: streaming::Nonce,
+) -> Result<CryptoStream<StreamSealer>, UnknownCryptoError> {
+    let key = derive_key_from_password(password.as_bytes(), &salt)?;
+    let e = StreamSealer::new(
+        &hazardous::aead::streaming::SecretKey::from_slice(key.unprotected_as_bytes())?,
+        &nonce,
+    );
+
+    Ok(CryptoStream { e, salt, nonce })
+}
+
+pub fn new_encryptor(password: &str) -> Result<CryptoStream<StreamSealer>, UnknownCryptoError> {
+    new_sealer(password, kdf::Salt::default(), streaming::Nonce::generate())
+}
+
+fn parse_encryption_metadata(
+    enc_metadata: &shared_types::EncryptionMetadata,
+) -> anyhow::Result<(kdf::Salt, streaming::Nonce)> {
+    let salt = kdf::Salt::from_slice(
+        enc_metadata
+            .salt
+            .as_ref()
+            .ok_or(anyhow!("encryption metadata field missing: 'salt'"))?,
+    )
+    .map_err(|_| anyhow!("invalid encryption metadata! password salt is not valid."))?;
+    let nonce = streaming::Nonce::from_slice(
+        enc_metadata
+            .nonce
+            .as_ref()
+            .ok_or(anyhow!("encryption metadata field missing: 'nonce'"))?,
+    )
+    .map_err(|_| anyhow!("invalid encryption metadata! received invalid nonce."))?;
+
+    Ok((salt, nonce))
+}
+
+/// starts sealing from the beginning of the stream described by `enc_metadata`, the caller has
+/// to seal (and discard) the blocks that were already sent to continue from where it stopped
+pub fn restore_encryptor(
+    password: &str,
+    enc_metadata: &shared_types::EncryptionMetadata,
+) -> anyhow::Result<CryptoStream<StreamSealer>> {
+    let (salt, nonce) = parse_encryption_metadata(enc_metadata)?;
+
+    new_sealer(password, salt, nonce)
+        .map_err(|_| anyhow!("error occured while initializing encryptor!"))
+}
+
+/// lets a password be checked against the one a stream was sealed with, without storing the key
+pub fn get_key_digest(password: &str, salt: &kdf::Salt) -> anyhow::Result<String> {
+    let key = derive_key_from_password(password.as_bytes(), salt)
+        .map_err(|_| anyhow!("error occured while deriving key!"))?;
+
+    Ok(x2str::bytes2hex(&Sha256::digest(
+        key.unprotected_as_bytes(),
+    )))
+}
+
+pub fn new_decryptor(
+    password: &str,
+    enc_metadata: &shared_types::EncryptionMetadata,
+) -> anyhow::Result<CryptoStream<streaming::StreamOpener>> {
+    let (salt, nonce) = parse_encryption_metadata(enc_metadata)?;
+    let key = derive_key_from_password(password.as_bytes(), &salt)
+        .map_err(|_| anyhow!("error occured while deriving key!"))?;
+    let e = streaming::StreamOpener::new(&key, &nonce)
+        .map_err(|_| anyhow!("error occured while initializing decryption stream!"))?;
+
+    Ok(CryptoStream { e, salt, nonce })
+}
+
+/// reads shell variable "PASSWORD" or prompts for one
+pub fn get_decryption_password() -> anyhow::Result<String> {
+    match var("PASSWORD") {
+        Ok(pwd) => Ok(pwd),
+        Err(_) => {
+            prompts::ensure_interactive("a password", "set the PASSWORD shell variable instead")?;
+
+            Ok(dialoguer::Password::new()
+                .with_prompt("File is encrypted, please enter a password:")
+                .interact()?)
+        }
+    }
 }
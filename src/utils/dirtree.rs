@@ -1,11 +1,19 @@
 use std::collections::HashMap;
 
+use crate::shared_types::FsFile;
+
 pub struct PrintDirTreeOpts<'a> {
     pub file_counts: Option<&'a HashMap<String, u32>>,
+    /// combined size of the files in each directory, by directory id
+    pub sizes: Option<&'a HashMap<String, u64>>,
+    /// files listed under each directory, by directory id
+    pub files: Option<&'a HashMap<String, Vec<FsFile>>>,
     pub indent: usize,
     pub level: i16,
     pub print_note: bool,
     pub cwd_dir_path: &'a str,
+    /// absolute path of the printed directory, "" for the root
+    pub dir_path: &'a str,
 }
 
 impl PrintDirTreeOpts<'_> {
@@ -13,9 +21,12 @@ impl PrintDirTreeOpts<'_> {
         Self {
             indent: 2,
             file_counts: None,
+            sizes: None,
+            files: None,
             level: i16::MAX,
             print_note: false,
             cwd_dir_path: "",
+            dir_path: "",
         }
     }
 }
@@ -28,7 +39,7 @@ pub fn get_absolute_path(path: &str, wd: &str) -> String {
 
     let mut abs_path = wd
         .split('/')
-        .filter_map(|seg| if seg == "" { None } else { Some(seg) })
+        .filter(|seg| !seg.is_empty())
         .collect::<Vec<&str>>();
 
     for segment in path.split('/') {
@@ -41,7 +52,7 @@ pub fn get_absolute_path(path: &str, wd: &str) -> String {
         }
     }
 
-    return String::from("/") + &abs_path.join("/");
+    String::from("/") + &abs_path.join("/")
 }
 
 /// split path into (dirpath, filename)
@@ -64,7 +75,7 @@ pub fn join_paths(paths: &[&str]) -> String {
     let mut path_str = String::from(segs_iter.next().unwrap_or(&"/").trim_end_matches("/"));
     for seg in segs_iter {
         let seg_str = seg.trim().trim_matches('/');
-        if seg_str.len() > 0 {
+        if !seg_str.is_empty() {
             path_str += "/";
             path_str += seg_str;
         }
@@ -15,6 +15,6 @@ pub fn get_share_url(token: Option<&str>, storage_id: &str) -> anyhow::Result<Ur
     Ok(url)
 }
 
-pub fn get_file_ext<'a>(filename: &'a str) -> &'a str {
+pub fn get_file_ext(filename: &str) -> &str {
     filename.split(".").last().unwrap_or("bin")
 }
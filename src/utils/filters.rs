@@ -29,22 +29,22 @@ pub fn parse_filter_str(filter_col: FilterCol, filter_str: &str) -> anyhow::Resu
     if filter_str.contains("...") {
         let filter_str_split = filter_str.split("...").collect::<Vec<&str>>();
 
-        if let Some(filter_str_seg) = filter_str_split.get(0) {
-            if filter_str_seg.len() > 0 {
+        if let Some(filter_str_seg) = filter_str_split.first() {
+            if !filter_str_seg.is_empty() {
                 let val = filter_str2json(filter_col, filter_str_seg)?;
-                filters.push(Filter(filter_col.clone(), FilterOp::Gt, val));
+                filters.push(Filter(filter_col, FilterOp::Gt, val));
             }
         };
 
         if let Some(filter_str_seg) = filter_str_split.get(1) {
-            if filter_str_seg.len() > 0 {
+            if !filter_str_seg.is_empty() {
                 let val = filter_str2json(filter_col, filter_str_seg)?;
                 filters.push(Filter(filter_col, FilterOp::Lt, val));
             }
         };
     }
 
-    if filters.len() == 0 {
+    if filters.is_empty() {
         return Err(anyhow::anyhow!("invalid filter string provided!"));
     }
 
@@ -1,9 +1,9 @@
-use std::sync::Mutex;
+use std::{env, sync::Mutex};
 
-use anyhow;
+use anyhow::{self, Context};
 use serde::{Deserialize, Serialize};
 
-use crate::{constants, shared_types::ApiKey};
+use crate::{config::CONFIG, constants, shared_types::ApiKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalAuthData {
@@ -11,9 +11,23 @@ pub struct LocalAuthData {
     pub api_key: ApiKey,
 }
 
+/// every profile has its own keyring entry, the default profile's entry keeps its old name
+fn get_keyring_entry(profile: &str) -> keyring::Result<keyring::Entry> {
+    let user = match profile == constants::DEFAULT_PROFILE {
+        true => constants::ROOT_ACCESS_TOKEN_TAG.to_string(),
+        false => format!("{}@{}", constants::ROOT_ACCESS_TOKEN_TAG, profile),
+    };
+
+    keyring::Entry::new(env!("CARGO_PKG_NAME"), &user)
+}
+
+fn get_active_keyring_entry() -> keyring::Result<keyring::Entry> {
+    get_keyring_entry(CONFIG.read().unwrap().get_profile())
+}
+
 impl LocalAuthData {
     pub fn save(&self) -> anyhow::Result<()> {
-        let entry = keyring::Entry::new(env!("CARGO_PKG_NAME"), constants::ROOT_ACCESS_TOKEN_TAG)?;
+        let entry = get_active_keyring_entry()?;
         let enc = serde_json::to_string(self)?;
 
         entry.set_secret(enc.as_bytes())?;
@@ -22,39 +36,84 @@ impl LocalAuthData {
         Ok(())
     }
 
+    /// does nothing if the credentials come from the environment, see `EnvAuthData`
     pub fn load() -> anyhow::Result<()> {
-        let mut local_auth_data = LOCAL_AUTH_DATA.lock().unwrap();
-
-        let entry = keyring::Entry::new(env!("CARGO_PKG_NAME"), constants::ROOT_ACCESS_TOKEN_TAG)?;
-        let json_data = match entry.get_secret() {
-            Ok(data) => data,
-            Err(keyring::Error::NoEntry) => return Ok(()),
+        if EnvAuthData::get().is_some() {
+            return Ok(());
+        }
+
+        let entry = get_active_keyring_entry()?;
+        let auth_data = match entry.get_secret() {
+            Ok(json_data) => Some(serde_json::from_slice(&json_data)?),
+            Err(keyring::Error::NoEntry) => None,
             Err(err) => return Err(anyhow::Error::new(err)),
         };
 
-        let auth_data: LocalAuthData = serde_json::from_slice(&json_data)?;
-
-        *local_auth_data = Some(auth_data);
+        *LOCAL_AUTH_DATA.lock().unwrap() = Some(auth_data);
 
         Ok(())
     }
 
-    pub fn get() -> Option<Self> {
-        let local_auth_data = LOCAL_AUTH_DATA.lock().unwrap();
-
-        let auth_data = local_auth_data.as_ref();
+    /// the keyring is read the first time the login is needed, so saved tokens can be used on
+    /// systems without one
+    pub fn get() -> anyhow::Result<Option<Self>> {
+        if LOCAL_AUTH_DATA.lock().unwrap().is_none() {
+            Self::load().context("error occured while reading the login from the keyring!")?;
+        }
 
-        auth_data.cloned()
+        Ok(LOCAL_AUTH_DATA.lock().unwrap().clone().flatten())
     }
 
     pub fn delete() -> anyhow::Result<()> {
-        let entry = keyring::Entry::new(env!("CARGO_PKG_NAME"), constants::ROOT_ACCESS_TOKEN_TAG)?;
+        let entry = get_active_keyring_entry()?;
 
         entry.delete_credential()?;
         Self::load()?;
 
         Ok(())
     }
+
+    /// logs out of another profile, nothing to do if it was never logged into
+    pub fn delete_profile(profile: &str) -> anyhow::Result<()> {
+        if EnvAuthData::get().is_some() {
+            return Ok(());
+        }
+
+        match get_keyring_entry(profile)?.delete_credential() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(anyhow::Error::new(err)),
+        }
+    }
+}
+
+/// `None` until the keyring is read, see `LocalAuthData::get`
+static LOCAL_AUTH_DATA: Mutex<Option<Option<LocalAuthData>>> = Mutex::new(None);
+
+/// credentials set in `SFS_TOKEN` (and `SFS_API_KEY` & `SFS_API_SECRET`), these take the place of
+/// the keyring and the local state, nothing is read from or saved to either
+#[derive(Debug, Clone)]
+pub struct EnvAuthData {
+    pub access_token: String,
+    /// (key, secret)
+    pub api_key: Option<(String, String)>,
 }
 
-static LOCAL_AUTH_DATA: Mutex<Option<LocalAuthData>> = Mutex::new(None);
+impl EnvAuthData {
+    pub fn get() -> Option<Self> {
+        let access_token = env::var(constants::ENV_TOKEN)
+            .ok()
+            .filter(|token| !token.is_empty())?;
+        let api_key = match (
+            env::var(constants::ENV_API_KEY),
+            env::var(constants::ENV_API_SECRET),
+        ) {
+            (Ok(key), Ok(secret)) => Some((key, secret)),
+            _ => None,
+        };
+
+        Some(Self {
+            access_token,
+            api_key,
+        })
+    }
+}
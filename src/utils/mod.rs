@@ -1,3 +1,4 @@
+pub mod checksum;
 pub mod chirpy_logs;
 pub mod crypto;
 pub mod dirtree;
@@ -7,6 +8,8 @@ pub mod local_auth;
 pub mod misc;
 pub mod net;
 pub mod paths;
+pub mod prompts;
+pub mod rate_limit;
 pub mod str2x;
 pub mod streams;
 pub mod term;
@@ -1,4 +1,3 @@
-use colored::Colorize;
 use regex::Regex;
 use std::env::current_dir;
 use std::io;
@@ -11,7 +10,7 @@ use super::{term, x2str};
 
 pub fn expand_tilde<P: AsRef<Path>>(path: P) -> PathBuf {
     let p = path.as_ref();
-    if let Some(stripped) = p.strip_prefix("~").ok() {
+    if let Ok(stripped) = p.strip_prefix("~") {
         if let Some(home_dir) = dirs::home_dir() {
             return home_dir.join(stripped);
         }
@@ -47,7 +46,7 @@ pub fn canonicalize<P: AsRef<Path>>(relative_path: P) -> io::Result<PathBuf> {
 }
 
 pub fn get_absolute_path<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
-    Ok(absolute(canonicalize(expand_tilde(path))?)?)
+    absolute(canonicalize(expand_tilde(path))?)
 }
 
 pub fn get_paths_from_pattern(patt: &str) -> anyhow::Result<Vec<(u64, PathBuf)>> {
@@ -86,6 +85,26 @@ pub fn get_paths_from_pattern(patt: &str) -> anyhow::Result<Vec<(u64, PathBuf)>>
         None => return Ok(paths),
         Some(patt) => patt,
     };
+    let patt = get_pattern_regex_str(patt, MAIN_SEPARATOR_STR);
+
+    let patt_regex = Regex::new(patt.as_str())?;
+
+    for entry in WalkDir::new(&ref_wd) {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let path = entry.path();
+
+        if path.is_file() && patt_regex.is_match(path.to_str().unwrap_or("")) {
+            paths.push((metadata.len(), path.to_path_buf()));
+        }
+    }
+
+    Ok(paths)
+}
+
+/// regex source of a path pattern, anchored at both ends. `**` matches across directories, `*`
+/// within one and `{a|b}` (or `{a,b}`) either of the alternatives.
+fn get_pattern_regex_str(patt: &str, separator: &str) -> String {
     let patt = patt
         .replace('\\', r"\\")
         .replace('(', r"\(")
@@ -94,34 +113,46 @@ pub fn get_paths_from_pattern(patt: &str) -> anyhow::Result<Vec<(u64, PathBuf)>>
         .replace(']', r"\]")
         .replace('-', r"\-")
         .replace('.', r"\.")
+        .replace('+', r"\+")
+        .replace('?', r"\?")
+        .replace('^', r"\^")
+        .replace('$', r"\$")
         .replace("**", r".+")
-        .replace("*", format!(r"[^\{}]*", MAIN_SEPARATOR_STR).as_str());
+        .replace("*", format!(r"[^\{}]*", separator).as_str());
 
-    let re = Regex::new(r"\{([\w\.\|]+)\}").unwrap();
+    let re = Regex::new(r"\{([\w\\\.\|,]+)\}").unwrap();
     let patt = re
         .replace_all(patt.as_str(), |caps: &regex::Captures| {
-            let cap = &caps[1];
+            let cap = caps[1].replace(',', "|");
 
             format!("({cap})")
         })
         .to_string();
 
-    let patt = format!("^{}$", patt);
+    format!("^{}$", patt)
+}
 
-    println!("{}", format!("REGEX: {}", patt).dimmed());
-    let patt_regex = Regex::new(patt.as_str())?;
+/// matches remote paths, see `get_paths_from_pattern` for the pattern syntax
+pub fn get_remote_pattern_regex(patt: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&get_pattern_regex_str(patt, "/"))
+}
 
-    for entry in WalkDir::new(&ref_wd) {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        let path = entry.path();
+/// true if the path has wildcards or alternatives in it
+pub fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '{'])
+}
 
-        if path.is_file() && patt_regex.is_match(path.to_str().unwrap_or("")) {
-            paths.push((metadata.len(), path.to_path_buf()));
-        }
-    }
+/// the pattern up to its last "/" before the first wildcard, e.g. "/photos/" of "/photos/**"
+pub fn get_remote_pattern_prefix(patt: &str) -> &str {
+    let fixed_part = match patt.find(['*', '{']) {
+        Some(i) => &patt[..i],
+        None => patt,
+    };
 
-    Ok(paths)
+    match fixed_part.rfind('/') {
+        Some(i) => &fixed_part[..=i],
+        None => "/",
+    }
 }
 
 fn get_file_str(path_str: &str, filesize: Option<u64>) -> String {
@@ -150,7 +181,7 @@ where
             None => continue,
         };
 
-        max_col_size = max_col_size.max(get_file_str(&path_str, Some(filesize)).len());
+        max_col_size = max_col_size.max(get_file_str(path_str, Some(filesize)).len());
 
         ref_wd = match ref_wd {
             Some(ref_wd) => {
@@ -162,7 +193,7 @@ where
                     if ref_seg != path_seg {
                         break;
                     }
-                    if ref_seg == "" {
+                    if ref_seg.is_empty() {
                         continue;
                     };
 
@@ -0,0 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::SfsError;
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// set once from the global `--non-interactive` flag
+pub fn set_non_interactive(non_interactive: bool) {
+    NON_INTERACTIVE.store(non_interactive, Ordering::Relaxed);
+}
+
+pub fn is_non_interactive() -> bool {
+    NON_INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// to be called before every prompt, errors out instead of waiting for input that never comes
+/// (e.g. in CI). `hint` tells how to do without the prompt.
+pub fn ensure_interactive(prompt: &str, hint: &str) -> anyhow::Result<()> {
+    if is_non_interactive() {
+        return Err(SfsError::Usage(format!(
+            "cannot prompt for {prompt} in non-interactive mode, {hint}"
+        ))
+        .into());
+    }
+
+    Ok(())
+}
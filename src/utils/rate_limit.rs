@@ -0,0 +1,79 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_util::{Stream, StreamExt};
+use tokio::time;
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// token bucket shared by concurrent transfers, caps their combined speed. bursts are limited to
+/// one second worth of bytes.
+pub struct RateLimiter {
+    bytes_per_sec: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(bytes_per_sec: f64) -> Self {
+        Self {
+            bytes_per_sec,
+            bucket: Mutex::new(Bucket {
+                tokens: bytes_per_sec,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// waits until `n_bytes` can be transferred. bytes are taken out of the bucket right away,
+    /// callers queue up behind each other's debt.
+    pub async fn acquire(&self, n_bytes: usize) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+
+            let now = Instant::now();
+            let refill = (now - bucket.refilled_at).as_secs_f64() * self.bytes_per_sec;
+            bucket.tokens = (bucket.tokens + refill).min(self.bytes_per_sec);
+            bucket.refilled_at = now;
+
+            bucket.tokens -= n_bytes as f64;
+            match bucket.tokens < 0.0 {
+                true => Duration::from_secs_f64(-bucket.tokens / self.bytes_per_sec),
+                false => Duration::ZERO,
+            }
+        };
+
+        if !wait.is_zero() {
+            time::sleep(wait).await;
+        }
+    }
+}
+
+/// holds back every chunk of `stream` until the limiter lets it through
+pub fn limit_stream<S>(
+    stream: S,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Pin<Box<dyn Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static>>
+where
+    S: Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static,
+{
+    let rate_limiter = match rate_limiter {
+        Some(rate_limiter) => rate_limiter,
+        None => return Box::pin(stream),
+    };
+
+    Box::pin(stream.then(move |chunk| {
+        let rate_limiter = rate_limiter.clone();
+        async move {
+            if let Ok(chunk) = &chunk {
+                rate_limiter.acquire(chunk.len()).await;
+            }
+            chunk
+        }
+    }))
+}
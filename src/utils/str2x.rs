@@ -9,7 +9,7 @@ pub fn str2datetime(datetime_str: &str) -> anyhow::Result<DateTime<Local>> {
     match NaiveDateTime::parse_from_str(datetime_str, constants::LOCAL_DATETIME_FORMAT) {
         Ok(datetime) => match Local.from_local_datetime(&datetime).single() {
             Some(datetime) => Ok(datetime),
-            None => return Err(anyhow::anyhow!("error occured while parsing datetime!")),
+            None => Err(anyhow::anyhow!("error occured while parsing datetime!")),
         },
         Err(err) => Err(anyhow::anyhow!(err)),
     }
@@ -17,24 +17,16 @@ pub fn str2datetime(datetime_str: &str) -> anyhow::Result<DateTime<Local>> {
 
 pub fn str2bytes(b_str: &str) -> anyhow::Result<f32> {
     let mut n: f32 =
-        String::from_iter(
-            b_str
-                .chars()
-                .filter_map(|c| match c.is_digit(10) || c == '.' {
-                    true => Some(c),
-                    false => None,
-                }),
-        )
-        .parse()?;
+        String::from_iter(b_str.chars().filter(|c| c.is_ascii_digit() || *c == '.')).parse()?;
 
     if b_str.ends_with("kb") {
-        n = n * 10_f32.powi(3).powi(1);
+        n *= 10_f32.powi(3).powi(1);
     } else if b_str.ends_with("mb") {
-        n = n * 10_f32.powi(3).powi(2);
+        n *= 10_f32.powi(3).powi(2);
     } else if b_str.ends_with("gb") {
-        n = n * 10_f32.powi(3).powi(3);
+        n *= 10_f32.powi(3).powi(3);
     } else if b_str.ends_with("tb") {
-        n = n * 10_f32.powi(3).powi(4);
+        n *= 10_f32.powi(3).powi(4);
     }
 
     Ok(n)
@@ -126,7 +118,7 @@ pub fn str2at(token_str: &str) -> anyhow::Result<AccessToken> {
             segs.first()
                 .ok_or(anyhow!("Invalid segments in access token."))?,
         )?
-        .splitn(3, |c| *c == '\n' as u8)
+        .splitn(3, |c| *c == b'\n')
         .take(2)
         .map(|s| String::from_utf8(s.to_vec()))
     {
@@ -144,7 +136,7 @@ pub fn str2at(token_str: &str) -> anyhow::Result<AccessToken> {
 
     let expires_at: DateTime<Local> = DateTime::parse_from_rfc3339(
         contents
-            .get(0)
+            .first()
             .ok_or(anyhow!("Invalid content segments in access token."))?,
     )?
     .into();
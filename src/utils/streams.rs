@@ -1,15 +1,52 @@
 use std::pin::Pin;
 
-use chrono::{Duration, Local};
+use async_compression::tokio::{bufread, write};
 use futures_util::Stream;
 use orion::aead::streaming;
-use tokio::{io::AsyncReadExt, sync::mpsc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, BufReader},
+    sync::mpsc,
+};
+
+use crate::{shared_types::CompressionCodec, utils::crypto::StreamSealer};
+
+/// compresses everything read through it with `codec`, contents are passed on as they are
+/// without one. compression happens before `read_into_stream` seals the blocks.
+pub fn compress_reader<R>(
+    reader: R,
+    codec: Option<CompressionCodec>,
+) -> Pin<Box<dyn AsyncRead + Send>>
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    match codec {
+        Some(CompressionCodec::Zstd) => Box::pin(bufread::ZstdEncoder::new(BufReader::new(reader))),
+        Some(CompressionCodec::Gzip) => Box::pin(bufread::GzipEncoder::new(BufReader::new(reader))),
+        None => Box::pin(reader),
+    }
+}
+
+/// decompresses everything written through it with `codec`, needs a `shutdown` at the end to
+/// flush the last frame
+pub fn decompress_writer<W>(
+    writer: W,
+    codec: Option<CompressionCodec>,
+) -> Pin<Box<dyn AsyncWrite + Send>>
+where
+    W: AsyncWrite + Send + Unpin + 'static,
+{
+    match codec {
+        Some(CompressionCodec::Zstd) => Box::pin(write::ZstdDecoder::new(writer)),
+        Some(CompressionCodec::Gzip) => Box::pin(write::GzipDecoder::new(writer)),
+        None => Box::pin(writer),
+    }
+}
 
 /// DO NOT PROVIDE BAD `read_size`.
-pub fn read_into_stream<'a, R>(
+pub fn read_into_stream<R>(
     reader: R,
     read_chunk_size: u32,
-    mut sealer: Option<streaming::StreamSealer>,
+    mut sealer: Option<StreamSealer>,
     ticks_channel: Option<mpsc::UnboundedSender<usize>>,
 ) -> Pin<Box<impl Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static>>
 where
@@ -42,10 +79,14 @@ where
                 _ = c.send(b_read);
             }
             yield match &mut sealer {
-                Some(s) => s.seal_chunk(&buf, match is_last_chunk {
-                    true => &streaming::StreamTag::Finish,
-                    false => &streaming::StreamTag::Message
-                })?,
+                Some(s) => {
+                    let mut sealed_chunk = vec![0; buf.len() + streaming::ABYTES];
+                    s.seal_chunk(&buf, None, &mut sealed_chunk, match is_last_chunk {
+                        true => &streaming::StreamTag::Finish,
+                        false => &streaming::StreamTag::Message
+                    })?;
+                    sealed_chunk
+                }
                 None => buf.clone()
             };
         }
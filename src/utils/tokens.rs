@@ -1,5 +1,5 @@
 use crate::shared_types::AccessTokenPermission;
 
 pub fn get_acp(permission: AccessTokenPermission, path_pattern: &str) -> String {
-    format!("{}:{}", permission.to_string(), path_pattern)
+    format!("{}:{}", permission, path_pattern)
 }
@@ -4,7 +4,7 @@ const DIVS: [(u32, &str); 4] = [(24 * 60 * 60, "d"), (60 * 60, "h"), (60, "m"),
 pub fn duration2str(duration: Duration) -> String {
     let mut duration_str = String::new();
 
-    let mut secs = duration.num_seconds().abs() as u64;
+    let mut secs = duration.num_seconds().unsigned_abs();
     if secs == 0 {
         return String::from("0s");
     }
@@ -40,3 +40,7 @@ pub fn bytes2str(filesize: u64) -> String {
         _ => format!("{:.3}tb", size),
     }
 }
+
+pub fn bytes2hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
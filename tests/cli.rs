@@ -0,0 +1,815 @@
+//! runs the CLI binary against a dev server with its files in a temp directory, every test gets
+//! a fresh file system, HOME & working directory.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    process::{Child, Command, Output, Stdio},
+    time::{Duration, Instant},
+};
+
+use indicatif::ProgressBar;
+use serde_json::Value;
+use sfs_cli::{api, constants, dev_server::DevServer, SfsClient};
+use tempfile::TempDir;
+
+struct TestEnv {
+    server: DevServer,
+    _data_dir: TempDir,
+    home_dir: TempDir,
+    local_dir: TempDir,
+}
+
+impl TestEnv {
+    fn new() -> Self {
+        let data_dir = TempDir::new().unwrap();
+        let server = DevServer::start(data_dir.path(), 0).unwrap();
+
+        Self {
+            server,
+            _data_dir: data_dir,
+            home_dir: TempDir::new().unwrap(),
+            local_dir: TempDir::new().unwrap(),
+        }
+    }
+
+    fn get_command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_sfs-cli"));
+        cmd.env_clear()
+            .env("HOME", self.home_dir.path())
+            .env("NO_COLOR", "1")
+            .env("RUST_BACKTRACE", "0")
+            .current_dir(self.local_dir.path());
+
+        cmd
+    }
+
+    /// as the file system owner
+    fn sfs(&self, args: &[&str]) -> Output {
+        self.get_command()
+            .envs(self.server.get_env())
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// as someone who was only given `token`
+    fn sfs_with_token(&self, token: &str, args: &[&str]) -> Output {
+        self.get_command()
+            .env(constants::ENV_BASE_URL, &self.server.get_creds().base_url)
+            .env(constants::ENV_TOKEN, token)
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// as someone with `tokens` saved locally under their tags, with no login or credentials in
+    /// the environment
+    fn sfs_with_saved_tokens(
+        &self,
+        tokens: &[(&str, &str)],
+        active_tag: &str,
+        args: &[&str],
+    ) -> Output {
+        let state = serde_json::json!({
+            "tokens": tokens.iter().cloned().collect::<HashMap<&str, &str>>(),
+            "active_token": { "Tag": active_tag },
+            "working_directory": "/",
+        });
+        let state_dirpath = self.home_dir.path().join(".sfs");
+        fs::create_dir_all(&state_dirpath).unwrap();
+        fs::write(state_dirpath.join("state.json"), state.to_string()).unwrap();
+
+        self.get_command()
+            .env(constants::ENV_BASE_URL, &self.server.get_creds().base_url)
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    fn write_local_file(&self, name: &str, contents: &[u8]) {
+        fs::write(self.local_dir.path().join(name), contents).unwrap();
+    }
+
+    fn get_local_path(&self, name: &str) -> String {
+        self.local_dir
+            .path()
+            .join(name)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+fn assert_success(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "command failed with {:?}\nstdout: {}\nstderr: {}",
+        output.status.code(),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn assert_exit_code(output: &Output, code: i32) {
+    assert_eq!(
+        output.status.code(),
+        Some(code),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn get_file_names(ls_json: &str) -> Vec<String> {
+    let files: Value = serde_json::from_str(ls_json).unwrap();
+    let mut names: Vec<String> = files
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+
+    names
+}
+
+fn get_dir_names(tree: &Value) -> Vec<String> {
+    let mut names: Vec<String> = tree["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|dir| dir["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+
+    names
+}
+
+fn upload(env: &TestEnv, local_name: &str, dirpath: &str, name: &str) -> Output {
+    env.sfs(&[
+        "upload",
+        "--no-confirm",
+        &env.get_local_path(local_name),
+        "--dirpath",
+        dirpath,
+        "-n",
+        name,
+    ])
+}
+
+#[test]
+fn upload_ls_cat() {
+    let env = TestEnv::new();
+    env.write_local_file("hello.txt", b"hello, world!");
+
+    assert_success(&env.sfs(&["mkdir", "/docs"]));
+    assert_success(&upload(&env, "hello.txt", "/docs", "hello.txt"));
+
+    let ls = assert_success(&env.sfs(&["ls", "/docs", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["hello.txt"]);
+    let files: Value = serde_json::from_str(&ls).unwrap();
+    assert_eq!(
+        files[0]["checksum"],
+        "68e656b251e67e8358bef8483ab0d51c6619f3e7a1a9f0e75838d41ff368f728"
+    );
+
+    let cat = assert_success(&env.sfs(&["cat", "/docs/hello.txt"]));
+    assert_eq!(cat, "hello, world!");
+
+    assert_exit_code(&env.sfs(&["cat", "/docs/missing.txt"]), 5);
+    assert_exit_code(&env.sfs(&["url", "/docs/missing.txt"]), 5);
+}
+
+#[test]
+fn upload_existing_file() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"first");
+    env.write_local_file("b.txt", b"second");
+
+    assert_success(&upload(&env, "a.txt", "/", "a.txt"));
+    assert_exit_code(&upload(&env, "b.txt", "/", "a.txt"), 6);
+
+    assert_success(&env.sfs(&[
+        "upload",
+        "--no-confirm",
+        "--force",
+        &env.get_local_path("b.txt"),
+        "--dirpath",
+        "/",
+        "-n",
+        "a.txt",
+    ]));
+    let cat = assert_success(&env.sfs(&["cat", "/a.txt"]));
+    assert_eq!(cat, "second");
+}
+
+#[test]
+fn get_downloads_contents() {
+    let env = TestEnv::new();
+    let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    env.write_local_file("data.bin", &contents);
+
+    assert_success(&upload(&env, "data.bin", "/", "data.bin"));
+    assert_success(&env.sfs(&["get", "/data.bin", &env.get_local_path("downloaded.bin")]));
+
+    let downloaded = fs::read(Path::new(&env.get_local_path("downloaded.bin"))).unwrap();
+    assert_eq!(downloaded, contents);
+}
+
+#[test]
+fn mv_rm_trash() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+
+    assert_success(&upload(&env, "a.txt", "/", "a.txt"));
+    assert_success(&env.sfs(&["mv", "/a.txt", "/moved/b.txt"]));
+
+    let ls = assert_success(&env.sfs(&["ls", "/moved", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["b.txt"]);
+    let ls = assert_success(&env.sfs(&["ls", "/", "--output", "json"]));
+    assert!(get_file_names(&ls).is_empty());
+
+    assert_success(&env.sfs(&["rm", "b.txt", "--dirpath", "/moved"]));
+    let ls = assert_success(&env.sfs(&["ls", "/moved", "--output", "json"]));
+    assert!(get_file_names(&ls).is_empty());
+    let ls = assert_success(&env.sfs(&["ls", "/moved", "--trash", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["b.txt"]);
+}
+
+#[test]
+fn mvdir_rmdir_tree() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+
+    assert_success(&env.sfs(&["mkdir", "/src/nested"]));
+    assert_success(&upload(&env, "a.txt", "/src/nested", "a.txt"));
+    assert_success(&env.sfs(&["mvdir", "/src", "/dst"]));
+
+    let tree: Value =
+        serde_json::from_str(&assert_success(&env.sfs(&["tree", "--output", "json"]))).unwrap();
+    assert_eq!(get_dir_names(&tree), ["dst"]);
+    let cat = assert_success(&env.sfs(&["cat", "/dst/nested/a.txt"]));
+    assert_eq!(cat, "a");
+
+    assert_success(&env.sfs(&["rmdir", "/dst"]));
+    let tree: Value =
+        serde_json::from_str(&assert_success(&env.sfs(&["tree", "--output", "json"]))).unwrap();
+    assert!(get_dir_names(&tree).is_empty());
+    assert_exit_code(&env.sfs(&["rmdir", "/dst"]), 5);
+}
+
+#[test]
+fn access_token_acpl() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"public a");
+
+    assert_success(&env.sfs(&["mkdir", "/pub"]));
+    assert_success(&env.sfs(&["mkdir", "/private"]));
+    assert_success(&upload(&env, "a.txt", "/pub", "a.txt"));
+    assert_success(&upload(&env, "a.txt", "/private", "a.txt"));
+
+    let generated = assert_success(&env.sfs(&["tokens", "generate", "r:/pub/**"]));
+    let token = generated.lines().next().unwrap().trim();
+
+    let ls = assert_success(&env.sfs_with_token(token, &["ls", "/pub", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["a.txt"]);
+    let cat = assert_success(&env.sfs_with_token(token, &["cat", "/pub/a.txt"]));
+    assert_eq!(cat, "public a");
+
+    assert_exit_code(&env.sfs_with_token(token, &["mkdir", "/pub/new"]), 4);
+    // files outside of the ACPL aren't listed to the token user at all
+    assert_exit_code(&env.sfs_with_token(token, &["cat", "/private/a.txt"]), 5);
+
+    assert_success(&env.sfs(&["tokens", "blacklist", token]));
+    assert_exit_code(&env.sfs_with_token(token, &["ls", "/pub"]), 3);
+    assert_exit_code(&env.sfs_with_token("garbage", &["ls", "/pub"]), 3);
+}
+
+#[test]
+fn usage_as_owner() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+    assert_success(&upload(&env, "a.txt", "/", "a.txt"));
+
+    let usage: Value =
+        serde_json::from_str(&assert_success(&env.sfs(&["usage", "--output", "json"]))).unwrap();
+    assert!(usage.is_object());
+}
+
+#[test]
+fn encrypted_upload_cat() {
+    let env = TestEnv::new();
+    env.write_local_file("secret.txt", b"top secret");
+
+    let output = env
+        .get_command()
+        .envs(env.server.get_env())
+        .env("PASSWORD", "hunter2")
+        .args([
+            "upload",
+            "--no-confirm",
+            "--password",
+            &env.get_local_path("secret.txt"),
+            "--dirpath",
+            "/",
+            "-n",
+            "secret.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_success(&output);
+
+    let output = env
+        .get_command()
+        .envs(env.server.get_env())
+        .env("PASSWORD", "hunter2")
+        .args(["cat", "/secret.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(assert_success(&output), "top secret");
+
+    let output = env
+        .get_command()
+        .envs(env.server.get_env())
+        .env("PASSWORD", "wrong")
+        .args(["cat", "/secret.txt"])
+        .output()
+        .unwrap();
+    assert_exit_code(&output, 9);
+}
+
+#[test]
+fn cp_file_and_dir() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+
+    assert_success(&env.sfs(&["mkdir", "/src/nested/empty"]));
+    assert_success(&upload(&env, "a.txt", "/src/nested", "a.txt"));
+
+    assert_success(&env.sfs(&["cp", "/src/nested/a.txt", "/b.txt"]));
+    assert_eq!(assert_success(&env.sfs(&["cat", "/b.txt"])), "a");
+    assert_exit_code(&env.sfs(&["cp", "/src/nested/a.txt", "/b.txt"]), 6);
+
+    assert_exit_code(&env.sfs(&["cp", "/src", "/dst"]), 2);
+    assert_exit_code(&env.sfs(&["cp", "-r", "/src", "/src/nested"]), 2);
+    assert_success(&env.sfs(&["cp", "-r", "/src", "/dst"]));
+    assert_eq!(assert_success(&env.sfs(&["cat", "/dst/nested/a.txt"])), "a");
+    assert_eq!(assert_success(&env.sfs(&["cat", "/src/nested/a.txt"])), "a");
+
+    // copied into the existing directory
+    assert_success(&env.sfs(&["cp", "-r", "/src", "/dst"]));
+    let ls = assert_success(&env.sfs(&["ls", "/dst/src/nested", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["a.txt"]);
+    let tree: Value = serde_json::from_str(&assert_success(&env.sfs(&[
+        "tree",
+        "/dst/nested",
+        "--output",
+        "json",
+    ])))
+    .unwrap();
+    assert_eq!(get_dir_names(&tree), ["empty"]);
+}
+
+#[test]
+fn cp_with_saved_tokens() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+
+    assert_success(&env.sfs(&["mkdir", "/src/nested"]));
+    assert_success(&upload(&env, "a.txt", "/src/nested", "a.txt"));
+
+    let generate = |acpl: &str| {
+        let generated = assert_success(&env.sfs(&["tokens", "generate", acpl]));
+        generated.lines().next().unwrap().trim().to_string()
+    };
+    let (owner_token, reader_token) = (generate("crud:/**"), generate("r:/src/**"));
+    let tokens = [
+        ("owner", owner_token.as_str()),
+        ("reader", reader_token.as_str()),
+    ];
+    let sfs = |args: &[&str]| env.sfs_with_saved_tokens(&tokens, "owner", args);
+
+    // the active token's tag, or another token of the same file system, is still the same one
+    assert_exit_code(
+        &sfs(&["cp", "-r", "/src", "/src/nested", "--from", "owner"]),
+        2,
+    );
+    assert_exit_code(
+        &sfs(&["cp", "-r", "/src", "/src/nested", "--from", "reader"]),
+        2,
+    );
+
+    assert_success(&sfs(&[
+        "cp", "-r", "/src", "/dst", "--from", "reader", "--to", "owner",
+    ]));
+    assert_eq!(assert_success(&sfs(&["cat", "/dst/nested/a.txt"])), "a");
+    // the reader's token can't write
+    assert_exit_code(
+        &sfs(&["cp", "/dst/nested/a.txt", "/b.txt", "--to", "reader"]),
+        4,
+    );
+}
+
+/// `cp --from <tag>` copies with a client of the tagged token, built here to check the copy
+/// itself at the api level
+#[tokio::test]
+async fn cp_between_clients() {
+    let env = TestEnv::new();
+    env.write_local_file("secret.txt", b"top secret");
+
+    let output = env
+        .get_command()
+        .envs(env.server.get_env())
+        .env("PASSWORD", "hunter2")
+        .args([
+            "upload",
+            "--no-confirm",
+            "--password",
+            &env.get_local_path("secret.txt"),
+            "--dirpath",
+            "/shared",
+            "-n",
+            "secret.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_success(&output);
+
+    let generated = assert_success(&env.sfs(&["tokens", "generate", "r:/shared/**"]));
+    let token = generated.lines().next().unwrap().trim();
+    let creds = env.server.get_creds();
+    let src_client = SfsClient::new(&creds.base_url, Some(token.to_string())).unwrap();
+    let client = SfsClient::new(&creds.base_url, Some(creds.token.clone())).unwrap();
+
+    let file = src_client
+        .get_file("/shared/secret.txt")
+        .await
+        .unwrap()
+        .unwrap();
+    let copy = api::uploads::copy_file(
+        &src_client,
+        &file,
+        &client,
+        "/copy.txt",
+        false,
+        None,
+        ProgressBar::hidden(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(copy.file_size, file.file_size);
+    assert!(src_client.get_file("/copy.txt").await.unwrap().is_none());
+
+    // stays encrypted with the same password
+    let output = env
+        .get_command()
+        .envs(env.server.get_env())
+        .env("PASSWORD", "hunter2")
+        .args(["cat", "/copy.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(assert_success(&output), "top secret");
+}
+
+#[test]
+fn remote_patterns() {
+    let env = TestEnv::new();
+    env.write_local_file("a.o", b"a");
+    env.write_local_file("b.c", b"b");
+
+    assert_success(&upload(&env, "a.o", "/build", "a.o"));
+    assert_success(&upload(&env, "a.o", "/build/nested", "b.o"));
+    assert_success(&upload(&env, "b.c", "/build/nested", "b.c"));
+
+    // like in ACPs, "**/" spans at least one directory
+    let cat = assert_success(&env.sfs(&["cat", "/build/**/*.{o|c}"]));
+    assert_eq!(cat, "ba");
+
+    let urls: Value = serde_json::from_str(&assert_success(&env.sfs(&[
+        "url",
+        "/build/*.o",
+        "--output",
+        "json",
+    ])))
+    .unwrap();
+    assert_eq!(urls.as_array().unwrap().len(), 1);
+    assert_eq!(urls[0]["path"], "/build/a.o");
+
+    assert_success(&env.sfs(&[
+        "metadata",
+        "/build/**",
+        "--visibility",
+        "public",
+        "--no-confirm",
+    ]));
+    let ls = assert_success(&env.sfs(&["ls", "/build/nested", "--public", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["b.c", "b.o"]);
+
+    assert_success(&env.sfs(&["rm", "/build/**/*.o", "--no-confirm"]));
+    let ls = assert_success(&env.sfs(&["ls", "/build/nested", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["b.c"]);
+    assert_exit_code(&env.sfs(&["rm", "/build/**/*.o", "--no-confirm"]), 5);
+
+    assert_success(&env.sfs(&["mv", "/build/**/*.c", "/src", "--no-confirm"]));
+    let ls = assert_success(&env.sfs(&["ls", "/src", "--output", "json"]));
+    assert_eq!(get_file_names(&ls), ["b.c"]);
+}
+
+#[test]
+fn find_across_directories() {
+    let env = TestEnv::new();
+    env.write_local_file("doc.pdf", b"pdf");
+    env.write_local_file("notes.txt", b"notes");
+
+    assert_success(&upload(&env, "doc.pdf", "/", "top.pdf"));
+    assert_success(&upload(&env, "doc.pdf", "/work/2024", "report.pdf"));
+    assert_success(&upload(&env, "notes.txt", "/work", "notes.txt"));
+
+    let find_paths = |args: &[&str]| {
+        let output = assert_success(&env.sfs(&[&["find", "--output", "json"], args].concat()));
+        let results: Value = serde_json::from_str(&output).unwrap();
+        results
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["path"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        find_paths(&["/", "--type", ".pdf"]),
+        ["/top.pdf", "/work/2024/report.pdf"]
+    );
+    assert_eq!(
+        find_paths(&["/work", "--name", "%s.%"]),
+        ["/work/notes.txt"]
+    );
+    assert_eq!(
+        find_paths(&["/", "--max-depth", "2"]),
+        ["/top.pdf", "/work/notes.txt"]
+    );
+    assert_exit_code(&env.sfs(&["find", "/missing"]), 5);
+}
+
+#[test]
+fn du_cumulative_sizes() {
+    let env = TestEnv::new();
+    env.write_local_file("small.bin", &[0; 100]);
+    env.write_local_file("large.bin", &[0; 1000]);
+
+    assert_success(&upload(&env, "small.bin", "/a", "small.bin"));
+    assert_success(&upload(&env, "large.bin", "/a/b", "large.bin"));
+    // sorts between "/a" & "/a/b", without being nested in "/a"
+    assert_success(&upload(&env, "small.bin", "/a-b", "small.bin"));
+    assert_success(&upload(&env, "large.bin", "/a-b", "deleted.bin"));
+    assert_success(&env.sfs(&["rm", "deleted.bin", "--dirpath", "/a-b"]));
+
+    let du: Value = serde_json::from_str(&assert_success(&env.sfs(&[
+        "du",
+        "/",
+        "--max-depth",
+        "1",
+        "--trash",
+        "--output",
+        "json",
+    ])))
+    .unwrap();
+    let rows: Vec<(&str, u64, u64, u64)> = du
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["path"].as_str().unwrap(),
+                row["size"].as_u64().unwrap(),
+                row["fileCount"].as_u64().unwrap(),
+                row["trashSize"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("/", 1200, 3, 1000),
+            ("/a", 1100, 2, 0),
+            ("/a-b", 100, 1, 1000)
+        ]
+    );
+}
+
+#[test]
+fn tree_files_cumulative_and_formats() {
+    let env = TestEnv::new();
+    env.write_local_file("small.bin", &[0; 100]);
+    env.write_local_file("main.rs", &[0; 1000]);
+
+    assert_success(&upload(&env, "small.bin", "/a", "small.bin"));
+    assert_success(&upload(&env, "main.rs", "/a/b", "main.rs"));
+    assert_success(&upload(&env, "small.bin", "/c", "small.bin"));
+
+    let tree: Value = serde_json::from_str(&assert_success(&env.sfs(&[
+        "tree",
+        "/",
+        "--files",
+        "--cumulative",
+        "--output",
+        "json",
+    ])))
+    .unwrap();
+    assert_eq!(tree["fileCount"], 3);
+    assert_eq!(tree["size"], 1200);
+    let dir_a = &tree["children"][0];
+    assert_eq!(dir_a["path"], "/a");
+    assert_eq!(dir_a["fileCount"], 2);
+    assert_eq!(dir_a["files"][0]["name"], "small.bin");
+    assert_eq!(dir_a["children"][0]["files"][0]["name"], "main.rs");
+
+    let pruned: Value = serde_json::from_str(&assert_success(&env.sfs(&[
+        "tree",
+        "/",
+        "--pattern",
+        "**/*.rs",
+        "--output",
+        "json",
+    ])))
+    .unwrap();
+    assert_eq!(get_dir_names(&pruned), ["a"]);
+    assert_eq!(pruned["children"][0]["fileCount"], 0);
+    assert_eq!(pruned["children"][0]["children"][0]["fileCount"], 1);
+
+    let dot = assert_success(&env.sfs(&["tree", "/a", "--files", "--format", "dot"]));
+    assert!(dot.starts_with("digraph tree {"));
+    assert!(dot.contains("\"/a\" -> \"/a/b\";"));
+    assert!(dot.contains("\"/a/b\" -> \"/a/b/main.rs\";"));
+
+    let markdown = assert_success(&env.sfs(&["tree", "/c", "--format", "markdown"]));
+    assert_eq!(markdown, "- **`c/`** (1 file)\n");
+
+    env.write_local_file("a`b.txt", &[0; 10]);
+    assert_success(&upload(&env, "a`b.txt", "/d", "a`b.txt"));
+    let markdown = assert_success(&env.sfs(&["tree", "/d", "--files", "--format", "markdown"]));
+    assert_eq!(
+        markdown,
+        "- **`d/`** (1 file, 10.000b)\n  - `` a`b.txt `` (10.000b)\n"
+    );
+
+    // `--format` takes precedence over the global `--output` flag
+    let dot = assert_success(&env.sfs(&["tree", "--format", "dot", "--output", "json"]));
+    assert!(dot.starts_with("digraph tree {"));
+    let tree: Value = serde_json::from_str(&assert_success(
+        &env.sfs(&["tree", "/c", "--format", "json"]),
+    ))
+    .unwrap();
+    assert_eq!(tree["fileCount"], 1);
+}
+
+#[test]
+fn throttled_upload_outlasts_read_timeout() {
+    let env = TestEnv::new();
+    env.write_local_file("slow.bin", &[7; 60 * 1024]);
+    assert_success(&env.sfs(&["config", "set", "read_timeout_secs", "1"]));
+
+    let started_at = Instant::now();
+    assert_success(&env.sfs(&[
+        "upload",
+        "--no-confirm",
+        "slow.bin",
+        "--dirpath",
+        "/",
+        "--limit-rate",
+        "20kb",
+    ]));
+    assert!(started_at.elapsed() > Duration::from_millis(1500));
+
+    let downloaded = env.get_local_path("downloaded.bin");
+    assert_success(&env.sfs(&["get", "/slow.bin", &downloaded, "--limit-rate", "40kb"]));
+    assert_eq!(fs::read(downloaded).unwrap(), [7; 60 * 1024]);
+}
+
+/// kills the server once the test is done, even if it failed
+struct ChildGuard(Child);
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        _ = self.0.kill();
+    }
+}
+
+#[test]
+fn webdav_requires_local_host_and_credentials() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"webdav contents");
+    assert_success(&upload(&env, "a.txt", "/", "a.txt"));
+
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut server = ChildGuard(
+        env.get_command()
+            .envs(env.server.get_env())
+            .args(["webdav", "--port", &port.to_string()])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    );
+    // kept open until the end, the server's request logs would fail otherwise
+    let mut server_logs = BufReader::new(server.0.stdout.take().unwrap()).lines();
+    let password = server_logs
+        .by_ref()
+        .map(|line| line.unwrap())
+        .find_map(|line| {
+            line.split("password: ")
+                .nth(1)
+                .map(|rest| rest.split(' ').next().unwrap().to_string())
+        })
+        .unwrap();
+
+    let http = reqwest::blocking::Client::new();
+    let url = format!("http://127.0.0.1:{port}/a.txt");
+    let res = http.get(&url).send().unwrap();
+    assert_eq!(res.status(), 401);
+    let res = http
+        .get(&url)
+        .basic_auth("sfs", Some(&password))
+        .header("Host", "evil.example.com")
+        .send()
+        .unwrap();
+    assert_eq!(res.status(), 403);
+    let res = http
+        .get(&url)
+        .basic_auth("sfs", Some(&password))
+        .send()
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.text().unwrap(), "webdav contents");
+}
+
+#[test]
+fn piped_shell_exits_with_last_status() {
+    let env = TestEnv::new();
+    let run_shell = |commands: &str| {
+        let mut shell = env
+            .get_command()
+            .envs(env.server.get_env())
+            .arg("shell")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        shell
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(commands.as_bytes())
+            .unwrap();
+        shell.wait_with_output().unwrap()
+    };
+
+    assert_success(&run_shell("mkdir /a\ncd /a\npwd\n"));
+    assert_success(&run_shell("rmdir /missing\nmkdir /b\n"));
+    assert_exit_code(&run_shell("mkdir /c\nrmdir /missing\n"), 5);
+    assert_exit_code(&run_shell("rmdir /missing\nexit\n"), 5);
+}
+
+#[test]
+fn config_set_rejects_invalid_values() {
+    let env = TestEnv::new();
+
+    for (key, value) in [
+        ("read_timeout_secs", "0"),
+        ("connect_timeout_secs", "0"),
+        ("timeout_secs", "-5"),
+        ("max_retries", "many"),
+        ("base_url", "not a url"),
+    ] {
+        assert_exit_code(&env.sfs(&["config", "set", key, value]), 2);
+    }
+    assert_success(&env.sfs(&["ls", "/"]));
+}
+
+#[test]
+fn upload_json_output_is_clean() {
+    let env = TestEnv::new();
+    env.write_local_file("a.txt", b"a");
+    env.write_local_file("b.txt", b"b");
+
+    let output = assert_success(&env.sfs(&[
+        "upload",
+        "--no-confirm",
+        "*.txt",
+        "--dirpath",
+        "/",
+        "--output",
+        "json",
+    ]));
+    let uploaded: Value = serde_json::from_str(&output).unwrap();
+    assert!(uploaded["file"]["name"].as_str().unwrap().ends_with(".zip"));
+}
@@ -0,0 +1,52 @@
+//! local path patterns, as expanded for `upload`
+
+use std::{fs, path::Path};
+
+use sfs_cli::utils::paths;
+use tempfile::TempDir;
+
+fn get_matched_names(dir: &Path, patt: &str) -> Vec<String> {
+    let patt = dir.join(patt);
+    let mut names: Vec<String> = paths::get_paths_from_pattern(patt.to_str().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|(_, path)| {
+            path.strip_prefix(dir)
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+
+    names
+}
+
+#[test]
+fn paths_from_pattern() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    for name in [
+        "a.txt",
+        "a+b.txt",
+        "notes.md",
+        "main.rs",
+        "what?.txt",
+        "sub/deep.txt",
+    ] {
+        fs::write(dir.path().join(name), name).unwrap();
+    }
+
+    assert_eq!(
+        get_matched_names(dir.path(), "*.{txt,md}"),
+        ["a+b.txt", "a.txt", "notes.md", "what?.txt"]
+    );
+    assert_eq!(
+        get_matched_names(dir.path(), "*.{rs|md}"),
+        ["main.rs", "notes.md"]
+    );
+    // regex metacharacters in names are matched literally
+    assert_eq!(get_matched_names(dir.path(), "a+*.txt"), ["a+b.txt"]);
+    assert_eq!(get_matched_names(dir.path(), "what?*"), ["what?.txt"]);
+    assert_eq!(get_matched_names(dir.path(), "**/*.txt"), ["sub/deep.txt"]);
+}